@@ -0,0 +1,47 @@
+#[derive(thiserror::Error, Debug)]
+pub enum ExecutionError {
+    #[error("The mapping plan contains a cycle and cannot be executed")]
+    CyclicPlan,
+
+    #[error("The node {node_id} needs {expected} input(s) but got {actual}")]
+    WrongInputCount {
+        node_id:  String,
+        expected: usize,
+        actual:   usize,
+    },
+
+    #[error("The fragment {fragment} is not produced by the node {node_id}")]
+    MissingFragment { node_id: String, fragment: String },
+
+    #[error("The join node {0} needs a left and a right input")]
+    MissingJoinSide(String),
+
+    #[error("The source is missing the configuration key: {0}")]
+    MissingSourceConfig(String),
+
+    #[error(
+        "The reference formulation {0:?} is not supported by the executor"
+    )]
+    ReferenceFormulationNotSupported(operator::formats::ReferenceFormulation),
+
+    #[error("The IO type {0:?} is not supported by the executor")]
+    IOTypeNotSupported(operator::IOType),
+
+    #[error("The JSONPath expression {0} is not supported")]
+    InvalidJSONPath(String),
+
+    #[error(transparent)]
+    FunctionError(#[from] operator::error::FunctionError),
+
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    CsvError(#[from] csv::Error),
+
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("Something else happened: {0:?}")]
+    GenericError(String),
+}
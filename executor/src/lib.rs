@@ -0,0 +1,588 @@
+pub mod error;
+mod operators;
+mod source;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use error::ExecutionError;
+use operator::fno::FunctionRegistry;
+use operator::tuples::{MappingTuple, SolutionSequence};
+use operator::{IOType, Operator, Target};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use plangenerator::plan::{DiGraphOperators, Plan};
+use plangenerator::validation::has_join_sides;
+
+pub type ExecutionResult<T> = Result<T, ExecutionError>;
+
+/// The data produced by a node of the mapping plan.
+/// Relational operators produce mapping tuples, keyed by the fragments of the
+/// outgoing edges, while serializers produce the serialized lines.
+#[derive(Debug, Clone)]
+enum NodeOutput {
+    Tuples(MappingTuple),
+    Serialized(Vec<String>),
+}
+
+/// The serialized output gathered by a target operator of the plan.
+#[derive(Debug, Clone)]
+pub struct SinkOutput {
+    pub node_id: String,
+    pub target:  Target,
+    pub lines:   Vec<String>,
+}
+
+/// Reference in-memory executor for mapping plans.
+///
+/// The executor walks the plan graph in topological order and runs each
+/// operator on local files. It aims for clarity over performance and is
+/// intended to check the output of translated mapping plans.
+///
+/// FnO functions are called through the built-in [FunctionRegistry] unless
+/// another registry is given with [Executor::with_registry].
+#[derive(Debug, Clone)]
+pub struct Executor {
+    pub base_path: PathBuf,
+    pub registry:  FunctionRegistry,
+}
+
+impl Executor {
+    pub fn new(base_path: PathBuf) -> Executor {
+        Executor {
+            base_path,
+            registry: FunctionRegistry::with_builtins(),
+        }
+    }
+
+    pub fn with_registry(mut self, registry: FunctionRegistry) -> Executor {
+        self.registry = registry;
+        self
+    }
+
+    pub fn execute_plan<T>(
+        &self,
+        plan: &Plan<T>,
+    ) -> ExecutionResult<Vec<SinkOutput>> {
+        let graph = plan.graph.borrow();
+        self.execute(&graph)
+    }
+
+    pub fn execute(
+        &self,
+        graph: &DiGraphOperators,
+    ) -> ExecutionResult<Vec<SinkOutput>> {
+        let ordered_nodes = petgraph::algo::toposort(graph, None)
+            .map_err(|_| ExecutionError::CyclicPlan)?;
+
+        let mut outputs: HashMap<NodeIndex, NodeOutput> = HashMap::new();
+        let mut sink_outputs = Vec::new();
+
+        for node_idx in ordered_nodes {
+            let plan_node = &graph[node_idx];
+            let operator = &plan_node.operator;
+            let inputs = collect_inputs(graph, &outputs, node_idx)?;
+
+            let check_input_count = |expected: usize| {
+                if inputs.len() != expected {
+                    return Err(ExecutionError::WrongInputCount {
+                        node_id: plan_node.id.clone(),
+                        expected,
+                        actual: inputs.len(),
+                    });
+                }
+                Ok(())
+            };
+
+            let output = match operator {
+                Operator::SourceOp { config } => {
+                    check_input_count(0)?;
+                    let sequence =
+                        source::read_source(config, &self.base_path)?;
+                    fan_out(graph, node_idx, sequence)
+                }
+                Operator::ProjectOp { config } => {
+                    check_input_count(1)?;
+                    let sequence =
+                        operators::project(tuples_input(&inputs[0])?, config);
+                    fan_out(graph, node_idx, sequence)
+                }
+                Operator::ExtendOp { config } => {
+                    check_input_count(1)?;
+                    let sequence = operators::extend(
+                        tuples_input(&inputs[0])?,
+                        config,
+                        &self.registry,
+                    )?;
+                    fan_out(graph, node_idx, sequence)
+                }
+                Operator::FilterOp { config } => {
+                    check_input_count(1)?;
+                    let sequence = operators::filter(
+                        tuples_input(&inputs[0])?,
+                        config,
+                        &self.registry,
+                    )?;
+                    fan_out(graph, node_idx, sequence)
+                }
+                Operator::AggregateOp { config } => {
+                    check_input_count(1)?;
+                    let sequence = operators::aggregate(
+                        &tuples_input(&inputs[0])?,
+                        config,
+                    );
+                    fan_out(graph, node_idx, sequence)
+                }
+                Operator::UnnestOp { config } => {
+                    check_input_count(1)?;
+                    let sequence =
+                        operators::unnest(tuples_input(&inputs[0])?, config);
+                    fan_out(graph, node_idx, sequence)
+                }
+                Operator::DistinctOp { config } => {
+                    check_input_count(1)?;
+                    let sequence =
+                        operators::distinct(tuples_input(&inputs[0])?, config);
+                    fan_out(graph, node_idx, sequence)
+                }
+                Operator::SortOp { config } => {
+                    check_input_count(1)?;
+                    let sequence =
+                        operators::sort(tuples_input(&inputs[0])?, config);
+                    fan_out(graph, node_idx, sequence)
+                }
+                Operator::RenameOp { config } => {
+                    check_input_count(1)?;
+                    let sequence =
+                        operators::rename(tuples_input(&inputs[0])?, config);
+                    fan_out(graph, node_idx, sequence)
+                }
+                Operator::JoinOp { config } => {
+                    check_input_count(2)?;
+                    if !has_join_sides(graph, node_idx) {
+                        return Err(ExecutionError::MissingJoinSide(
+                            plan_node.id.clone(),
+                        ));
+                    }
+                    let sequence = operators::join(
+                        tuples_input(&inputs[0])?,
+                        tuples_input(&inputs[1])?,
+                        config,
+                    );
+                    fan_out(graph, node_idx, sequence)
+                }
+                Operator::UnionOp { .. } => {
+                    if inputs.len() < 2 {
+                        return Err(ExecutionError::WrongInputCount {
+                            node_id:  plan_node.id.clone(),
+                            expected: 2,
+                            actual:   inputs.len(),
+                        });
+                    }
+                    let mut sequence = Vec::new();
+                    for input in &inputs {
+                        sequence.extend(tuples_input(input)?);
+                    }
+                    fan_out(graph, node_idx, sequence)
+                }
+                Operator::FragmentOp { config } => {
+                    check_input_count(1)?;
+                    let sequence = tuples_input(&inputs[0])?;
+                    NodeOutput::Tuples(
+                        config
+                            .to
+                            .iter()
+                            .map(|frag| (frag.clone(), sequence.clone()))
+                            .collect(),
+                    )
+                }
+                Operator::SerializerOp { config } => {
+                    check_input_count(1)?;
+                    let sequence = tuples_input(&inputs[0])?;
+                    NodeOutput::Serialized(operators::serialize(
+                        &sequence, config,
+                    ))
+                }
+                Operator::TargetOp { config } => {
+                    check_input_count(1)?;
+                    let lines = match &inputs[0] {
+                        NodeOutput::Serialized(lines) => lines.clone(),
+                        NodeOutput::Tuples(_) => {
+                            return Err(ExecutionError::GenericError(format!(
+                                "Target operator {} needs serialized input",
+                                plan_node.id
+                            )))
+                        }
+                    };
+                    sink_outputs.push(SinkOutput {
+                        node_id: plan_node.id.clone(),
+                        target: config.clone(),
+                        lines,
+                    });
+                    NodeOutput::Serialized(vec![])
+                }
+            };
+
+            outputs.insert(node_idx, output);
+        }
+
+        Ok(sink_outputs)
+    }
+
+    /// Executes the plan and writes the serialized output of each target.
+    /// File targets are written relative to the base path, targets
+    /// writing to the same file are merged.
+    pub fn run<T>(
+        &self,
+        plan: &Plan<T>,
+        stdout: &mut dyn Write,
+    ) -> ExecutionResult<()> {
+        let sink_outputs = self.execute_plan(plan)?;
+
+        let mut file_outputs: Vec<(PathBuf, Vec<String>)> = Vec::new();
+        for sink_output in sink_outputs {
+            match sink_output.target.target_type {
+                IOType::StdOut => {
+                    for line in sink_output.lines {
+                        writeln!(stdout, "{}", line)?;
+                    }
+                }
+                IOType::File => {
+                    let path_str =
+                        sink_output.target.configuration.get("path").ok_or(
+                            ExecutionError::GenericError(format!(
+                                "File target {} needs a path",
+                                sink_output.node_id
+                            )),
+                        )?;
+                    let path = self.base_path.join(path_str);
+
+                    match file_outputs.iter_mut().find(|(p, _)| *p == path) {
+                        Some((_, lines)) => lines.extend(sink_output.lines),
+                        None => file_outputs.push((path, sink_output.lines)),
+                    }
+                }
+                other => return Err(ExecutionError::IOTypeNotSupported(other)),
+            }
+        }
+
+        for (path, lines) in file_outputs {
+            let mut writer = BufWriter::new(File::create(path)?);
+            for line in lines {
+                writeln!(writer, "{}", line)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn collect_inputs(
+    graph: &DiGraphOperators,
+    outputs: &HashMap<NodeIndex, NodeOutput>,
+    node_idx: NodeIndex,
+) -> ExecutionResult<Vec<NodeOutput>> {
+    // The left input of a join comes before the right input, the inputs of
+    // the other operators are ordered by insertion
+    let mut edges: Vec<_> = graph
+        .edges_directed(node_idx, Direction::Incoming)
+        .collect();
+    edges.sort_by_key(|edge| (edge.weight().join_side, edge.id()));
+
+    edges
+        .into_iter()
+        .map(|edge| {
+            let fragment = &edge.weight().fragment;
+            let source_node = &graph[edge.source()];
+            match outputs.get(&edge.source()) {
+                Some(NodeOutput::Tuples(mapping_tuple)) => {
+                    let sequence = mapping_tuple.get(fragment).ok_or(
+                        ExecutionError::MissingFragment {
+                            node_id:  source_node.id.clone(),
+                            fragment: fragment.clone(),
+                        },
+                    )?;
+                    let mut mapping_tuple = MappingTuple::new();
+                    mapping_tuple.insert(fragment.clone(), sequence.clone());
+                    Ok(NodeOutput::Tuples(mapping_tuple))
+                }
+                Some(serialized) => Ok(serialized.clone()),
+                None => {
+                    Err(ExecutionError::GenericError(format!(
+                        "Node {} was not executed before its successor",
+                        source_node.id
+                    )))
+                }
+            }
+        })
+        .collect()
+}
+
+/// Sends the solution sequence produced by a relational operator along
+/// every outgoing edge of the node.
+fn fan_out(
+    graph: &DiGraphOperators,
+    node_idx: NodeIndex,
+    sequence: SolutionSequence,
+) -> NodeOutput {
+    NodeOutput::Tuples(
+        graph
+            .edges_directed(node_idx, Direction::Outgoing)
+            .map(|edge| (edge.weight().fragment.clone(), sequence.clone()))
+            .collect(),
+    )
+}
+
+fn tuples_input(input: &NodeOutput) -> ExecutionResult<SolutionSequence> {
+    match input {
+        NodeOutput::Tuples(mapping_tuple) => {
+            Ok(mapping_tuple.values().flatten().cloned().collect())
+        }
+        NodeOutput::Serialized(_) => {
+            Err(ExecutionError::GenericError(
+                "Relational operators cannot process serialized output"
+                    .to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use operator::formats::DataFormat;
+    use operator::template::parse_template;
+    use operator::{
+        Extend, Function, Iterator, Projection, Serializer, Source,
+    };
+    use plangenerator::plan::{join, union, Init};
+
+    use super::*;
+
+    fn csv_source(path: &str) -> Source {
+        Source {
+            config:        HashMap::from([(
+                "path".to_string(),
+                path.to_string(),
+            )]),
+            source_type:   IOType::File,
+            root_iterator: Iterator::default(),
+        }
+    }
+
+    fn iri_template(template: &str) -> Function {
+        Function::Iri {
+            inner_function: Arc::new(Function::UriEncode {
+                inner_function: Arc::new(Function::TemplateString {
+                    value: template.to_string(),
+                }),
+            }),
+            base_iri:       None,
+        }
+    }
+
+    fn stdout_target() -> Target {
+        Target {
+            configuration: HashMap::new(),
+            target_type:   IOType::StdOut,
+            data_format:   DataFormat::NQuads,
+        }
+    }
+
+    fn test_executor() -> Executor {
+        Executor::new(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("resources/tests"),
+        )
+    }
+
+    #[test]
+    fn test_execute_linear_plan() -> Result<(), Box<dyn std::error::Error>> {
+        let mut plan = Plan::new();
+        let projection = Operator::ProjectOp {
+            config: Projection {
+                projection_attributes: HashSet::from([
+                    "ID".to_string(),
+                    "Name".to_string(),
+                ]),
+            },
+        };
+        let extend = Operator::ExtendOp {
+            config: Extend {
+                extend_pairs: HashMap::from([
+                    (
+                        "?s".to_string(),
+                        iri_template("http://example.com/student/{ID}"),
+                    ),
+                    (
+                        "?o".to_string(),
+                        Function::Literal {
+                            inner_function:    Arc::new(Function::Reference {
+                                value: "Name".to_string(),
+                            }),
+                            dtype_function:    None,
+                            langtype_function: None,
+                        },
+                    ),
+                ]),
+            },
+        };
+        let serializer = Serializer {
+            template: parse_template(
+                "?s <http://xmlns.com/foaf/0.1/name> ?o .",
+            )
+            .unwrap(),
+            options:  None,
+            format:   DataFormat::NQuads,
+        };
+
+        plan.source(csv_source("student.csv"))
+            .apply(&projection, "Projection")?
+            .apply(&extend, "Extend")?
+            .serialize(serializer)?
+            .sink(&stdout_target())?;
+
+        let outputs = test_executor().execute_plan(&plan)?;
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(
+            outputs[0].lines,
+            vec![
+                "<http://example.com/student/10> <http://xmlns.com/foaf/0.1/name> \"Venus Williams\" .",
+                "<http://example.com/student/20> <http://xmlns.com/foaf/0.1/name> \"Demi Moore\" .",
+            ]
+        );
+        Ok(())
+    }
+
+    fn join_plan() -> Result<Plan<Init>, Box<dyn std::error::Error>> {
+        let mut plan = Plan::new();
+        let student_plan = Rc::new(std::cell::RefCell::new(
+            plan.source(csv_source("student.csv")),
+        ));
+        let sport_plan = Rc::new(std::cell::RefCell::new(
+            plan.source(csv_source("sport.csv")),
+        ));
+
+        let extend = Operator::ExtendOp {
+            config: Extend {
+                extend_pairs: HashMap::from([
+                    (
+                        "?s".to_string(),
+                        iri_template("http://example.com/student/{ID}"),
+                    ),
+                    (
+                        "?o".to_string(),
+                        iri_template("http://example.com/sport/{join_1_ID}"),
+                    ),
+                ]),
+            },
+        };
+        let serializer = Serializer {
+            template: parse_template("?s <http://example.com/practises> ?o .")
+                .unwrap(),
+            options:  None,
+            format:   DataFormat::NQuads,
+        };
+
+        join(student_plan, sport_plan)?
+            .alias("join_1")?
+            .where_by(vec!["Sport"])?
+            .compared_to(vec!["ID"])?
+            .apply(&extend, "Extend")?
+            .serialize(serializer)?
+            .sink(&stdout_target())?;
+        Ok(plan)
+    }
+
+    #[test]
+    fn test_execute_join_plan() -> Result<(), Box<dyn std::error::Error>> {
+        let outputs = test_executor().execute_plan(&join_plan()?)?;
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(
+            outputs[0].lines,
+            vec!["<http://example.com/student/10> <http://example.com/practises> <http://example.com/sport/100> ."]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_inputs_follow_join_sides(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let plan = join_plan()?;
+        let expected = test_executor().execute_plan(&plan)?;
+
+        // Adding the edges in reverse order swaps the edge indices of the
+        // left and right join inputs
+        let (nodes, edges) = plan.graph.borrow().clone().into_nodes_edges();
+        let mut graph = DiGraphOperators::new();
+        for node in nodes {
+            graph.add_node(node.weight);
+        }
+        for edge in edges.iter().rev() {
+            graph.add_edge(edge.source(), edge.target(), edge.weight.clone());
+        }
+        let outputs = test_executor().execute(&graph)?;
+        assert_eq!(outputs[0].lines, expected[0].lines);
+
+        graph
+            .edge_weights_mut()
+            .for_each(|edge| edge.join_side = None);
+        assert!(matches!(
+            test_executor().execute(&graph),
+            Err(ExecutionError::MissingJoinSide(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_union_plan() -> Result<(), Box<dyn std::error::Error>> {
+        let mut plan = Plan::new();
+        let student_plan = Rc::new(std::cell::RefCell::new(
+            plan.source(csv_source("student.csv")),
+        ));
+        let sport_plan = Rc::new(std::cell::RefCell::new(
+            plan.source(csv_source("sport.csv")),
+        ));
+
+        let extend = Operator::ExtendOp {
+            config: Extend {
+                extend_pairs: HashMap::from([(
+                    "?s".to_string(),
+                    iri_template("http://example.com/{Name}"),
+                )]),
+            },
+        };
+        let serializer = Serializer {
+            template: parse_template(
+                "?s <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.com/Thing> .",
+            )
+            .unwrap(),
+            options:  None,
+            format:   DataFormat::NQuads,
+        };
+
+        union(vec![student_plan, sport_plan])?
+            .alias("union_1")?
+            .apply(&extend, "Extend")?
+            .serialize(serializer)?
+            .sink(&stdout_target())?;
+
+        let outputs = test_executor().execute_plan(&plan)?;
+        let mut lines = outputs[0].lines.clone();
+        lines.sort();
+        assert_eq!(
+            lines,
+            vec![
+                "<http://example.com/Demi%20Moore> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.com/Thing> .",
+                "<http://example.com/Tennis> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.com/Thing> .",
+                "<http://example.com/Venus%20Williams> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.com/Thing> .",
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,756 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use log::warn;
+use operator::error::FunctionError;
+use operator::fno::FunctionRegistry;
+use operator::template::QuadPattern;
+use operator::tuples::{SolutionMapping, SolutionSequence};
+use operator::value::Value;
+use operator::{
+    Aggregate, Distinct, Extend, Filter, Join, JoinCondition, JoinType,
+    Projection, Rename, Serializer, Sort, Unnest,
+};
+
+use crate::error::ExecutionError;
+
+pub fn project(
+    input: SolutionSequence,
+    projection: &Projection,
+) -> SolutionSequence {
+    input
+        .into_iter()
+        .map(|mapping| {
+            mapping
+                .into_iter()
+                .filter(|(attr, _)| {
+                    projection.projection_attributes.contains(attr)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Extends every solution mapping with the values of the extend functions.
+/// Attributes whose function refers to a missing or null value are left out
+/// of the solution mapping, so no terms are generated for them. Invalid RDF
+/// terms (e.g. an invalid IRI or language tag) are data errors which are
+/// reported and left out as well. Any other evaluation error aborts the
+/// execution.
+pub fn extend(
+    input: SolutionSequence,
+    extend: &Extend,
+    registry: &FunctionRegistry,
+) -> Result<SolutionSequence, ExecutionError> {
+    let mut result = Vec::with_capacity(input.len());
+    for mut mapping in input {
+        let mut extended = Vec::new();
+        for (attr, func) in &extend.extend_pairs {
+            match func.evaluate_with(&mapping, registry) {
+                Ok(value) => extended.push((attr.clone(), value)),
+                Err(FunctionError::MissingAttribute(_))
+                | Err(FunctionError::NullAttribute(_)) => {}
+                Err(
+                    err @ (FunctionError::InvalidIri(_)
+                    | FunctionError::InvalidLanguageTag(_)
+                    | FunctionError::InvalidBlankNode(_)
+                    | FunctionError::IllTypedLiteral { .. }),
+                ) => {
+                    warn!("Leaving out the term of {}: {}", attr, err);
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        mapping.extend(extended);
+        result.push(mapping);
+    }
+    Ok(result)
+}
+
+/// Keeps the solution mappings which satisfy the filter predicate.
+pub fn filter(
+    input: SolutionSequence,
+    filter: &Filter,
+    registry: &FunctionRegistry,
+) -> Result<SolutionSequence, ExecutionError> {
+    let predicate = filter.predicate.compile()?;
+    let mut result = Vec::new();
+    for mapping in input {
+        if predicate.evaluate_with(&mapping, registry)? {
+            result.push(mapping);
+        }
+    }
+    Ok(result)
+}
+
+/// The values of the group-by attributes of a solution mapping.
+type GroupKey = Vec<Option<Value>>;
+
+/// Groups the solution mappings by the values of the group-by attributes,
+/// in the order in which the groups first appear in the input. The values
+/// are compared as RDF terms, so e.g. an IRI and a string with the same
+/// text are in different groups.
+pub fn aggregate(
+    input: &SolutionSequence,
+    aggregate: &Aggregate,
+) -> SolutionSequence {
+    // Values can't be compared for total equality, hence the group keys are
+    // bucketed by their hashes and compared within the buckets
+    let mut group_idxs: HashMap<u64, Vec<(GroupKey, usize)>> = HashMap::new();
+    let mut groups: Vec<Vec<&SolutionMapping>> = Vec::new();
+
+    for mapping in input {
+        let key: GroupKey = aggregate
+            .group_by
+            .iter()
+            .map(|attr| mapping.get(attr).cloned())
+            .collect();
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+
+        let bucket = group_idxs.entry(hasher.finish()).or_default();
+        let idx = match bucket.iter().find(|(group_key, _)| *group_key == key) {
+            Some((_, idx)) => *idx,
+            None => {
+                groups.push(Vec::new());
+                bucket.push((key, groups.len() - 1));
+                groups.len() - 1
+            }
+        };
+        groups[idx].push(mapping);
+    }
+
+    groups
+        .into_iter()
+        .map(|group| {
+            let mut result: SolutionMapping = aggregate
+                .group_by
+                .iter()
+                .filter_map(|attr| {
+                    group[0]
+                        .get(attr)
+                        .map(|value| (attr.clone(), value.clone()))
+                })
+                .collect();
+            result.extend(
+                aggregate
+                    .aggregate_pairs
+                    .iter()
+                    .map(|(attr, func)| (attr.clone(), func.aggregate(&group))),
+            );
+            result
+        })
+        .collect()
+}
+
+/// Expands the array value of the unnest attribute into one solution mapping
+/// per element. Object elements are flattened into `{attribute}.{key}`
+/// attributes, whereas other elements replace the attribute's value.
+///
+/// Solution mappings without values for the attribute are kept with the
+/// attribute removed, so that the terms of the other attributes are still
+/// generated.
+pub fn unnest(input: SolutionSequence, unnest: &Unnest) -> SolutionSequence {
+    let attribute = &unnest.attribute;
+    let mut result = Vec::new();
+
+    for mut mapping in input {
+        let elements = match mapping.remove(attribute) {
+            Some(Value::Array(elements)) => elements,
+            Some(object @ Value::Object(_)) => vec![object],
+            Some(Value::Null) | None => vec![],
+            Some(scalar) => vec![scalar],
+        };
+
+        if elements.is_empty() {
+            result.push(mapping);
+            continue;
+        }
+
+        for element in elements {
+            let mut unnested = mapping.clone();
+            match element {
+                Value::Object(object) => {
+                    unnested.extend(object.into_iter().map(|(key, value)| {
+                        (format!("{}.{}", attribute, key), value)
+                    }));
+                }
+                value => {
+                    unnested.insert(attribute.clone(), value);
+                }
+            }
+            result.push(unnested);
+        }
+    }
+
+    result
+}
+
+/// The values of the distinct attributes of a solution mapping.
+type DistinctKey = Vec<(String, Option<Value>)>;
+
+/// Keeps the first occurrence of every solution mapping with distinct values
+/// for the distinct attributes.
+pub fn distinct(
+    input: SolutionSequence,
+    distinct: &Distinct,
+) -> SolutionSequence {
+    let distinct_key = |mapping: &SolutionMapping| -> DistinctKey {
+        match &distinct.attributes {
+            Some(attributes) => {
+                attributes
+                    .iter()
+                    .map(|attr| (attr.clone(), mapping.get(attr).cloned()))
+                    .collect()
+            }
+            None => {
+                let mut pairs: Vec<_> = mapping
+                    .iter()
+                    .map(|(attr, value)| (attr.clone(), Some(value.clone())))
+                    .collect();
+                pairs.sort_by(|left, right| left.0.cmp(&right.0));
+                pairs
+            }
+        }
+    };
+
+    // Values can't be compared for total equality, hence the keys are
+    // bucketed by their hashes and compared within the buckets
+    let mut seen: HashMap<u64, Vec<DistinctKey>> = HashMap::new();
+    input
+        .into_iter()
+        .filter(|mapping| {
+            let key = distinct_key(mapping);
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+
+            let bucket = seen.entry(hasher.finish()).or_default();
+            if bucket.contains(&key) {
+                false
+            } else {
+                bucket.push(key);
+                true
+            }
+        })
+        .collect()
+}
+
+/// Sorts the solution mappings by the sort keys. The sort is stable, so
+/// solution mappings with equal sort keys keep their input order.
+pub fn sort(mut input: SolutionSequence, sort: &Sort) -> SolutionSequence {
+    input.sort_by(|left, right| sort.compare(left, right));
+    input
+}
+
+pub fn rename(input: SolutionSequence, rename: &Rename) -> SolutionSequence {
+    input
+        .into_iter()
+        .map(|mapping| {
+            mapping
+                .into_iter()
+                .map(|(attr, value)| {
+                    match rename.rename_pairs.get(&attr) {
+                        Some(new_attr) => (new_attr.clone(), value),
+                        None => (attr, value),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn prefix_mapping(mapping: &SolutionMapping, alias: &str) -> SolutionMapping {
+    mapping
+        .iter()
+        .map(|(attr, value)| (format!("{}_{}", alias, attr), value.clone()))
+        .collect()
+}
+
+/// Joins the left and right solution sequences. The attributes of the right
+/// solution mappings are prefixed with the join alias in the output, except
+/// for semi and anti joins which only output the left solution mappings.
+pub fn join(
+    left: SolutionSequence,
+    right: SolutionSequence,
+    join: &Join,
+) -> SolutionSequence {
+    let conditions: Vec<JoinCondition> = match join.join_type {
+        JoinType::CrossJoin => vec![],
+        JoinType::NaturalJoin => {
+            let left_attrs: HashSet<_> =
+                left.iter().flat_map(|mapping| mapping.keys()).collect();
+            let right_attrs: HashSet<_> =
+                right.iter().flat_map(|mapping| mapping.keys()).collect();
+            left_attrs
+                .intersection(&right_attrs)
+                .map(|attr| JoinCondition::equal(attr, attr))
+                .collect()
+        }
+        _ => join.join_conditions.clone(),
+    };
+
+    let matches = |left_map: &SolutionMapping, right_map: &SolutionMapping| {
+        conditions.iter().all(|condition| {
+            match (
+                left_map.get(&condition.left_attribute),
+                right_map.get(&condition.right_attribute),
+            ) {
+                (Some(Value::Null), _) | (_, Some(Value::Null)) => false,
+                (Some(l), Some(r)) => condition.predicate_type.compare(l, r),
+                _ => false,
+            }
+        })
+    };
+
+    let mut result = Vec::new();
+    let mut matched_right = vec![false; right.len()];
+    for left_map in &left {
+        let mut left_matched = false;
+        for (right_idx, right_map) in right.iter().enumerate() {
+            if matches(left_map, right_map) {
+                left_matched = true;
+                matched_right[right_idx] = true;
+                if matches!(
+                    join.join_type,
+                    JoinType::SemiJoin | JoinType::AntiJoin
+                ) {
+                    break;
+                }
+                let mut joined = left_map.clone();
+                joined.extend(prefix_mapping(right_map, &join.join_alias));
+                result.push(joined);
+            }
+        }
+
+        let keep_left = match join.join_type {
+            JoinType::LeftJoin
+            | JoinType::FullOuterJoin
+            | JoinType::AntiJoin => !left_matched,
+            JoinType::SemiJoin => left_matched,
+            _ => false,
+        };
+        if keep_left {
+            result.push(left_map.clone());
+        }
+    }
+
+    if matches!(
+        join.join_type,
+        JoinType::RightJoin | JoinType::FullOuterJoin
+    ) {
+        result.extend(
+            right
+                .iter()
+                .zip(matched_right)
+                .filter(|(_, matched)| !matched)
+                .map(|(right_map, _)| {
+                    prefix_mapping(right_map, &join.join_alias)
+                }),
+        );
+    }
+
+    result
+}
+
+/// The rendered IRI of `rr:defaultGraph`, quads in this graph are written as
+/// triples.
+const DEFAULT_GRAPH: &str = "<http://www.w3.org/ns/r2rml#defaultGraph>";
+
+/// Instantiates the quad patterns of the serializer template with the values
+/// of each solution mapping. Patterns referring to attributes which are
+/// missing in a solution mapping are not generated for that mapping, and
+/// graph terms instantiated to `rr:defaultGraph` are left out. Values which
+/// are not RDF terms are written as literals, see [Value::to_literal].
+pub fn serialize(
+    input: &SolutionSequence,
+    serializer: &Serializer,
+) -> Vec<String> {
+    input
+        .iter()
+        .flat_map(|mapping| {
+            let binding = |variable: &str| {
+                match lookup_variable(variable, mapping) {
+                    Some(Value::Null) | None => None,
+                    Some(value) => {
+                        value.to_ntriples().or_else(|| {
+                            value
+                                .to_literal()
+                                .map(|literal| literal.to_ntriples())
+                        })
+                    }
+                }
+            };
+            serializer.template.iter().filter_map(move |pattern| {
+                let QuadPattern {
+                    subject,
+                    predicate,
+                    object,
+                    graph,
+                } = pattern;
+                let graph = match graph {
+                    Some(graph) => Some(graph.instantiate(&binding)?),
+                    None => None,
+                };
+                let mut terms = [subject, predicate, object]
+                    .into_iter()
+                    .map(|term| term.instantiate(&binding))
+                    .collect::<Option<Vec<_>>>()?;
+                terms.extend(graph.filter(|graph| graph != DEFAULT_GRAPH));
+                Some(format!("{} .", terms.join(" ")))
+            })
+        })
+        .collect()
+}
+
+fn lookup_variable<'a>(
+    variable: &str,
+    mapping: &'a SolutionMapping,
+) -> Option<&'a Value> {
+    mapping
+        .get(&format!("?{}", variable))
+        .or_else(|| mapping.get(variable))
+}
+
+#[cfg(test)]
+mod tests {
+    use operator::template::parse_template;
+    use operator::value::Literal;
+    use operator::{Function, PredicateType};
+
+    use super::*;
+
+    fn mapping(pairs: &[(&str, &str)]) -> SolutionMapping {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), Value::String(v.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_join_prefixes_right_attributes() {
+        let left =
+            vec![mapping(&[("Sport", "100")]), mapping(&[("Sport", "")])];
+        let right = vec![mapping(&[("ID", "100"), ("Name", "Tennis")])];
+        let join_config = Join {
+            join_conditions: vec![JoinCondition::equal("Sport", "ID")],
+            join_alias: "join_1".to_string(),
+            ..Default::default()
+        };
+
+        let result = join(left.clone(), right.clone(), &join_config);
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].get("join_1_Name"),
+            Some(&Value::String("Tennis".to_string()))
+        );
+
+        let left_join = Join {
+            join_type: JoinType::LeftJoin,
+            ..join_config
+        };
+        assert_eq!(join(left, right, &left_join).len(), 2);
+    }
+
+    #[test]
+    fn test_join_types_with_condition_predicates() {
+        let left = vec![
+            mapping(&[("name", "Venus"), ("age", "23")]),
+            mapping(&[("name", "Demi"), ("age", "9")]),
+        ];
+        let right = vec![
+            mapping(&[("name", "Venus"), ("min_age", "18")]),
+            mapping(&[("name", "Serena"), ("min_age", "18")]),
+        ];
+        let join_config = |join_type| {
+            Join {
+                join_conditions: vec![
+                    JoinCondition::equal("name", "name"),
+                    JoinCondition {
+                        left_attribute:  "age".to_string(),
+                        right_attribute: "min_age".to_string(),
+                        predicate_type:  PredicateType::GEqual,
+                    },
+                ],
+                join_type,
+                join_alias: "adult".to_string(),
+            }
+        };
+        let run = |join_type| {
+            join(left.clone(), right.clone(), &join_config(join_type))
+        };
+
+        assert_eq!(run(JoinType::SemiJoin), left[..1].to_vec());
+        assert_eq!(run(JoinType::AntiJoin), left[1..].to_vec());
+        assert_eq!(
+            run(JoinType::FullOuterJoin),
+            vec![
+                mapping(&[
+                    ("name", "Venus"),
+                    ("age", "23"),
+                    ("adult_name", "Venus"),
+                    ("adult_min_age", "18"),
+                ]),
+                left[1].clone(),
+                mapping(&[("adult_name", "Serena"), ("adult_min_age", "18")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unnest_keeps_parent_attributes() {
+        let film = |cast: Value| {
+            let mut film = mapping(&[("film.name", "Dunkirk")]);
+            film.insert("film.cast".to_string(), cast);
+            film
+        };
+        let actor = |name: &str| {
+            Value::Object(HashMap::from([(
+                "name".to_string(),
+                Value::String(name.to_string()),
+            )]))
+        };
+        let input = vec![
+            film(Value::Array(vec![actor("Tom Hardy"), actor("Cillian")])),
+            film(Value::Array(vec![])),
+        ];
+
+        let result = unnest(
+            input,
+            &Unnest {
+                attribute: "film.cast".to_string(),
+            },
+        );
+        assert_eq!(
+            result,
+            vec![
+                mapping(&[
+                    ("film.name", "Dunkirk"),
+                    ("film.cast.name", "Tom Hardy")
+                ]),
+                mapping(&[
+                    ("film.name", "Dunkirk"),
+                    ("film.cast.name", "Cillian")
+                ]),
+                mapping(&[("film.name", "Dunkirk")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distinct_over_attributes() {
+        let input = vec![
+            mapping(&[("s", "a"), ("o", "1")]),
+            mapping(&[("s", "a"), ("o", "2")]),
+            mapping(&[("s", "a"), ("o", "1")]),
+        ];
+
+        assert_eq!(
+            distinct(input.clone(), &Distinct::default()),
+            input[..2].to_vec()
+        );
+        assert_eq!(
+            distinct(
+                input.clone(),
+                &Distinct {
+                    attributes: Some(vec!["s".to_string()]),
+                }
+            ),
+            input[..1].to_vec()
+        );
+    }
+
+    #[test]
+    fn test_aggregate_groups_in_input_order() {
+        let input = vec![
+            mapping(&[("team", "B"), ("player", "Serena")]),
+            mapping(&[("team", "A"), ("player", "Venus")]),
+            mapping(&[("team", "B"), ("player", "Demi")]),
+        ];
+        let aggregate_config = Aggregate {
+            group_by:        vec!["team".to_string()],
+            aggregate_pairs: HashMap::from([(
+                "players".to_string(),
+                operator::AggregateFunction::StringJoin {
+                    attribute: "player".to_string(),
+                    separator: ", ".to_string(),
+                },
+            )]),
+        };
+
+        let result = aggregate(&input, &aggregate_config);
+        assert_eq!(
+            result,
+            vec![
+                mapping(&[("team", "B"), ("players", "Serena, Demi")]),
+                mapping(&[("team", "A"), ("players", "Venus")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialize_default_graph_as_triple() {
+        let serializer = Serializer {
+            template: parse_template("?s <http://ex.com/p> ?o ?g .").unwrap(),
+            options:  None,
+            format:   operator::formats::DataFormat::NQuads,
+        };
+        let graph_mapping = |graph: &str| {
+            HashMap::from([
+                ("?s".to_string(), Value::Iri("http://ex.com/1".to_string())),
+                ("?o".to_string(), Value::Literal(Literal::simple("one"))),
+                ("?g".to_string(), Value::Iri(graph.to_string())),
+            ])
+        };
+        let input = vec![
+            graph_mapping("http://www.w3.org/ns/r2rml#defaultGraph"),
+            graph_mapping("http://ex.com/graph"),
+        ];
+
+        let output = serialize(&input, &serializer);
+        assert_eq!(
+            output,
+            vec![
+                "<http://ex.com/1> <http://ex.com/p> \"one\" .".to_string(),
+                "<http://ex.com/1> <http://ex.com/p> \"one\" \
+                 <http://ex.com/graph> ."
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extend_leaves_out_invalid_terms() {
+        let iri_function = |attr: &str| {
+            Function::Iri {
+                inner_function: Function::Reference {
+                    value: attr.to_string(),
+                }
+                .into(),
+                base_iri:       Some("http://ex.com/base/".to_string()),
+            }
+        };
+        let extend_config = Extend {
+            extend_pairs: HashMap::from([(
+                "?s".to_string(),
+                iri_function("IRI"),
+            )]),
+        };
+        let registry = FunctionRegistry::default();
+
+        let input = vec![
+            mapping(&[("IRI", "http://ex.com/1")]),
+            mapping(&[("Name", "Venus")]),
+        ];
+        let output = extend(input, &extend_config, &registry).unwrap();
+        assert_eq!(
+            output[0].get("?s"),
+            Some(&Value::Iri("http://ex.com/1".to_string()))
+        );
+        assert_eq!(output[1].get("?s"), None);
+
+        let input = vec![
+            mapping(&[("IRI", "Venus")]),
+            mapping(&[("IRI", "Venus Williams")]),
+        ];
+        let output = extend(input, &extend_config, &registry).unwrap();
+        assert_eq!(
+            output[0].get("?s"),
+            Some(&Value::Iri("http://ex.com/base/Venus".to_string()))
+        );
+        assert_eq!(output[1].get("?s"), None);
+
+        let malformed_config = Extend {
+            extend_pairs: HashMap::from([(
+                "?s".to_string(),
+                Function::TemplateString {
+                    value: "http://ex.com/{IRI".to_string(),
+                },
+            )]),
+        };
+        let input = vec![mapping(&[("IRI", "Venus")])];
+        assert!(matches!(
+            extend(input, &malformed_config, &registry),
+            Err(ExecutionError::FunctionError(
+                FunctionError::MalformedTemplate(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_groups_on_terms() {
+        let teams = [
+            Value::String("x".to_string()),
+            Value::Iri("x".to_string()),
+            Value::Literal(Literal::language_tagged("x", "en")),
+            Value::Null,
+            Value::String("null".to_string()),
+            Value::Iri("x".to_string()),
+        ];
+        let input: SolutionSequence = teams
+            .iter()
+            .map(|team| HashMap::from([("team".to_string(), team.clone())]))
+            .collect();
+        let aggregate_config = Aggregate {
+            group_by:        vec!["team".to_string()],
+            aggregate_pairs: HashMap::new(),
+        };
+
+        let result = aggregate(&input, &aggregate_config);
+        assert_eq!(result, input[..5]);
+    }
+
+    #[test]
+    fn test_serialize_skips_missing_variables() {
+        let serializer = Serializer {
+            template: parse_template(
+                "?s <http://ex.com/p?q> ?o .\n?s ?p ?missing .",
+            )
+            .unwrap(),
+            options:  None,
+            format:   operator::formats::DataFormat::NQuads,
+        };
+        let input = vec![HashMap::from([
+            ("?s".to_string(), Value::Iri("http://ex.com/1".to_string())),
+            ("o".to_string(), Value::Literal(Literal::simple("one"))),
+            ("?p".to_string(), Value::Iri("http://ex.com/p".to_string())),
+        ])];
+
+        let output = serialize(&input, &serializer);
+        assert_eq!(
+            output,
+            vec!["<http://ex.com/1> <http://ex.com/p?q> \"one\" .".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_serialize_non_rdf_values_as_literals() {
+        let serializer = Serializer {
+            template: parse_template("?s <http://ex.com/p> ?o .").unwrap(),
+            options:  None,
+            format:   operator::formats::DataFormat::NQuads,
+        };
+        let value_mapping = |value: Value| {
+            HashMap::from([
+                ("?s".to_string(), Value::Iri("http://ex.com/1".to_string())),
+                ("?o".to_string(), value),
+            ])
+        };
+        let input = vec![
+            value_mapping(Value::String("Venus \"V\"".to_string())),
+            value_mapping(Value::Boolean(true)),
+        ];
+
+        let output = serialize(&input, &serializer);
+        assert_eq!(
+            output,
+            vec![
+                "<http://ex.com/1> <http://ex.com/p> \"Venus \\\"V\\\"\" ."
+                    .to_string(),
+                "<http://ex.com/1> <http://ex.com/p> \"true\"^^\
+                 <http://www.w3.org/2001/XMLSchema#boolean> ."
+                    .to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use operator::formats::ReferenceFormulation;
+use operator::tuples::{SolutionMapping, SolutionSequence};
+use operator::value::Value;
+use operator::{Field, IOType, Source};
+
+use crate::error::ExecutionError;
+
+/// Reads the data of a source operator from the local file system.
+/// Relative paths are resolved against the given base path.
+pub fn read_source(
+    source: &Source,
+    base_path: &Path,
+) -> Result<SolutionSequence, ExecutionError> {
+    if source.source_type != IOType::File {
+        return Err(ExecutionError::IOTypeNotSupported(
+            source.source_type.clone(),
+        ));
+    }
+
+    let path_str = source
+        .config
+        .get("path")
+        .or_else(|| source.config.get("url"))
+        .ok_or(ExecutionError::MissingSourceConfig("path".to_string()))?;
+    let path = base_path.join(path_str.trim_start_matches("file://"));
+
+    let iterator = &source.root_iterator;
+    match iterator.reference_formulation {
+        ReferenceFormulation::CSVRows => read_csv(&path, iterator),
+        ReferenceFormulation::JSONPath => read_json(&path, iterator),
+        ref other => {
+            Err(ExecutionError::ReferenceFormulationNotSupported(
+                other.clone(),
+            ))
+        }
+    }
+}
+
+fn attribute_name(prefix: &Option<String>, alias: &str) -> String {
+    match prefix {
+        Some(prefix) => format!("{}.{}", prefix, alias),
+        None => alias.to_string(),
+    }
+}
+
+fn read_csv(
+    path: &Path,
+    iterator: &operator::Iterator,
+) -> Result<SolutionSequence, ExecutionError> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let headers = reader.headers()?.clone();
+
+    let mut result = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let row: SolutionMapping = headers
+            .iter()
+            .zip(record.iter())
+            .map(|(header, value)| {
+                (header.to_string(), Value::String(value.to_string()))
+            })
+            .collect();
+
+        let mapping = if iterator.fields.is_empty() {
+            row.into_iter()
+                .map(|(header, value)| {
+                    (attribute_name(&iterator.alias, &header), value)
+                })
+                .collect()
+        } else {
+            iterator
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    row.get(&field.reference).map(|value| {
+                        (
+                            attribute_name(&iterator.alias, &field.alias),
+                            value.clone(),
+                        )
+                    })
+                })
+                .collect()
+        };
+
+        result.push(mapping);
+    }
+
+    Ok(result)
+}
+
+fn read_json(
+    path: &Path,
+    iterator: &operator::Iterator,
+) -> Result<SolutionSequence, ExecutionError> {
+    let document: serde_json::Value =
+        serde_json::from_reader(BufReader::new(File::open(path)?))?;
+
+    let root_path = iterator.reference.as_deref().unwrap_or("$");
+    let items = select_json(&document, root_path)?;
+
+    items
+        .into_iter()
+        .map(|item| {
+            let mut mapping = SolutionMapping::new();
+            if iterator.fields.is_empty() {
+                if let serde_json::Value::Object(obj) = item {
+                    mapping.extend(obj.iter().map(|(key, value)| {
+                        (
+                            attribute_name(&iterator.alias, key),
+                            json_to_value(value),
+                        )
+                    }));
+                }
+            } else {
+                let prefix = iterator.alias.clone();
+                for field in &iterator.fields {
+                    extract_json_field(item, field, &prefix, &mut mapping)?;
+                }
+            }
+            Ok(mapping)
+        })
+        .collect()
+}
+
+fn extract_json_field(
+    item: &serde_json::Value,
+    field: &Field,
+    prefix: &Option<String>,
+    mapping: &mut SolutionMapping,
+) -> Result<(), ExecutionError> {
+    let attribute = attribute_name(prefix, &field.alias);
+    let selected = select_json(item, &field.reference)?;
+
+    if field.inner_fields.is_empty() {
+        let value = match selected.as_slice() {
+            [] => return Ok(()),
+            [single] => json_to_value(single),
+            multiple => {
+                Value::Array(
+                    multiple.iter().map(|v| json_to_value(v)).collect(),
+                )
+            }
+        };
+        mapping.insert(attribute, value);
+        return Ok(());
+    }
+
+    // Nested iterator fields yield an array with one object per nested
+    // item, which gets flattened by an unnest operator
+    let nested_items = selected
+        .into_iter()
+        .map(|nested_item| {
+            let mut nested_mapping = SolutionMapping::new();
+            for inner_field in &field.inner_fields {
+                extract_json_field(
+                    nested_item,
+                    inner_field,
+                    &None,
+                    &mut nested_mapping,
+                )?;
+            }
+            Ok(Value::Object(nested_mapping))
+        })
+        .collect::<Result<_, ExecutionError>>()?;
+
+    mapping.insert(attribute, Value::Array(nested_items));
+    Ok(())
+}
+
+fn json_to_value(json: &serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Boolean(*b),
+        serde_json::Value::Number(num) => Value::String(num.to_string()),
+        serde_json::Value::String(string) => Value::String(string.clone()),
+        serde_json::Value::Array(values) => {
+            Value::Array(values.iter().map(json_to_value).collect())
+        }
+        serde_json::Value::Object(obj) => {
+            Value::Object(
+                obj.iter()
+                    .map(|(key, value)| (key.clone(), json_to_value(value)))
+                    .collect(),
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// Parses the supported subset of JSONPath: `$`, `@`, `.key`, `['key']`,
+/// `[n]`, `[*]` and `.*`. Paths without a leading `$` or `@` are evaluated
+/// relative to the current item.
+fn parse_json_path(path: &str) -> Result<Vec<PathSegment>, ExecutionError> {
+    let invalid = || ExecutionError::InvalidJSONPath(path.to_string());
+    let trimmed = path.trim().trim_start_matches('$').trim_start_matches('@');
+
+    let mut segments = Vec::new();
+    let mut chars = trimmed.chars().peekable();
+    let mut key = String::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !key.is_empty() {
+                    segments.push(PathSegment::Key(std::mem::take(&mut key)));
+                }
+            }
+            '[' => {
+                if !key.is_empty() {
+                    segments.push(PathSegment::Key(std::mem::take(&mut key)));
+                }
+                let inner: String =
+                    chars.by_ref().take_while(|c| *c != ']').collect();
+                let inner = inner.trim();
+                let segment = if inner == "*" {
+                    PathSegment::Wildcard
+                } else if let Ok(idx) = inner.parse::<usize>() {
+                    PathSegment::Index(idx)
+                } else if inner.len() >= 2
+                    && (inner.starts_with('\'') || inner.starts_with('"'))
+                {
+                    PathSegment::Key(inner[1..inner.len() - 1].to_string())
+                } else {
+                    return Err(invalid());
+                };
+                segments.push(segment);
+            }
+            '*' if key.is_empty() => segments.push(PathSegment::Wildcard),
+            c => key.push(c),
+        }
+    }
+
+    if !key.is_empty() {
+        segments.push(PathSegment::Key(key));
+    }
+
+    Ok(segments)
+}
+
+fn select_json<'a>(
+    json: &'a serde_json::Value,
+    path: &str,
+) -> Result<Vec<&'a serde_json::Value>, ExecutionError> {
+    let segments = parse_json_path(path)?;
+    let mut current = vec![json];
+
+    for segment in segments {
+        current = current
+            .into_iter()
+            .flat_map(|value| {
+                match (&segment, value) {
+                    (PathSegment::Key(key), serde_json::Value::Object(obj)) => {
+                        obj.get(key).into_iter().collect::<Vec<_>>()
+                    }
+                    (
+                        PathSegment::Index(idx),
+                        serde_json::Value::Array(arr),
+                    ) => arr.get(*idx).into_iter().collect(),
+                    (PathSegment::Wildcard, serde_json::Value::Array(arr)) => {
+                        arr.iter().collect()
+                    }
+                    (PathSegment::Wildcard, serde_json::Value::Object(obj)) => {
+                        obj.values().collect()
+                    }
+                    _ => vec![],
+                }
+            })
+            .collect();
+    }
+
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_json_path_selection() -> Result<(), ExecutionError> {
+        let document = json!({
+            "films": [
+                {"name": "Dunkirk", "reviews": {"good": [{"user": "a"}, {"user": "b"}]}},
+                {"name": "Interstellar"}
+            ]
+        });
+
+        let films = select_json(&document, "$.films[*]")?;
+        assert_eq!(films.len(), 2);
+
+        let users = select_json(films[0], "reviews.good[*].user")?;
+        assert_eq!(users, vec![&json!("a"), &json!("b")]);
+
+        let name = select_json(&document, "$['films'][1].name")?;
+        assert_eq!(name, vec![&json!("Interstellar")]);
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use std::cmp::Ordering;
+
+use crate::tuples::SolutionMapping;
+use crate::value::Value;
+use crate::AggregateFunction;
+
+impl AggregateFunction {
+    pub fn attribute(&self) -> Option<&str> {
+        match self {
+            AggregateFunction::CollectList { attribute }
+            | AggregateFunction::Min { attribute }
+            | AggregateFunction::Max { attribute }
+            | AggregateFunction::StringJoin { attribute, .. } => {
+                Some(attribute)
+            }
+            AggregateFunction::Count { attribute } => attribute.as_deref(),
+        }
+    }
+
+    /// Computes the aggregate over the solution mappings of a group.
+    ///
+    /// Missing and null values are ignored, hence `Count` without an
+    /// attribute counts the solution mappings while `Count` with an
+    /// attribute counts the non-null values. `Min` and `Max` of a group
+    /// without values are null.
+    pub fn aggregate(&self, group: &[&SolutionMapping]) -> Value {
+        let values: Vec<&Value> = match self.attribute() {
+            Some(attribute) => {
+                group
+                    .iter()
+                    .filter_map(|mapping| mapping.get(attribute))
+                    .filter(|value| **value != Value::Null)
+                    .collect()
+            }
+            None => vec![],
+        };
+
+        match self {
+            AggregateFunction::CollectList { .. } => {
+                Value::Array(values.into_iter().cloned().collect())
+            }
+            AggregateFunction::Count { attribute: None } => {
+                Value::from(group.len() as u64)
+            }
+            AggregateFunction::Count { .. } => Value::from(values.len() as u64),
+            AggregateFunction::Min { .. } => extremum(values, Ordering::Less),
+            AggregateFunction::Max { .. } => {
+                extremum(values, Ordering::Greater)
+            }
+            AggregateFunction::StringJoin { separator, .. } => {
+                let strings: Vec<String> =
+                    values.into_iter().map(String::from).collect();
+                Value::String(strings.join(separator))
+            }
+        }
+    }
+}
+
+/// Finds the first value which is ordered before (for [Ordering::Less]) or
+/// after (for [Ordering::Greater]) all the other values by
+/// [Value::total_cmp].
+fn extremum(values: Vec<&Value>, ordering: Ordering) -> Value {
+    values
+        .into_iter()
+        .reduce(|current, value| {
+            if value.total_cmp(current) == ordering {
+                value
+            } else {
+                current
+            }
+        })
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::Aggregate;
+
+    fn group() -> Vec<SolutionMapping> {
+        vec![
+            HashMap::from([
+                ("team".to_string(), Value::String("A".to_string())),
+                ("score".to_string(), Value::String("9".to_string())),
+            ]),
+            HashMap::from([
+                ("team".to_string(), Value::String("A".to_string())),
+                ("score".to_string(), Value::String("10".to_string())),
+            ]),
+            HashMap::from([
+                ("team".to_string(), Value::String("A".to_string())),
+                ("score".to_string(), Value::Null),
+            ]),
+        ]
+    }
+
+    #[test]
+    fn test_aggregate_functions() {
+        let group = group();
+        let group: Vec<_> = group.iter().collect();
+        let score = || "score".to_string();
+
+        assert_eq!(
+            AggregateFunction::Count { attribute: None }.aggregate(&group),
+            Value::from(3_u64)
+        );
+        assert_eq!(
+            AggregateFunction::Count {
+                attribute: Some(score()),
+            }
+            .aggregate(&group),
+            Value::from(2_u64)
+        );
+        assert_eq!(
+            AggregateFunction::Max { attribute: score() }.aggregate(&group),
+            Value::String("10".to_string())
+        );
+        assert_eq!(
+            AggregateFunction::Min { attribute: score() }.aggregate(&group),
+            Value::String("9".to_string())
+        );
+        assert_eq!(
+            AggregateFunction::StringJoin {
+                attribute: score(),
+                separator: ";".to_string(),
+            }
+            .aggregate(&group),
+            Value::String("9;10".to_string())
+        );
+        assert_eq!(
+            AggregateFunction::CollectList { attribute: score() }
+                .aggregate(&group),
+            Value::Array(vec![
+                Value::String("9".to_string()),
+                Value::String("10".to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn test_aggregate_json() -> Result<(), serde_json::Error> {
+        let aggregate_op = crate::Operator::AggregateOp {
+            config: Aggregate {
+                group_by:        vec!["team".to_string()],
+                aggregate_pairs: HashMap::from([(
+                    "scores".to_string(),
+                    AggregateFunction::StringJoin {
+                        attribute: "score".to_string(),
+                        separator: ",".to_string(),
+                    },
+                )]),
+            },
+        };
+
+        let json = serde_json::to_value(&aggregate_op)?;
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "AggregateOp",
+                "config": {
+                    "group_by": ["team"],
+                    "aggregate_pairs": {
+                        "scores": {
+                            "type": "StringJoin",
+                            "attribute": "score",
+                            "separator": ","
+                        }
+                    }
+                }
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<crate::Operator>(json)?,
+            aggregate_op
+        );
+        Ok(())
+    }
+}
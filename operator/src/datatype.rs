@@ -0,0 +1,346 @@
+use vocab::xsd::TYPE;
+use vocab::{ToString as _, PAIR};
+
+use crate::error::FunctionError;
+use crate::value::{Number, Value};
+
+/// XSD datatypes whose literals are coerced to a typed [Value].
+///
+/// Integers outside of the 64-bit range of their [Number] variant are not
+/// supported and are reported as ill-typed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XsdDatatype {
+    String,
+    Boolean,
+    Decimal,
+    Float,
+    Double,
+    Integer,
+    NonPositiveInteger,
+    NegativeInteger,
+    Long,
+    Int,
+    Short,
+    Byte,
+    NonNegativeInteger,
+    PositiveInteger,
+    UnsignedLong,
+    UnsignedInt,
+    UnsignedShort,
+    UnsignedByte,
+}
+
+const DATATYPE_IRIS: [(XsdDatatype, PAIR); 18] = [
+    (XsdDatatype::String, TYPE::XSD_STRING),
+    (XsdDatatype::Boolean, TYPE::XSD_BOOLEAN),
+    (XsdDatatype::Decimal, TYPE::XSD_DECIMAL),
+    (XsdDatatype::Float, TYPE::XSD_FLOAT),
+    (XsdDatatype::Double, TYPE::XSD_DOUBLE),
+    (XsdDatatype::Integer, TYPE::XSD_INTEGER),
+    (
+        XsdDatatype::NonPositiveInteger,
+        TYPE::XSD_NON_POSITIVE_INTEGER,
+    ),
+    (XsdDatatype::NegativeInteger, TYPE::XSD_NEGATIVE_INTEGER),
+    (XsdDatatype::Long, TYPE::XSD_LONG),
+    (XsdDatatype::Int, TYPE::XSD_INT),
+    (XsdDatatype::Short, TYPE::XSD_SHORT),
+    (XsdDatatype::Byte, TYPE::XSD_BYTE),
+    (
+        XsdDatatype::NonNegativeInteger,
+        TYPE::XSD_NON_NEGATIVE_INTEGER,
+    ),
+    (XsdDatatype::PositiveInteger, TYPE::XSD_POSITIVE_INTEGER),
+    (XsdDatatype::UnsignedLong, TYPE::XSD_UNSIGNED_LONG),
+    (XsdDatatype::UnsignedInt, TYPE::XSD_UNSIGNED_INT),
+    (XsdDatatype::UnsignedShort, TYPE::XSD_UNSIGNED_SHORT),
+    (XsdDatatype::UnsignedByte, TYPE::XSD_UNSIGNED_BYTE),
+];
+
+impl XsdDatatype {
+    pub fn from_iri(iri: &str) -> Option<Self> {
+        DATATYPE_IRIS
+            .iter()
+            .find(|(_, pair)| pair.to_string() == iri)
+            .map(|(datatype, _)| *datatype)
+    }
+
+    pub fn iri(&self) -> String {
+        DATATYPE_IRIS
+            .iter()
+            .find(|(datatype, _)| datatype == self)
+            .map(|(_, pair)| pair.to_string())
+            .unwrap()
+    }
+
+    /// Coerces the lexical form to a value of the datatype.
+    ///
+    /// The string form of the returned value is the canonical lexical form
+    /// of the datatype, e.g. `"007"` is coerced to the `xsd:integer` 7 and
+    /// `"23"` to the `xsd:double` written as `2.3E1`.
+    pub fn coerce(&self, lexical_form: &str) -> Result<Value, FunctionError> {
+        let ill_typed = || {
+            FunctionError::IllTypedLiteral {
+                lexical_form: lexical_form.to_string(),
+                datatype:     self.iri(),
+            }
+        };
+
+        // All the datatypes except for strings collapse their whitespace.
+        let collapsed = lexical_form.trim();
+        match self {
+            XsdDatatype::String => Ok(Value::String(lexical_form.to_string())),
+            XsdDatatype::Boolean => {
+                match collapsed {
+                    "true" | "1" => Ok(Value::Boolean(true)),
+                    "false" | "0" => Ok(Value::Boolean(false)),
+                    _ => Err(ill_typed()),
+                }
+            }
+            XsdDatatype::Decimal => {
+                canonical_decimal(collapsed)
+                    .map(|decimal| Value::Number(Number::Decimal(decimal)))
+                    .ok_or_else(ill_typed)
+            }
+            XsdDatatype::Float => {
+                parse_floating(collapsed)
+                    .map(|double| floating_number(double as f32, Number::Float))
+                    .map(Value::Number)
+                    .ok_or_else(ill_typed)
+            }
+            XsdDatatype::Double => {
+                parse_floating(collapsed)
+                    .map(|double| floating_number(double, Number::Double))
+                    .map(Value::Number)
+                    .ok_or_else(ill_typed)
+            }
+            integer_datatype => {
+                let (min, max) = integer_datatype.integer_range();
+                let integer = parse_integer(collapsed)
+                    .filter(|integer| (min..=max).contains(integer))
+                    .ok_or_else(ill_typed)?;
+                Ok(Value::Number(integer_datatype.integer_number(integer)))
+            }
+        }
+    }
+
+    fn integer_range(&self) -> (i128, i128) {
+        match self {
+            XsdDatatype::NonPositiveInteger => (i64::MIN.into(), 0),
+            XsdDatatype::NegativeInteger => (i64::MIN.into(), -1),
+            XsdDatatype::Int => (i32::MIN.into(), i32::MAX.into()),
+            XsdDatatype::Short => (i16::MIN.into(), i16::MAX.into()),
+            XsdDatatype::Byte => (i8::MIN.into(), i8::MAX.into()),
+            XsdDatatype::NonNegativeInteger | XsdDatatype::UnsignedLong => {
+                (0, u64::MAX.into())
+            }
+            XsdDatatype::PositiveInteger => (1, u64::MAX.into()),
+            XsdDatatype::UnsignedInt => (0, u32::MAX.into()),
+            XsdDatatype::UnsignedShort => (0, u16::MAX.into()),
+            XsdDatatype::UnsignedByte => (0, u8::MAX.into()),
+            _ => (i64::MIN.into(), i64::MAX.into()),
+        }
+    }
+
+    /// Wraps the integer, which has to be in the range of the datatype, in
+    /// the smallest [Number] variant holding all the values of the datatype.
+    fn integer_number(&self, integer: i128) -> Number {
+        match self {
+            XsdDatatype::Int | XsdDatatype::Short | XsdDatatype::Byte => {
+                Number::Short(integer as i32)
+            }
+            XsdDatatype::NonNegativeInteger
+            | XsdDatatype::PositiveInteger
+            | XsdDatatype::UnsignedLong => Number::UInt(integer as u64),
+            XsdDatatype::UnsignedInt | XsdDatatype::UnsignedShort => {
+                Number::UShort(integer as u32)
+            }
+            XsdDatatype::UnsignedByte => Number::Byte(integer as u8),
+            _ => Number::Int(integer as i64),
+        }
+    }
+}
+
+/// Returns the canonical lexical form of a literal with the given datatype.
+///
+/// The lexical forms of datatypes which are not an [XsdDatatype] are kept
+/// as they are.
+pub fn canonical_lexical_form(
+    lexical_form: &str,
+    datatype: &str,
+) -> Result<String, FunctionError> {
+    match XsdDatatype::from_iri(datatype) {
+        Some(xsd_datatype) => {
+            Ok(String::from(&xsd_datatype.coerce(lexical_form)?))
+        }
+        None => Ok(lexical_form.to_string()),
+    }
+}
+
+fn split_sign(lexical_form: &str) -> (bool, &str) {
+    if let Some(unsigned) = lexical_form.strip_prefix('-') {
+        (true, unsigned)
+    } else {
+        (
+            false,
+            lexical_form.strip_prefix('+').unwrap_or(lexical_form),
+        )
+    }
+}
+
+fn is_digits(digits: &str) -> bool {
+    digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Splits an unsigned decimal into its integer and fractional digits.
+fn split_decimal(unsigned: &str) -> Option<(&str, &str)> {
+    let (integer, fraction) =
+        unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let valid = !(integer.is_empty() && fraction.is_empty())
+        && is_digits(integer)
+        && is_digits(fraction);
+    valid.then_some((integer, fraction))
+}
+
+fn parse_integer(lexical_form: &str) -> Option<i128> {
+    let (_, digits) = split_sign(lexical_form);
+    if digits.is_empty() || !is_digits(digits) {
+        return None;
+    }
+    lexical_form.parse().ok()
+}
+
+/// Canonical decimals have at least one digit on both sides of the decimal
+/// point, without any redundant zeros or signs.
+fn canonical_decimal(lexical_form: &str) -> Option<String> {
+    let (negative, unsigned) = split_sign(lexical_form);
+    let (integer, fraction) = split_decimal(unsigned)?;
+
+    let integer = match integer.trim_start_matches('0') {
+        "" => "0",
+        integer => integer,
+    };
+    let fraction = match fraction.trim_end_matches('0') {
+        "" => "0",
+        fraction => fraction,
+    };
+    let sign = if negative && (integer != "0" || fraction != "0") {
+        "-"
+    } else {
+        ""
+    };
+    Some(format!("{}{}.{}", sign, integer, fraction))
+}
+
+fn parse_floating(lexical_form: &str) -> Option<f64> {
+    match lexical_form {
+        "INF" | "+INF" => return Some(f64::INFINITY),
+        "-INF" => return Some(f64::NEG_INFINITY),
+        "NaN" => return Some(f64::NAN),
+        _ => {}
+    }
+
+    let (_, unsigned) = split_sign(lexical_form);
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (unsigned, None),
+    };
+    let exponent_valid = exponent.is_none_or(|exponent| {
+        let (_, digits) = split_sign(exponent);
+        !digits.is_empty() && is_digits(digits)
+    });
+
+    if split_decimal(mantissa).is_none() || !exponent_valid {
+        return None;
+    }
+    lexical_form.parse().ok()
+}
+
+fn floating_number<F: Into<f64> + Copy>(
+    value: F,
+    number: fn(F) -> Number,
+) -> Number {
+    let double: f64 = value.into();
+    if double == f64::INFINITY {
+        Number::PosInfinity
+    } else if double == f64::NEG_INFINITY {
+        Number::NegInfinity
+    } else {
+        number(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(lexical_form: &str, datatype: XsdDatatype) -> String {
+        canonical_lexical_form(lexical_form, &datatype.iri()).unwrap()
+    }
+
+    fn is_ill_typed(lexical_form: &str, datatype: XsdDatatype) -> bool {
+        matches!(
+            datatype.coerce(lexical_form),
+            Err(FunctionError::IllTypedLiteral { .. })
+        )
+    }
+
+    #[test]
+    fn test_coerce_integers() {
+        assert_eq!(
+            XsdDatatype::Integer.coerce(" 007 "),
+            Ok(Value::Number(Number::Int(7)))
+        );
+        assert_eq!(
+            XsdDatatype::UnsignedByte.coerce("23"),
+            Ok(Value::Number(Number::Byte(23)))
+        );
+        assert_eq!(canonical("+0023", XsdDatatype::Int), "23");
+        assert_eq!(canonical("-0", XsdDatatype::NonNegativeInteger), "0");
+        assert_eq!(canonical("255", XsdDatatype::UnsignedByte), "255");
+
+        assert!(is_ill_typed("23.0", XsdDatatype::Integer));
+        assert!(is_ill_typed("1_000", XsdDatatype::Integer));
+        assert!(is_ill_typed("128", XsdDatatype::Byte));
+        assert!(is_ill_typed("0", XsdDatatype::PositiveInteger));
+        assert!(is_ill_typed("-1", XsdDatatype::UnsignedLong));
+        assert!(is_ill_typed("", XsdDatatype::Long));
+    }
+
+    #[test]
+    fn test_coerce_decimals_and_floating_points() {
+        assert_eq!(canonical("0023.500", XsdDatatype::Decimal), "23.5");
+        assert_eq!(canonical("-.5", XsdDatatype::Decimal), "-0.5");
+        assert_eq!(canonical("-0.0", XsdDatatype::Decimal), "0.0");
+        assert_eq!(canonical("23", XsdDatatype::Decimal), "23.0");
+        assert!(is_ill_typed(".", XsdDatatype::Decimal));
+        assert!(is_ill_typed("1e5", XsdDatatype::Decimal));
+
+        assert_eq!(canonical("23", XsdDatatype::Double), "2.3E1");
+        assert_eq!(canonical("0.00125", XsdDatatype::Double), "1.25E-3");
+        assert_eq!(canonical("1e0", XsdDatatype::Float), "1.0E0");
+        assert_eq!(canonical("-INF", XsdDatatype::Double), "-INF");
+        assert_eq!(canonical("1e400", XsdDatatype::Double), "INF");
+        assert_eq!(canonical("NaN", XsdDatatype::Float), "NaN");
+        assert!(is_ill_typed("inf", XsdDatatype::Double));
+        assert!(is_ill_typed("1e", XsdDatatype::Double));
+        assert!(is_ill_typed("0x10", XsdDatatype::Float));
+    }
+
+    #[test]
+    fn test_coerce_booleans_and_strings() {
+        assert_eq!(canonical("1", XsdDatatype::Boolean), "true");
+        assert_eq!(canonical(" false", XsdDatatype::Boolean), "false");
+        assert!(is_ill_typed("yes", XsdDatatype::Boolean));
+        assert_eq!(canonical(" 007 ", XsdDatatype::String), " 007 ");
+    }
+
+    #[test]
+    fn test_unknown_datatypes_are_kept() {
+        assert_eq!(XsdDatatype::from_iri("http://example.com/type"), None);
+        assert_eq!(
+            canonical_lexical_form(" 007 ", "http://example.com/type"),
+            Ok(" 007 ".to_string())
+        );
+    }
+}
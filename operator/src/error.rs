@@ -0,0 +1,73 @@
+use crate::fno::ParameterType;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum FunctionError {
+    #[error("The attribute {0} is missing from the solution mapping")]
+    MissingAttribute(String),
+
+    #[error("The attribute {0} has a null value")]
+    NullAttribute(String),
+
+    #[error("The template variable {0} is not bound to any function")]
+    UnboundTemplateVariable(String),
+
+    #[error("The template {0} has an unterminated variable")]
+    MalformedTemplate(String),
+
+    #[error("The value {value} matches several replacements {replacements:?}")]
+    AmbiguousReplacement {
+        value:        String,
+        replacements: Vec<String>,
+    },
+
+    #[error("The value {0} is not a valid IRI")]
+    InvalidIri(String),
+
+    #[error("The value {0} is not a valid language tag")]
+    InvalidLanguageTag(String),
+
+    #[error("The value {0} is not a valid blank node identifier")]
+    InvalidBlankNode(String),
+
+    #[error(
+        "The lexical form {lexical_form:?} is not valid for the datatype {datatype}"
+    )]
+    IllTypedLiteral {
+        lexical_form: String,
+        datatype:     String,
+    },
+
+    #[error("The regular expression {0} is invalid")]
+    InvalidPattern(String),
+
+    #[error("The function {0} is not supported")]
+    UnsupportedFunction(String),
+
+    #[error("The function {function} is missing the parameter {parameter}")]
+    MissingParameter {
+        function:  String,
+        parameter: String,
+    },
+
+    #[error(
+        "The value {value:?} of the parameter {parameter} of the function {function} is not of the type {expected:?}"
+    )]
+    InvalidParameter {
+        function:  String,
+        parameter: String,
+        value:     String,
+        expected:  ParameterType,
+    },
+
+    #[error("The function {function} failed: {message}")]
+    FunctionFailed { function: String, message: String },
+
+    #[error("The function {0} has no result")]
+    EmptyResult(String),
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum TemplateError {
+    #[error("The template pattern {pattern:?} is malformed: {reason}")]
+    MalformedPattern { pattern: String, reason: String },
+}
@@ -0,0 +1,368 @@
+//! Built-in implementations of the GREL functions.
+//!
+//! Strings are indexed by characters, and negative indices count from the
+//! end of the string as in GREL.
+
+use vocab::grel::{FUNCTION, PARAMETER};
+use vocab::{ToString as _, PAIR};
+
+use super::{
+    boolean_arg, integer_arg, string_arg, FunctionDefinition, Parameter,
+    ParameterType,
+};
+use crate::value::{Number, Value};
+
+fn required(parameter: PAIR, parameter_type: ParameterType) -> Parameter {
+    Parameter::required(&parameter.to_string(), parameter_type)
+}
+
+fn optional(parameter: PAIR, parameter_type: ParameterType) -> Parameter {
+    Parameter::optional(&parameter.to_string(), parameter_type)
+}
+
+/// Defines a function on the string of the `valueParameter`.
+fn string_function<F>(function: PAIR, map: F) -> FunctionDefinition
+where
+    F: Fn(&str) -> Value + Send + Sync + 'static,
+{
+    FunctionDefinition::new(
+        &function.to_string(),
+        vec![required(PARAMETER::VALUE, ParameterType::String)],
+        move |args| Ok(map(string_arg(args, 0).unwrap_or_default())),
+    )
+}
+
+/// Defines a predicate on the strings of the `valueParameter` and the
+/// `string_sub` parameter.
+fn substring_predicate<F>(function: PAIR, predicate: F) -> FunctionDefinition
+where
+    F: Fn(&str, &str) -> bool + Send + Sync + 'static,
+{
+    FunctionDefinition::new(
+        &function.to_string(),
+        vec![
+            required(PARAMETER::VALUE, ParameterType::String),
+            required(PARAMETER::STRING_SUB, ParameterType::String),
+        ],
+        move |args| {
+            Ok(Value::Boolean(predicate(
+                string_arg(args, 0).unwrap_or_default(),
+                string_arg(args, 1).unwrap_or_default(),
+            )))
+        },
+    )
+}
+
+pub fn definitions() -> Vec<FunctionDefinition> {
+    vec![
+        string_function(FUNCTION::TO_UPPER_CASE, |value| {
+            value.to_uppercase().into()
+        }),
+        string_function(FUNCTION::TO_LOWER_CASE, |value| {
+            value.to_lowercase().into()
+        }),
+        string_function(FUNCTION::TO_TITLE_CASE, |value| {
+            title_case(value).into()
+        }),
+        string_function(FUNCTION::STRING_TRIM, |value| value.trim().into()),
+        string_function(FUNCTION::STRING_LENGTH, |value| {
+            Value::Number(Number::Int(value.chars().count() as i64))
+        }),
+        substring_predicate(FUNCTION::STRING_CONTAINS, |value, sub| {
+            value.contains(sub)
+        }),
+        substring_predicate(FUNCTION::STRING_STARTS_WITH, |value, sub| {
+            value.starts_with(sub)
+        }),
+        substring_predicate(FUNCTION::STRING_ENDS_WITH, |value, sub| {
+            value.ends_with(sub)
+        }),
+        FunctionDefinition::new(
+            &FUNCTION::STRING_REPLACE.to_string(),
+            vec![
+                required(PARAMETER::VALUE, ParameterType::String),
+                required(PARAMETER::STRING_FIND, ParameterType::String),
+                required(PARAMETER::STRING_REPLACE, ParameterType::String),
+            ],
+            |args| {
+                let value = string_arg(args, 0).unwrap_or_default();
+                let find = string_arg(args, 1).unwrap_or_default();
+                let replace = string_arg(args, 2).unwrap_or_default();
+                if find.is_empty() {
+                    return Ok(value.into());
+                }
+                Ok(value.replace(find, replace).into())
+            },
+        ),
+        FunctionDefinition::new(
+            &FUNCTION::STRING_SUBSTRING.to_string(),
+            vec![
+                required(PARAMETER::VALUE, ParameterType::String),
+                required(PARAMETER::INT_FROM, ParameterType::Integer),
+                optional(PARAMETER::INT_OPT_TO, ParameterType::Integer),
+            ],
+            |args| {
+                let chars: Vec<char> =
+                    string_arg(args, 0).unwrap_or_default().chars().collect();
+                let from = char_index(integer_arg(args, 1), chars.len(), 0);
+                let to =
+                    char_index(integer_arg(args, 2), chars.len(), chars.len());
+                if from > to {
+                    return Ok(String::new().into());
+                }
+                Ok(chars[from..to].iter().collect::<String>().into())
+            },
+        ),
+        FunctionDefinition::new(
+            &FUNCTION::STRING_SPLIT.to_string(),
+            vec![
+                required(PARAMETER::VALUE, ParameterType::String),
+                required(PARAMETER::STRING_SEP, ParameterType::String),
+            ],
+            |args| {
+                let value = string_arg(args, 0).unwrap_or_default();
+                let separator = string_arg(args, 1).unwrap_or_default();
+                if separator.is_empty() {
+                    return Err("the separator is empty".to_string());
+                }
+                Ok(value.split(separator).collect::<Vec<_>>().into())
+            },
+        ),
+        FunctionDefinition::new(
+            &FUNCTION::ARRAY_JOIN.to_string(),
+            vec![
+                required(PARAMETER::ARRAY_A, ParameterType::Array),
+                optional(PARAMETER::STRING_SEP, ParameterType::String),
+            ],
+            |args| {
+                let separator = string_arg(args, 1).unwrap_or_default();
+                let values: Vec<String> = match &args[0] {
+                    Value::Array(values) => {
+                        values
+                            .iter()
+                            .filter(|value| !matches!(value, Value::Null))
+                            .map(String::from)
+                            .collect()
+                    }
+                    _ => Vec::new(),
+                };
+                Ok(values.join(separator).into())
+            },
+        ),
+        FunctionDefinition::new(
+            &FUNCTION::BOOLEAN_NOT.to_string(),
+            vec![required(PARAMETER::BOOL_B, ParameterType::Boolean)],
+            |args| {
+                Ok(Value::Boolean(!boolean_arg(args, 0).unwrap_or_default()))
+            },
+        ),
+    ]
+}
+
+/// Capitalizes the first letter of every whitespace-separated word and
+/// lowercases the others.
+fn title_case(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut word_start = true;
+    for c in value.chars() {
+        if word_start {
+            result.extend(c.to_uppercase());
+        } else {
+            result.extend(c.to_lowercase());
+        }
+        word_start = c.is_whitespace();
+    }
+    result
+}
+
+/// Resolves the (possibly negative) GREL index to a character index within
+/// the bounds of the string.
+fn char_index(index: Option<i64>, len: usize, default: usize) -> usize {
+    match index {
+        None => default,
+        Some(idx) if idx < 0 => len.saturating_sub(idx.unsigned_abs() as usize),
+        Some(idx) => (idx as usize).min(len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::error::FunctionError;
+    use crate::fno::FunctionRegistry;
+
+    fn call(
+        function: PAIR,
+        arguments: &[(PAIR, Value)],
+    ) -> Result<Value, FunctionError> {
+        let arguments = arguments
+            .iter()
+            .map(|(parameter, value)| (parameter.to_string(), value.clone()))
+            .collect::<HashMap<_, _>>();
+        FunctionRegistry::builtins().call(&function.to_string(), &arguments)
+    }
+
+    #[test]
+    fn test_string_functions() {
+        let value = |value: &str| [(PARAMETER::VALUE, Value::from(value))];
+        assert_eq!(
+            call(FUNCTION::TO_UPPER_CASE, &value("Venus")),
+            Ok("VENUS".into())
+        );
+        assert_eq!(
+            call(FUNCTION::TO_LOWER_CASE, &value("Venus")),
+            Ok("venus".into())
+        );
+        assert_eq!(
+            call(FUNCTION::TO_TITLE_CASE, &value("venus WILLIAMS")),
+            Ok("Venus Williams".into())
+        );
+        assert_eq!(
+            call(FUNCTION::STRING_TRIM, &value("  Venus \t")),
+            Ok("Venus".into())
+        );
+        assert_eq!(
+            call(FUNCTION::STRING_LENGTH, &value("Zoë")),
+            Ok(Value::Number(Number::Int(3)))
+        );
+
+        let sub = |value: &str, sub: &str| {
+            [
+                (PARAMETER::VALUE, Value::from(value)),
+                (PARAMETER::STRING_SUB, Value::from(sub)),
+            ]
+        };
+        assert_eq!(
+            call(FUNCTION::STRING_CONTAINS, &sub("Venus", "nu")),
+            Ok(Value::Boolean(true))
+        );
+        assert_eq!(
+            call(FUNCTION::STRING_STARTS_WITH, &sub("Venus", "nu")),
+            Ok(Value::Boolean(false))
+        );
+        assert_eq!(
+            call(FUNCTION::STRING_ENDS_WITH, &sub("Venus", "nus")),
+            Ok(Value::Boolean(true))
+        );
+
+        let replace = |find: &str| {
+            [
+                (PARAMETER::VALUE, Value::from("a-b-c")),
+                (PARAMETER::STRING_FIND, Value::from(find)),
+                (PARAMETER::STRING_REPLACE, Value::from("+")),
+            ]
+        };
+        assert_eq!(
+            call(FUNCTION::STRING_REPLACE, &replace("-")),
+            Ok("a+b+c".into())
+        );
+        assert_eq!(
+            call(FUNCTION::STRING_REPLACE, &replace("")),
+            Ok("a-b-c".into())
+        );
+    }
+
+    #[test]
+    fn test_substring_split_and_join() {
+        let substring = |from: i64, to: Option<i64>| {
+            let mut arguments = vec![
+                (PARAMETER::VALUE, Value::from("Venus Williams")),
+                (PARAMETER::INT_FROM, Value::from(from)),
+            ];
+            arguments
+                .extend(to.map(|to| (PARAMETER::INT_OPT_TO, Value::from(to))));
+            call(FUNCTION::STRING_SUBSTRING, &arguments)
+        };
+        assert_eq!(substring(6, None), Ok("Williams".into()));
+        assert_eq!(substring(0, Some(5)), Ok("Venus".into()));
+        assert_eq!(substring(-8, Some(-4)), Ok("Will".into()));
+        assert_eq!(substring(5, Some(2)), Ok("".into()));
+
+        let split = |separator: &str| {
+            call(
+                FUNCTION::STRING_SPLIT,
+                &[
+                    (PARAMETER::VALUE, Value::from("a b c")),
+                    (PARAMETER::STRING_SEP, Value::from(separator)),
+                ],
+            )
+        };
+        assert_eq!(split(" "), Ok(Value::from(vec!["a", "b", "c"])));
+        assert_eq!(
+            split(""),
+            Err(FunctionError::FunctionFailed {
+                function: FUNCTION::STRING_SPLIT.to_string(),
+                message:  "the separator is empty".to_string(),
+            })
+        );
+
+        let values = Value::Array(vec!["a".into(), Value::Null, "b".into()]);
+        assert_eq!(
+            call(
+                FUNCTION::ARRAY_JOIN,
+                &[
+                    (PARAMETER::ARRAY_A, values),
+                    (PARAMETER::STRING_SEP, Value::from(",")),
+                ],
+            ),
+            Ok("a,b".into())
+        );
+        // A single value is joined as a one-element array
+        assert_eq!(
+            call(FUNCTION::ARRAY_JOIN, &[(PARAMETER::ARRAY_A, "a".into())]),
+            Ok("a".into())
+        );
+    }
+
+    #[test]
+    fn test_argument_errors() {
+        assert_eq!(
+            call(
+                FUNCTION::BOOLEAN_NOT,
+                &[(PARAMETER::BOOL_B, Value::from("false"))]
+            ),
+            Ok(Value::Boolean(true))
+        );
+        assert_eq!(
+            call(
+                FUNCTION::BOOLEAN_NOT,
+                &[(PARAMETER::BOOL_B, Value::from("maybe"))]
+            ),
+            Err(FunctionError::InvalidParameter {
+                function:  FUNCTION::BOOLEAN_NOT.to_string(),
+                parameter: PARAMETER::BOOL_B.to_string(),
+                value:     "maybe".to_string(),
+                expected:  ParameterType::Boolean,
+            })
+        );
+        assert_eq!(
+            call(
+                FUNCTION::STRING_CONTAINS,
+                &[(PARAMETER::VALUE, Value::from("Venus"))]
+            ),
+            Err(FunctionError::MissingParameter {
+                function:  FUNCTION::STRING_CONTAINS.to_string(),
+                parameter: PARAMETER::STRING_SUB.to_string(),
+            })
+        );
+        assert_eq!(
+            call(FUNCTION::TO_UPPER_CASE, &[(PARAMETER::VALUE, Value::Null)]),
+            Err(FunctionError::MissingParameter {
+                function:  FUNCTION::TO_UPPER_CASE.to_string(),
+                parameter: PARAMETER::VALUE.to_string(),
+            })
+        );
+        assert!(matches!(
+            call(
+                FUNCTION::STRING_LENGTH,
+                &[(PARAMETER::VALUE, Value::from(vec!["a", "b"]))]
+            ),
+            Err(FunctionError::InvalidParameter {
+                expected: ParameterType::String,
+                ..
+            })
+        ));
+    }
+}
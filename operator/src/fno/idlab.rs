@@ -0,0 +1,392 @@
+//! Built-in implementations of the IDLab functions.
+//!
+//! The date patterns of `normalizeDate` and `normalizeDateTime` use the
+//! letters of Java's `DateTimeFormatter`, as the IDLab functions do.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use vocab::idlab_fn::{FUNCTION, PARAMETER};
+use vocab::{ToString as _, PAIR};
+
+use super::{
+    boolean_arg, string_arg, FunctionDefinition, Parameter, ParameterType,
+};
+use crate::value::Value;
+
+fn required(parameter: PAIR, parameter_type: ParameterType) -> Parameter {
+    Parameter::required(&parameter.to_string(), parameter_type)
+}
+
+fn optional(parameter: PAIR, parameter_type: ParameterType) -> Parameter {
+    Parameter::optional(&parameter.to_string(), parameter_type)
+}
+
+/// Defines a function on the strings of the `str` and `otherStr`
+/// parameters.
+fn string_pair_function<F>(function: PAIR, map: F) -> FunctionDefinition
+where
+    F: Fn(&str, &str) -> Value + Send + Sync + 'static,
+{
+    FunctionDefinition::new(
+        &function.to_string(),
+        vec![
+            required(PARAMETER::STR, ParameterType::String),
+            required(PARAMETER::OTHER_STR, ParameterType::String),
+        ],
+        move |args| {
+            Ok(map(
+                string_arg(args, 0).unwrap_or_default(),
+                string_arg(args, 1).unwrap_or_default(),
+            ))
+        },
+    )
+}
+
+/// Defines a date normalization function, which parses the `strDate` with
+/// the `pattern` and writes it with the given chrono format.
+fn date_function<F>(function: PAIR, normalize: F) -> FunctionDefinition
+where
+    F: Fn(&str, &str) -> Result<String, chrono::ParseError>
+        + Send
+        + Sync
+        + 'static,
+{
+    FunctionDefinition::new(
+        &function.to_string(),
+        vec![
+            required(PARAMETER::STR_DATE, ParameterType::String),
+            required(PARAMETER::PATTERN, ParameterType::String),
+        ],
+        move |args| {
+            let date = string_arg(args, 0).unwrap_or_default();
+            let pattern = string_arg(args, 1).unwrap_or_default();
+            let format = chrono_format(pattern)?;
+            normalize(date.trim(), &format)
+                .map(Value::String)
+                .map_err(|err| {
+                    format!(
+                        "{:?} doesn't match the pattern {}: {}",
+                        date, pattern, err
+                    )
+                })
+        },
+    )
+}
+
+pub fn definitions() -> Vec<FunctionDefinition> {
+    vec![
+        string_pair_function(FUNCTION::EQUAL, |str, other| {
+            Value::Boolean(str == other)
+        }),
+        string_pair_function(FUNCTION::NOT_EQUAL, |str, other| {
+            Value::Boolean(str != other)
+        }),
+        FunctionDefinition::new(
+            &FUNCTION::IS_NULL.to_string(),
+            vec![optional(PARAMETER::STR, ParameterType::Any)],
+            |args| Ok(Value::Boolean(matches!(args[0], Value::Null))),
+        ),
+        FunctionDefinition::new(
+            &FUNCTION::TRUE_CONDITION.to_string(),
+            vec![
+                required(PARAMETER::STR_BOOLEAN, ParameterType::Boolean),
+                required(PARAMETER::STR, ParameterType::Any),
+            ],
+            |args| {
+                match boolean_arg(args, 0) {
+                    Some(true) => Ok(args[1].clone()),
+                    _ => Ok(Value::Null),
+                }
+            },
+        ),
+        FunctionDefinition::new(
+            &FUNCTION::CONCAT.to_string(),
+            vec![
+                required(PARAMETER::STR, ParameterType::String),
+                required(PARAMETER::OTHER_STR, ParameterType::String),
+                optional(PARAMETER::DELIMITER, ParameterType::String),
+            ],
+            |args| {
+                Ok(Value::String(format!(
+                    "{}{}{}",
+                    string_arg(args, 0).unwrap_or_default(),
+                    string_arg(args, 2).unwrap_or_default(),
+                    string_arg(args, 1).unwrap_or_default()
+                )))
+            },
+        ),
+        FunctionDefinition::new(
+            &FUNCTION::STRING_CONTAINS_OTHER_STRING.to_string(),
+            vec![
+                required(PARAMETER::STR, ParameterType::String),
+                required(PARAMETER::OTHER_STR, ParameterType::String),
+                required(PARAMETER::DELIMITER, ParameterType::String),
+            ],
+            |args| {
+                let str = string_arg(args, 0).unwrap_or_default();
+                let other = string_arg(args, 1).unwrap_or_default();
+                let delimiter = string_arg(args, 2).unwrap_or_default();
+                if delimiter.is_empty() {
+                    return Err("the delimiter is empty".to_string());
+                }
+                Ok(Value::Boolean(str.split(delimiter).any(|s| s == other)))
+            },
+        ),
+        date_function(FUNCTION::NORMALIZE_DATE, |date, format| {
+            NaiveDate::parse_from_str(date, format)
+                .map(|date| date.format("%Y-%m-%d").to_string())
+        }),
+        date_function(FUNCTION::NORMALIZE_DATE_TIME, |date, format| {
+            NaiveDateTime::parse_from_str(date, format)
+                .map(|date| date.format("%Y-%m-%dT%H:%M:%S").to_string())
+        }),
+    ]
+}
+
+/// Converts a Java date pattern to a chrono format string. Text between
+/// single quotes is kept literally, and `''` is a single quote.
+fn chrono_format(pattern: &str) -> Result<String, String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut format = String::new();
+    let mut idx = 0;
+
+    while idx < chars.len() {
+        let c = chars[idx];
+        let run = chars[idx..].iter().take_while(|&&next| next == c).count();
+
+        if c == '\'' {
+            if run >= 2 {
+                format.push('\'');
+                idx += 2;
+                continue;
+            }
+            let end = chars[idx + 1..]
+                .iter()
+                .position(|&next| next == '\'')
+                .ok_or(format!("unterminated quote in pattern {}", pattern))?;
+            for &literal in &chars[idx + 1..idx + 1 + end] {
+                push_literal(&mut format, literal);
+            }
+            idx += end + 2;
+            continue;
+        }
+
+        if c.is_ascii_alphabetic() {
+            let specifier = match (c, run) {
+                ('y' | 'u', 2) => "%y",
+                ('y' | 'u', _) => "%Y",
+                ('M', 1 | 2) => "%m",
+                ('M', 3) => "%b",
+                ('M', _) => "%B",
+                ('d', _) => "%d",
+                ('D', _) => "%j",
+                ('E', 1..=3) => "%a",
+                ('E', _) => "%A",
+                ('a', _) => "%p",
+                ('H', _) => "%H",
+                ('h', _) => "%I",
+                ('m', _) => "%M",
+                ('s', _) => "%S",
+                ('S', _) => "%.f",
+                ('X' | 'x' | 'Z', _) => "%z",
+                _ => {
+                    return Err(format!(
+                        "unsupported pattern letter {} in {}",
+                        c, pattern
+                    ))
+                }
+            };
+            // Fractional seconds include their leading dot in chrono
+            if c == 'S' && format.ends_with('.') {
+                format.pop();
+            }
+            format.push_str(specifier);
+        } else {
+            for _ in 0..run {
+                push_literal(&mut format, c);
+            }
+        }
+        idx += run;
+    }
+
+    Ok(format)
+}
+
+fn push_literal(format: &mut String, c: char) {
+    if c == '%' {
+        format.push_str("%%");
+    } else {
+        format.push(c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::error::FunctionError;
+    use crate::fno::FunctionRegistry;
+
+    fn call(
+        function: PAIR,
+        arguments: &[(PAIR, Value)],
+    ) -> Result<Value, FunctionError> {
+        let arguments = arguments
+            .iter()
+            .map(|(parameter, value)| (parameter.to_string(), value.clone()))
+            .collect::<HashMap<_, _>>();
+        FunctionRegistry::builtins().call(&function.to_string(), &arguments)
+    }
+
+    #[test]
+    fn test_comparison_functions() {
+        let pair = |str: &str, other: &str| {
+            [
+                (PARAMETER::STR, Value::from(str)),
+                (PARAMETER::OTHER_STR, Value::from(other)),
+            ]
+        };
+        assert_eq!(
+            call(FUNCTION::EQUAL, &pair("Venus", "Venus")),
+            Ok(Value::Boolean(true))
+        );
+        assert_eq!(
+            call(FUNCTION::NOT_EQUAL, &pair("Venus", "Serena")),
+            Ok(Value::Boolean(true))
+        );
+        assert_eq!(call(FUNCTION::IS_NULL, &[]), Ok(Value::Boolean(true)));
+        assert_eq!(
+            call(FUNCTION::IS_NULL, &[(PARAMETER::STR, Value::from(""))]),
+            Ok(Value::Boolean(false))
+        );
+
+        let contains = |delimiter: &str| {
+            call(
+                FUNCTION::STRING_CONTAINS_OTHER_STRING,
+                &[
+                    (PARAMETER::STR, Value::from("tennis golf")),
+                    (PARAMETER::OTHER_STR, Value::from("golf")),
+                    (PARAMETER::DELIMITER, Value::from(delimiter)),
+                ],
+            )
+        };
+        assert_eq!(contains(" "), Ok(Value::Boolean(true)));
+        assert_eq!(contains(","), Ok(Value::Boolean(false)));
+        assert_eq!(
+            contains(""),
+            Err(FunctionError::FunctionFailed {
+                function: FUNCTION::STRING_CONTAINS_OTHER_STRING.to_string(),
+                message:  "the delimiter is empty".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_value_functions() {
+        let condition = |condition: &str| {
+            call(
+                FUNCTION::TRUE_CONDITION,
+                &[
+                    (PARAMETER::STR_BOOLEAN, Value::from(condition)),
+                    (PARAMETER::STR, Value::from("Venus")),
+                ],
+            )
+        };
+        assert_eq!(condition("true"), Ok("Venus".into()));
+        assert_eq!(
+            condition("false"),
+            Err(FunctionError::EmptyResult(
+                FUNCTION::TRUE_CONDITION.to_string()
+            ))
+        );
+        assert_eq!(
+            condition("yes"),
+            Err(FunctionError::InvalidParameter {
+                function:  FUNCTION::TRUE_CONDITION.to_string(),
+                parameter: PARAMETER::STR_BOOLEAN.to_string(),
+                value:     "yes".to_string(),
+                expected:  ParameterType::Boolean,
+            })
+        );
+
+        let concat = |arguments: &[(PAIR, &str)]| {
+            let arguments: Vec<_> = arguments
+                .iter()
+                .map(|(parameter, value)| (*parameter, Value::from(*value)))
+                .collect();
+            call(FUNCTION::CONCAT, &arguments)
+        };
+        assert_eq!(
+            concat(&[(PARAMETER::STR, "a"), (PARAMETER::OTHER_STR, "b")]),
+            Ok("ab".into())
+        );
+        assert_eq!(
+            concat(&[
+                (PARAMETER::STR, "a"),
+                (PARAMETER::OTHER_STR, "b"),
+                (PARAMETER::DELIMITER, "-"),
+            ]),
+            Ok("a-b".into())
+        );
+        assert_eq!(
+            concat(&[(PARAMETER::STR, "a")]),
+            Err(FunctionError::MissingParameter {
+                function:  FUNCTION::CONCAT.to_string(),
+                parameter: PARAMETER::OTHER_STR.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_date_functions() {
+        let normalize = |function: PAIR, date: &str, pattern: &str| {
+            call(
+                function,
+                &[
+                    (PARAMETER::STR_DATE, Value::from(date)),
+                    (PARAMETER::PATTERN, Value::from(pattern)),
+                ],
+            )
+        };
+        assert_eq!(
+            normalize(FUNCTION::NORMALIZE_DATE, " 17/06/1980 ", "dd/MM/yyyy"),
+            Ok("1980-06-17".into())
+        );
+        assert_eq!(
+            normalize(
+                FUNCTION::NORMALIZE_DATE_TIME,
+                "17 Jun 1980 14:05:09",
+                "dd MMM yyyy HH:mm:ss"
+            ),
+            Ok("1980-06-17T14:05:09".into())
+        );
+        assert!(matches!(
+            normalize(FUNCTION::NORMALIZE_DATE, "1980-06-17", "dd/MM/yyyy"),
+            Err(FunctionError::FunctionFailed { message, .. })
+                if message.starts_with("\"1980-06-17\" doesn't match")
+        ));
+        assert_eq!(
+            normalize(FUNCTION::NORMALIZE_DATE, "1980-W25", "yyyy-'W'ww"),
+            Err(FunctionError::FunctionFailed {
+                function: FUNCTION::NORMALIZE_DATE.to_string(),
+                message:  "unsupported pattern letter w in yyyy-'W'ww"
+                    .to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_chrono_format() {
+        assert_eq!(chrono_format("dd/MM/yyyy"), Ok("%d/%m/%Y".to_string()));
+        assert_eq!(
+            chrono_format("yyyy-MM-dd'T'HH:mm:ss.SSS"),
+            Ok("%Y-%m-%dT%H:%M:%S%.f".to_string())
+        );
+        assert_eq!(
+            chrono_format("d MMMM yy 'at' h a, '100%'''"),
+            Ok("%d %B %y at %I %p, 100%%'".to_string())
+        );
+        assert!(chrono_format("yyyy-ww").is_err());
+        assert!(chrono_format("yyyy 'week").is_err());
+    }
+}
@@ -0,0 +1,401 @@
+//! Registry of the functions which can be called by
+//! [Function::FnO](crate::Function::FnO), keyed by their FnO function IRI.
+//!
+//! The registry comes with built-in implementations of the commonly used
+//! GREL and IDLab functions, and custom functions can be registered next to
+//! them.
+
+mod grel;
+mod idlab;
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, OnceLock};
+
+use crate::datatype::XsdDatatype;
+use crate::error::FunctionError;
+use crate::value::{Number, Value};
+
+/// The type to which the argument of a parameter is coerced before the
+/// function implementation is called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParameterType {
+    /// The string form of any non-array value.
+    String,
+    /// An `xsd:integer`, coerced to [Number::Int].
+    Integer,
+    /// An `xsd:boolean`, coerced to [Value::Boolean].
+    Boolean,
+    /// An array, single values are wrapped in a one-element array.
+    Array,
+    /// Any value, passed on as it is.
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Parameter {
+    pub iri:            String,
+    pub parameter_type: ParameterType,
+    pub optional:       bool,
+}
+
+impl Parameter {
+    pub fn required(iri: &str, parameter_type: ParameterType) -> Parameter {
+        Parameter {
+            iri: iri.to_string(),
+            parameter_type,
+            optional: false,
+        }
+    }
+
+    pub fn optional(iri: &str, parameter_type: ParameterType) -> Parameter {
+        Parameter {
+            iri: iri.to_string(),
+            parameter_type,
+            optional: true,
+        }
+    }
+
+    fn coerce(&self, value: &Value) -> Option<Value> {
+        match (self.parameter_type, value) {
+            (ParameterType::Any, value) => Some(value.clone()),
+            (ParameterType::Array, Value::Array(_)) => Some(value.clone()),
+            (ParameterType::Array, value) => {
+                Some(Value::Array(vec![value.clone()]))
+            }
+            (_, Value::Array(_) | Value::Object(_)) => None,
+            (ParameterType::String, value) => Some(Value::String(value.into())),
+            (ParameterType::Boolean, Value::Boolean(_)) => Some(value.clone()),
+            (ParameterType::Boolean, value) => {
+                XsdDatatype::Boolean.coerce(&String::from(value)).ok()
+            }
+            (ParameterType::Integer, value) => {
+                XsdDatatype::Integer.coerce(&String::from(value)).ok()
+            }
+        }
+    }
+}
+
+/// The implementation of a function. It is called with the arguments in the
+/// order of the parameters of the [FunctionDefinition], coerced to their
+/// [ParameterType]. Absent optional arguments are [Value::Null].
+///
+/// A [Value::Null] result means that the function has no result for the
+/// arguments.
+pub type FunctionImplementation =
+    Arc<dyn Fn(&[Value]) -> Result<Value, String> + Send + Sync>;
+
+#[derive(Clone)]
+pub struct FunctionDefinition {
+    pub iri:            String,
+    pub parameters:     Vec<Parameter>,
+    pub implementation: FunctionImplementation,
+}
+
+impl FunctionDefinition {
+    pub fn new<F>(
+        iri: &str,
+        parameters: Vec<Parameter>,
+        implementation: F,
+    ) -> Self
+    where
+        F: Fn(&[Value]) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        FunctionDefinition {
+            iri: iri.to_string(),
+            parameters,
+            implementation: Arc::new(implementation),
+        }
+    }
+}
+
+impl Debug for FunctionDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FunctionDefinition")
+            .field("iri", &self.iri)
+            .field("parameters", &self.parameters)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FunctionRegistry {
+    functions: HashMap<String, FunctionDefinition>,
+}
+
+impl FunctionRegistry {
+    /// Creates an empty registry without any built-in functions.
+    pub fn new() -> FunctionRegistry {
+        FunctionRegistry::default()
+    }
+
+    /// Returns the shared registry with the built-in GREL and IDLab
+    /// functions.
+    pub fn builtins() -> &'static FunctionRegistry {
+        static BUILTINS: OnceLock<FunctionRegistry> = OnceLock::new();
+        BUILTINS.get_or_init(|| {
+            let mut registry = FunctionRegistry::new();
+            grel::definitions()
+                .into_iter()
+                .chain(idlab::definitions())
+                .for_each(|definition| {
+                    registry.register(definition);
+                });
+            registry
+        })
+    }
+
+    /// Creates a registry with the built-in functions, to which custom
+    /// functions can be added.
+    pub fn with_builtins() -> FunctionRegistry {
+        FunctionRegistry::builtins().clone()
+    }
+
+    /// Registers the function under its IRI, returning the definition it
+    /// replaces if any.
+    pub fn register(
+        &mut self,
+        definition: FunctionDefinition,
+    ) -> Option<FunctionDefinition> {
+        self.functions.insert(definition.iri.clone(), definition)
+    }
+
+    pub fn get(&self, iri: &str) -> Option<&FunctionDefinition> {
+        self.functions.get(iri)
+    }
+
+    pub fn contains(&self, iri: &str) -> bool {
+        self.functions.contains_key(iri)
+    }
+
+    /// Calls the function with the arguments keyed by their parameter IRI.
+    /// Arguments for parameters which the function doesn't define are
+    /// ignored.
+    pub fn call(
+        &self,
+        iri: &str,
+        arguments: &HashMap<String, Value>,
+    ) -> Result<Value, FunctionError> {
+        let definition = self
+            .get(iri)
+            .ok_or(FunctionError::UnsupportedFunction(iri.to_string()))?;
+
+        let mut values = Vec::with_capacity(definition.parameters.len());
+        for parameter in &definition.parameters {
+            let value = match arguments.get(&parameter.iri) {
+                None | Some(Value::Null) if parameter.optional => Value::Null,
+                None | Some(Value::Null) => {
+                    return Err(FunctionError::MissingParameter {
+                        function:  iri.to_string(),
+                        parameter: parameter.iri.clone(),
+                    });
+                }
+                Some(value) => {
+                    parameter.coerce(value).ok_or_else(|| {
+                        FunctionError::InvalidParameter {
+                            function:  iri.to_string(),
+                            parameter: parameter.iri.clone(),
+                            value:     value.into(),
+                            expected:  parameter.parameter_type,
+                        }
+                    })?
+                }
+            };
+            values.push(value);
+        }
+
+        match (definition.implementation)(&values) {
+            Ok(Value::Null) => Err(FunctionError::EmptyResult(iri.to_string())),
+            Ok(value) => Ok(value),
+            Err(message) => {
+                Err(FunctionError::FunctionFailed {
+                    function: iri.to_string(),
+                    message,
+                })
+            }
+        }
+    }
+}
+
+// Accessors for the coerced arguments of the built-in functions
+
+fn string_arg(args: &[Value], idx: usize) -> Option<&str> {
+    match &args[idx] {
+        Value::String(value) => Some(value),
+        _ => None,
+    }
+}
+
+fn integer_arg(args: &[Value], idx: usize) -> Option<i64> {
+    match &args[idx] {
+        Value::Number(Number::Int(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+fn boolean_arg(args: &[Value], idx: usize) -> Option<bool> {
+    match &args[idx] {
+        Value::Boolean(value) => Some(*value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use vocab::ToString;
+
+    use super::*;
+    use crate::Function;
+
+    fn constant(value: &str) -> Arc<Function> {
+        Arc::new(Function::Constant {
+            value: value.to_string(),
+        })
+    }
+
+    fn fno(
+        function: vocab::PAIR,
+        params: Vec<(vocab::PAIR, Arc<Function>)>,
+    ) -> Function {
+        Function::FnO {
+            fno_identifier:   function.to_string(),
+            param_func_pairs: params
+                .into_iter()
+                .map(|(param, func)| (param.to_string(), func))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_builtin_functions() {
+        use vocab::grel::{FUNCTION, PARAMETER};
+        let mapping = HashMap::new();
+
+        let upper = fno(
+            FUNCTION::TO_UPPER_CASE,
+            vec![(PARAMETER::VALUE, constant("venus"))],
+        );
+        assert_eq!(upper.evaluate(&mapping), Ok("VENUS".into()));
+
+        let replace = fno(
+            FUNCTION::STRING_REPLACE,
+            vec![
+                (PARAMETER::VALUE, constant("a-b-c")),
+                (PARAMETER::STRING_FIND, constant("-")),
+                (PARAMETER::STRING_REPLACE, constant("+")),
+            ],
+        );
+        assert_eq!(replace.evaluate(&mapping), Ok("a+b+c".into()));
+
+        let split = Arc::new(fno(
+            FUNCTION::STRING_SPLIT,
+            vec![
+                (PARAMETER::VALUE, constant("a b c")),
+                (PARAMETER::STRING_SEP, constant(" ")),
+            ],
+        ));
+        assert_eq!(
+            split.evaluate(&mapping),
+            Ok(Value::from(vec!["a", "b", "c"]))
+        );
+
+        let join = fno(
+            FUNCTION::ARRAY_JOIN,
+            vec![
+                (PARAMETER::ARRAY_A, split),
+                (PARAMETER::STRING_SEP, constant(",")),
+            ],
+        );
+        assert_eq!(join.evaluate(&mapping), Ok("a,b,c".into()));
+
+        let substring = fno(
+            FUNCTION::STRING_SUBSTRING,
+            vec![
+                (PARAMETER::VALUE, constant("Venus Williams")),
+                (PARAMETER::INT_FROM, constant("6")),
+            ],
+        );
+        assert_eq!(substring.evaluate(&mapping), Ok("Williams".into()));
+
+        let normalize = fno(
+            vocab::idlab_fn::FUNCTION::NORMALIZE_DATE,
+            vec![
+                (vocab::idlab_fn::PARAMETER::STR_DATE, constant("17/06/1980")),
+                (vocab::idlab_fn::PARAMETER::PATTERN, constant("dd/MM/yyyy")),
+            ],
+        );
+        assert_eq!(normalize.evaluate(&mapping), Ok("1980-06-17".into()));
+    }
+
+    #[test]
+    fn test_parameter_checking() {
+        use vocab::grel::{FUNCTION, PARAMETER};
+        let registry = FunctionRegistry::builtins();
+        let substring = FUNCTION::STRING_SUBSTRING.to_string();
+
+        assert_eq!(
+            registry.call(&substring, &HashMap::new()),
+            Err(FunctionError::MissingParameter {
+                function:  substring.clone(),
+                parameter: PARAMETER::VALUE.to_string(),
+            })
+        );
+
+        let arguments = HashMap::from([
+            (PARAMETER::VALUE.to_string(), Value::from("Venus")),
+            (PARAMETER::INT_FROM.to_string(), Value::from("first")),
+        ]);
+        assert_eq!(
+            registry.call(&substring, &arguments),
+            Err(FunctionError::InvalidParameter {
+                function:  substring.clone(),
+                parameter: PARAMETER::INT_FROM.to_string(),
+                value:     "first".to_string(),
+                expected:  ParameterType::Integer,
+            })
+        );
+
+        let arguments = HashMap::from([(
+            PARAMETER::BOOL_B.to_string(),
+            Value::from("true"),
+        )]);
+        assert_eq!(
+            registry.call(&FUNCTION::BOOLEAN_NOT.to_string(), &arguments),
+            Ok(Value::Boolean(false))
+        );
+    }
+
+    #[test]
+    fn test_custom_function() {
+        let iri = "http://example.com/fn/reverse";
+        let param = "http://example.com/fn/value";
+        let mut registry = FunctionRegistry::with_builtins();
+        registry.register(FunctionDefinition::new(
+            iri,
+            vec![Parameter::required(param, ParameterType::String)],
+            |args| {
+                let value = string_arg(args, 0).unwrap_or_default();
+                Ok(Value::String(value.chars().rev().collect()))
+            },
+        ));
+
+        let function = Function::FnO {
+            fno_identifier:   iri.to_string(),
+            param_func_pairs: BTreeMap::from([(
+                param.to_string(),
+                constant("abc"),
+            )]),
+        };
+        assert_eq!(
+            function.evaluate_with(&HashMap::new(), &registry),
+            Ok("cba".into())
+        );
+        assert_eq!(
+            function.evaluate(&HashMap::new()),
+            Err(FunctionError::UnsupportedFunction(iri.to_string()))
+        );
+    }
+}
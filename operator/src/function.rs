@@ -0,0 +1,792 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::datatype::canonical_lexical_form;
+use crate::error::FunctionError;
+use crate::fno::FunctionRegistry;
+use crate::tuples::SolutionMapping;
+use crate::value::{Literal, Value};
+use crate::{ArcExtendFunction, Function};
+
+type EvalResult = Result<String, FunctionError>;
+
+impl Function {
+    /// Evaluates the function against the given solution mapping.
+    ///
+    /// The RDF term functions (`Iri`, `Literal` and `BlankNode`) validate
+    /// their inner values and evaluate to the corresponding RDF term
+    /// variants of [Value]. When nested in other functions, only the IRI,
+    /// the lexical form of the literal or the blank node identifier is used.
+    /// Relative IRIs are prefixed with the base IRI of the `Iri` function,
+    /// as the base IRI is concatenated instead of resolved in R2RML.
+    ///
+    /// Literals with an XSD datatype are written in the canonical lexical
+    /// form of the datatype, and ill-typed literals are errors.
+    ///
+    /// [Function::FnO] functions are called through the built-in
+    /// [FunctionRegistry], use [Function::evaluate_with] to call custom
+    /// functions.
+    pub fn evaluate(
+        &self,
+        mapping: &SolutionMapping,
+    ) -> Result<Value, FunctionError> {
+        self.evaluate_with(mapping, FunctionRegistry::builtins())
+    }
+
+    /// Evaluates the function against the given solution mapping, calling
+    /// the [Function::FnO] functions through the given registry.
+    pub fn evaluate_with(
+        &self,
+        mapping: &SolutionMapping,
+        registry: &FunctionRegistry,
+    ) -> Result<Value, FunctionError> {
+        let value = match self {
+            Function::Iri { .. } => {
+                Value::Iri(self.evaluate_string(mapping, registry)?)
+            }
+            Function::BlankNode { .. } => {
+                Value::BlankNode(self.evaluate_string(mapping, registry)?)
+            }
+            Function::Literal {
+                dtype_function,
+                langtype_function,
+                ..
+            } => {
+                let lexical_form = self.evaluate_string(mapping, registry)?;
+                let literal = if let Some(lang_func) = langtype_function {
+                    Literal::language_tagged(
+                        &lexical_form,
+                        &lang_func.evaluate_string(mapping, registry)?,
+                    )
+                } else if let Some(dtype_func) = dtype_function {
+                    Literal::typed(
+                        &lexical_form,
+                        &dtype_func.evaluate_string(mapping, registry)?,
+                    )
+                } else {
+                    Literal::simple(&lexical_form)
+                };
+                Value::Literal(literal)
+            }
+            Function::FnO {
+                fno_identifier,
+                param_func_pairs,
+            } => {
+                call_function(
+                    fno_identifier,
+                    param_func_pairs,
+                    mapping,
+                    registry,
+                )?
+            }
+            _ => Value::String(self.evaluate_string(mapping, registry)?),
+        };
+
+        Ok(value)
+    }
+
+    /// Returns the attributes of the solution mapping which the function
+    /// refers to, either directly or through template placeholders.
+    pub fn referenced_attributes(&self) -> HashSet<String> {
+        match self {
+            Function::Reference { value } => HashSet::from([value.clone()]),
+            Function::TemplateString { value } => template_variables(value),
+            Function::Constant { .. } => HashSet::new(),
+            Function::TemplateFunctionValue {
+                variable_function_pairs,
+                ..
+            } => {
+                variable_function_pairs
+                    .iter()
+                    .flat_map(|(_, func)| func.referenced_attributes())
+                    .collect()
+            }
+            Function::Concatenate {
+                left_value,
+                right_value,
+                ..
+            } => {
+                let mut attributes = left_value.referenced_attributes();
+                attributes.extend(right_value.referenced_attributes());
+                attributes
+            }
+            Function::Literal {
+                inner_function,
+                dtype_function,
+                langtype_function,
+            } => {
+                let mut attributes = inner_function.referenced_attributes();
+                for func in dtype_function.iter().chain(langtype_function) {
+                    attributes.extend(func.referenced_attributes());
+                }
+                attributes
+            }
+            Function::Replace { inner_function, .. }
+            | Function::UriEncode { inner_function }
+            | Function::Iri { inner_function, .. }
+            | Function::BlankNode { inner_function }
+            | Function::Upper { inner_function }
+            | Function::Lower { inner_function } => {
+                inner_function.referenced_attributes()
+            }
+            Function::FnO {
+                param_func_pairs, ..
+            } => {
+                param_func_pairs
+                    .values()
+                    .flat_map(|func| func.referenced_attributes())
+                    .collect()
+            }
+        }
+    }
+
+    /// Evaluates the function like [evaluate_string], but makes the values
+    /// substituted into templates IRI-safe. Constants, references and the
+    /// results of the other functions already denote complete IRIs, hence
+    /// they are left as they are (R2RML semantics).
+    ///
+    /// [evaluate_string]: Function::evaluate_string
+    fn evaluate_iri_safe(
+        &self,
+        mapping: &SolutionMapping,
+        registry: &FunctionRegistry,
+    ) -> EvalResult {
+        match self {
+            Function::TemplateString { value } => {
+                fill_template(value, &|attr| {
+                    lookup_attribute(attr, mapping).map(|val| iri_safe(&val))
+                })
+            }
+            Function::TemplateFunctionValue {
+                template,
+                variable_function_pairs,
+            } => {
+                fill_template(template, &|variable| {
+                    bound_function(variable, variable_function_pairs)?
+                        .evaluate_string(mapping, registry)
+                        .map(|val| iri_safe(&val))
+                })
+            }
+            Function::Concatenate {
+                left_value,
+                separator,
+                right_value,
+            } => {
+                Ok(format!(
+                    "{}{}{}",
+                    left_value.evaluate_iri_safe(mapping, registry)?,
+                    separator,
+                    right_value.evaluate_iri_safe(mapping, registry)?
+                ))
+            }
+            Function::Upper { inner_function } => {
+                Ok(inner_function
+                    .evaluate_iri_safe(mapping, registry)?
+                    .to_uppercase())
+            }
+            Function::Lower { inner_function } => {
+                Ok(inner_function
+                    .evaluate_iri_safe(mapping, registry)?
+                    .to_lowercase())
+            }
+            _ => self.evaluate_string(mapping, registry),
+        }
+    }
+
+    fn evaluate_string(
+        &self,
+        mapping: &SolutionMapping,
+        registry: &FunctionRegistry,
+    ) -> EvalResult {
+        match self {
+            Function::Reference { value } => lookup_attribute(value, mapping),
+            Function::Constant { value } => Ok(value.clone()),
+            Function::TemplateString { value } => {
+                fill_template(value, &|attr| lookup_attribute(attr, mapping))
+            }
+            Function::TemplateFunctionValue {
+                template,
+                variable_function_pairs,
+            } => {
+                fill_template(template, &|variable| {
+                    bound_function(variable, variable_function_pairs)?
+                        .evaluate_string(mapping, registry)
+                })
+            }
+            Function::Concatenate {
+                left_value,
+                separator,
+                right_value,
+            } => {
+                Ok(format!(
+                    "{}{}{}",
+                    left_value.evaluate_string(mapping, registry)?,
+                    separator,
+                    right_value.evaluate_string(mapping, registry)?
+                ))
+            }
+            Function::Replace {
+                replace_map,
+                inner_function,
+            } => {
+                let value =
+                    inner_function.evaluate_string(mapping, registry)?;
+                let replacements: Vec<_> = replace_map
+                    .iter()
+                    .filter(|(_, matches)| matches.contains(&value))
+                    .map(|(replacement, _)| replacement.clone())
+                    .collect();
+
+                match &replacements[..] {
+                    [] => Ok(value),
+                    [replacement] => Ok(replacement.clone()),
+                    _ => {
+                        Err(FunctionError::AmbiguousReplacement {
+                            value,
+                            replacements,
+                        })
+                    }
+                }
+            }
+            Function::UriEncode { inner_function } => {
+                inner_function.evaluate_iri_safe(mapping, registry)
+            }
+            Function::Iri {
+                inner_function,
+                base_iri,
+            } => {
+                let mut iri =
+                    inner_function.evaluate_string(mapping, registry)?;
+                if let Some(base_iri) =
+                    base_iri.as_ref().filter(|_| !has_iri_scheme(&iri))
+                {
+                    iri = format!("{}{}", base_iri, iri);
+                }
+                if !is_valid_iri(&iri) {
+                    return Err(FunctionError::InvalidIri(iri));
+                }
+                Ok(iri)
+            }
+            Function::Literal {
+                inner_function,
+                dtype_function,
+                langtype_function,
+            } => {
+                if let Some(lang_func) = langtype_function {
+                    let lang = lang_func.evaluate_string(mapping, registry)?;
+                    if !is_valid_language_tag(&lang) {
+                        return Err(FunctionError::InvalidLanguageTag(lang));
+                    }
+                } else if let Some(dtype_func) = dtype_function {
+                    let dtype =
+                        dtype_func.evaluate_string(mapping, registry)?;
+                    if !is_valid_iri(&dtype) {
+                        return Err(FunctionError::InvalidIri(dtype));
+                    }
+                    let lexical_form =
+                        inner_function.evaluate_string(mapping, registry)?;
+                    return canonical_lexical_form(&lexical_form, &dtype);
+                }
+
+                inner_function.evaluate_string(mapping, registry)
+            }
+            Function::BlankNode { inner_function } => {
+                let label =
+                    inner_function.evaluate_string(mapping, registry)?;
+                if label.is_empty() || label.chars().any(char::is_whitespace) {
+                    return Err(FunctionError::InvalidBlankNode(label));
+                }
+                Ok(label)
+            }
+            Function::Upper { inner_function } => {
+                Ok(inner_function
+                    .evaluate_string(mapping, registry)?
+                    .to_uppercase())
+            }
+            Function::Lower { inner_function } => {
+                Ok(inner_function
+                    .evaluate_string(mapping, registry)?
+                    .to_lowercase())
+            }
+            Function::FnO {
+                fno_identifier,
+                param_func_pairs,
+            } => {
+                let value = call_function(
+                    fno_identifier,
+                    param_func_pairs,
+                    mapping,
+                    registry,
+                )?;
+                Ok(String::from(&value))
+            }
+        }
+    }
+}
+
+/// Calls the FnO function with the values of the parameter functions.
+/// Parameters whose functions refer to missing or null attributes are
+/// absent, and if the function requires them the attribute error is
+/// returned.
+fn call_function(
+    fno_identifier: &str,
+    param_func_pairs: &BTreeMap<String, Arc<Function>>,
+    mapping: &SolutionMapping,
+    registry: &FunctionRegistry,
+) -> Result<Value, FunctionError> {
+    let mut arguments = HashMap::new();
+    let mut absent = HashMap::new();
+    for (param, func) in param_func_pairs {
+        match func.evaluate_with(mapping, registry) {
+            Ok(value) => {
+                arguments.insert(param.clone(), value);
+            }
+            Err(
+                err @ (FunctionError::MissingAttribute(_)
+                | FunctionError::NullAttribute(_)),
+            ) => {
+                absent.insert(param.clone(), err);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    registry.call(fno_identifier, &arguments).map_err(|err| {
+        match err {
+            FunctionError::MissingParameter { ref parameter, .. } => {
+                absent.remove(parameter).unwrap_or(err)
+            }
+            err => err,
+        }
+    })
+}
+
+/// Returns the function bound to the variable of a template.
+fn bound_function<'a>(
+    variable: &str,
+    variable_function_pairs: &'a [(String, ArcExtendFunction)],
+) -> Result<&'a Function, FunctionError> {
+    variable_function_pairs
+        .iter()
+        .find(|(var, _)| var == variable)
+        .map(|(_, func)| func.as_ref())
+        .ok_or(FunctionError::UnboundTemplateVariable(variable.to_string()))
+}
+
+fn lookup_attribute(attribute: &str, mapping: &SolutionMapping) -> EvalResult {
+    match mapping.get(attribute) {
+        None => Err(FunctionError::MissingAttribute(attribute.to_string())),
+        Some(Value::Null) => {
+            Err(FunctionError::NullAttribute(attribute.to_string()))
+        }
+        Some(value) => Ok(value.into()),
+    }
+}
+
+/// Substitutes every `{variable}` in the template with the value returned by
+/// the given lookup function. Escaped curly braces (`\{` and `\}`) are kept
+/// as literal braces.
+fn fill_template(
+    template: &str,
+    lookup: &dyn Fn(&str) -> EvalResult,
+) -> EvalResult {
+    let mut result = String::new();
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    result.push(escaped);
+                }
+            }
+            '{' => {
+                let mut variable = String::new();
+                let mut terminated = false;
+                for inner in chars.by_ref() {
+                    if inner == '}' {
+                        terminated = true;
+                        break;
+                    }
+                    variable.push(inner);
+                }
+
+                if !terminated {
+                    return Err(FunctionError::MalformedTemplate(
+                        template.to_string(),
+                    ));
+                }
+                result.push_str(&lookup(&variable)?);
+            }
+            c => result.push(c),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Collects the placeholder variables of the template. Malformed templates
+/// yield the variables found before the unterminated placeholder.
+fn template_variables(template: &str) -> HashSet<String> {
+    let variables = RefCell::new(HashSet::new());
+    let _ = fill_template(template, &|variable| {
+        variables.borrow_mut().insert(variable.to_string());
+        Ok(String::new())
+    });
+    variables.into_inner()
+}
+
+/// Percent-encodes every character which is not in the `iunreserved`
+/// production of RFC 3987.
+fn iri_safe(value: &str) -> String {
+    let mut result = String::new();
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric() || "-._~".contains(c) || !c.is_ascii() {
+            result.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                result.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    result
+}
+
+/// Checks whether the IRI starts with a scheme, i.e. whether it is absolute.
+fn has_iri_scheme(iri: &str) -> bool {
+    match iri.split_once(':') {
+        Some((scheme, _)) => {
+            let mut scheme_chars = scheme.chars();
+            scheme_chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+                && scheme_chars
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        }
+        None => false,
+    }
+}
+
+/// Checks that the IRI is absolute (it starts with a scheme) and doesn't
+/// contain any characters which are not allowed in IRIs.
+pub fn is_valid_iri(iri: &str) -> bool {
+    has_iri_scheme(iri)
+        && !iri
+            .chars()
+            .any(|c| c.is_whitespace() || "<>\"{}|^`\\".contains(c))
+}
+
+/// Checks that the language tag is well-formed according to the BCP 47
+/// grammar. The primary language subtag has 2 to 8 letters unless the tag is
+/// a private use (`x-`) or grandfathered (`i-`) tag, and every singleton
+/// subtag has to be followed by at least one more subtag.
+pub fn is_valid_language_tag(tag: &str) -> bool {
+    let subtags: Vec<&str> = tag.split('-').collect();
+    let subtags_valid = subtags.iter().all(|subtag| {
+        (1..=8).contains(&subtag.len())
+            && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+    let primary_valid = subtags.first().is_some_and(|primary| {
+        let singleton = primary.eq_ignore_ascii_case("x")
+            || primary.eq_ignore_ascii_case("i");
+        primary.chars().all(|c| c.is_ascii_alphabetic())
+            && ((2..=8).contains(&primary.len()) || singleton)
+    });
+    let singletons_valid = subtags
+        .iter()
+        .enumerate()
+        .all(|(idx, subtag)| subtag.len() != 1 || idx + 1 < subtags.len());
+
+    subtags_valid && primary_valid && singletons_valid
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    fn mapping() -> SolutionMapping {
+        HashMap::from([
+            ("id".to_string(), Value::String("10".to_string())),
+            (
+                "name".to_string(),
+                Value::String("Venus Williams".to_string()),
+            ),
+            ("sport".to_string(), Value::Null),
+        ])
+    }
+
+    fn reference(attr: &str) -> Arc<Function> {
+        Arc::new(Function::Reference {
+            value: attr.to_string(),
+        })
+    }
+
+    fn constant(value: &str) -> Arc<Function> {
+        Arc::new(Function::Constant {
+            value: value.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_reference_errors() {
+        let missing = Function::Reference {
+            value: "age".to_string(),
+        };
+        assert_eq!(
+            missing.evaluate(&mapping()),
+            Err(FunctionError::MissingAttribute("age".to_string()))
+        );
+
+        let null = Function::Reference {
+            value: "sport".to_string(),
+        };
+        assert_eq!(
+            null.evaluate(&mapping()),
+            Err(FunctionError::NullAttribute("sport".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_template_functions() {
+        let template = Function::TemplateString {
+            value: "http://ex.com/{id}/\\{{name}\\}".to_string(),
+        };
+        assert_eq!(
+            template.evaluate(&mapping()),
+            Ok(Value::String(
+                "http://ex.com/10/{Venus Williams}".to_string()
+            ))
+        );
+
+        let encoded = Function::Iri {
+            inner_function: Arc::new(Function::UriEncode {
+                inner_function: Arc::new(Function::TemplateString {
+                    value: "http://ex.com/{name}".to_string(),
+                }),
+            }),
+            base_iri:       None,
+        };
+        assert_eq!(
+            encoded.evaluate(&mapping()),
+            Ok(Value::Iri("http://ex.com/Venus%20Williams".to_string()))
+        );
+
+        let malformed = Function::TemplateString {
+            value: "http://ex.com/{id".to_string(),
+        };
+        assert!(matches!(
+            malformed.evaluate(&mapping()),
+            Err(FunctionError::MalformedTemplate(_))
+        ));
+
+        let func_value = Function::TemplateFunctionValue {
+            template:                "http://ex.com/{func_value}".to_string(),
+            variable_function_pairs: vec![(
+                "func_value".to_string(),
+                Arc::new(Function::Upper {
+                    inner_function: reference("name"),
+                }),
+            )],
+        };
+        assert_eq!(
+            func_value.evaluate(&mapping()),
+            Ok(Value::String("http://ex.com/VENUS WILLIAMS".to_string()))
+        );
+
+        let encoded_func_value = Function::UriEncode {
+            inner_function: Arc::new(Function::Concatenate {
+                left_value:  Arc::new(func_value),
+                separator:   "/".to_string(),
+                right_value: reference("name"),
+            }),
+        };
+        assert_eq!(
+            encoded_func_value.evaluate(&mapping()),
+            Ok(Value::String(
+                "http://ex.com/VENUS%20WILLIAMS/Venus Williams".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_string_functions() {
+        let concatenate = Function::Concatenate {
+            left_value:  reference("id"),
+            separator:   "_".to_string(),
+            right_value: Arc::new(Function::Lower {
+                inner_function: reference("name"),
+            }),
+        };
+        assert_eq!(
+            concatenate.evaluate(&mapping()),
+            Ok(Value::String("10_venus williams".to_string()))
+        );
+
+        let replace = Function::Replace {
+            replace_map:    BTreeMap::from([(
+                "Tennis player".to_string(),
+                BTreeSet::from(["Venus Williams".to_string()]),
+            )]),
+            inner_function: reference("name"),
+        };
+        assert_eq!(
+            replace.evaluate(&mapping()),
+            Ok(Value::String("Tennis player".to_string()))
+        );
+
+        let ambiguous_replace = Function::Replace {
+            replace_map:    BTreeMap::from([
+                (
+                    "Tennis player".to_string(),
+                    BTreeSet::from(["Venus Williams".to_string()]),
+                ),
+                (
+                    "Athlete".to_string(),
+                    BTreeSet::from(["Venus Williams".to_string()]),
+                ),
+            ]),
+            inner_function: reference("name"),
+        };
+        assert_eq!(
+            ambiguous_replace.evaluate(&mapping()),
+            Err(FunctionError::AmbiguousReplacement {
+                value:        "Venus Williams".to_string(),
+                replacements: vec![
+                    "Athlete".to_string(),
+                    "Tennis player".to_string()
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_rdf_term_functions() {
+        let invalid_iri = Function::Iri {
+            inner_function: reference("name"),
+            base_iri:       None,
+        };
+        assert_eq!(
+            invalid_iri.evaluate(&mapping()),
+            Err(FunctionError::InvalidIri("Venus Williams".to_string()))
+        );
+
+        let relative_iri = |attr: &str| {
+            Function::Iri {
+                inner_function: reference(attr),
+                base_iri:       Some("http://ex.com/base/".to_string()),
+            }
+        };
+        assert_eq!(
+            relative_iri("id").evaluate(&mapping()),
+            Ok(Value::Iri("http://ex.com/base/10".to_string()))
+        );
+        let absolute_iri = Function::Iri {
+            inner_function: constant("http://ex.com/10"),
+            base_iri:       Some("http://ex.com/base/".to_string()),
+        };
+        assert_eq!(
+            absolute_iri.evaluate(&mapping()),
+            Ok(Value::Iri("http://ex.com/10".to_string()))
+        );
+        assert_eq!(
+            relative_iri("name").evaluate(&mapping()),
+            Err(FunctionError::InvalidIri(
+                "http://ex.com/base/Venus Williams".to_string()
+            ))
+        );
+
+        let literal = Function::Literal {
+            inner_function:    reference("name"),
+            dtype_function:    None,
+            langtype_function: Some(constant("en-GB")),
+        };
+        assert_eq!(
+            literal.evaluate(&mapping()),
+            Ok(Value::Literal(Literal::language_tagged(
+                "Venus Williams",
+                "en-GB"
+            )))
+        );
+
+        let invalid_lang = Function::Literal {
+            inner_function:    reference("name"),
+            dtype_function:    None,
+            langtype_function: Some(constant("en GB")),
+        };
+        assert_eq!(
+            invalid_lang.evaluate(&mapping()),
+            Err(FunctionError::InvalidLanguageTag("en GB".to_string()))
+        );
+        for tag in ["e", "x", "en-", "en--GB", "de-a", "en-toolongsubtag"] {
+            assert!(!is_valid_language_tag(tag), "{}", tag);
+        }
+        for tag in ["en", "english", "zh-Hant-TW", "x-private", "i-klingon"] {
+            assert!(is_valid_language_tag(tag), "{}", tag);
+        }
+
+        let typed = |lexical_form: &str, datatype: &str| {
+            Function::Literal {
+                inner_function:    constant(lexical_form),
+                dtype_function:    Some(constant(datatype)),
+                langtype_function: None,
+            }
+        };
+        let xsd_integer = "http://www.w3.org/2001/XMLSchema#integer";
+        assert_eq!(
+            typed("007", xsd_integer).evaluate(&mapping()),
+            Ok(Value::Literal(Literal::typed("7", xsd_integer)))
+        );
+        assert_eq!(
+            typed("7.5", xsd_integer).evaluate(&mapping()),
+            Err(FunctionError::IllTypedLiteral {
+                lexical_form: "7.5".to_string(),
+                datatype:     xsd_integer.to_string(),
+            })
+        );
+
+        let invalid_bnode = Function::BlankNode {
+            inner_function: reference("name"),
+        };
+        assert_eq!(
+            invalid_bnode.evaluate(&mapping()),
+            Err(FunctionError::InvalidBlankNode(
+                "Venus Williams".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_referenced_attributes() {
+        let func = Function::Iri {
+            inner_function: Arc::new(Function::Concatenate {
+                left_value:  Arc::new(Function::TemplateString {
+                    value: "http://ex.com/{id}/\\{escaped\\}".to_string(),
+                }),
+                separator:   "/".to_string(),
+                right_value: reference("name"),
+            }),
+            base_iri:       None,
+        };
+
+        assert_eq!(
+            func.referenced_attributes(),
+            HashSet::from(["id".to_string(), "name".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_fno_unsupported() {
+        let fno = Function::FnO {
+            fno_identifier:   "http://example.com/idlab/fn/toUpper".to_string(),
+            param_func_pairs: BTreeMap::new(),
+        };
+        assert_eq!(
+            fno.evaluate(&mapping()),
+            Err(FunctionError::UnsupportedFunction(
+                "http://example.com/idlab/fn/toUpper".to_string()
+            ))
+        );
+    }
+}
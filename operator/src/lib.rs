@@ -1,21 +1,36 @@
+mod aggregate;
+pub mod datatype;
 pub mod display;
+pub mod error;
+pub mod fno;
 pub mod formats;
+mod function;
+mod ordering;
+mod predicate;
+mod sort;
+pub mod template;
 mod test_util;
 pub mod tuples;
+mod unnest;
 pub mod value;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
+use std::sync::Arc;
 
 use anyhow::Result;
 use display::{JsonDisplay, PrettyDisplay};
 use formats::{DataFormat, ReferenceFormulation};
+pub use function::is_valid_language_tag;
+pub use predicate::CompiledPredicate;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use template::QuadPattern;
 
 pub type RcOperator = Rc<Operator>;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum Operator {
     SourceOp { config: Source },
@@ -26,6 +41,12 @@ pub enum Operator {
     SerializerOp { config: Serializer },
     TargetOp { config: Target },
     FragmentOp { config: Fragmenter },
+    FilterOp { config: Filter },
+    UnionOp { config: Union },
+    AggregateOp { config: Aggregate },
+    UnnestOp { config: Unnest },
+    DistinctOp { config: Distinct },
+    SortOp { config: Sort },
 }
 
 impl JsonDisplay for Operator {
@@ -61,6 +82,24 @@ impl PrettyDisplay for Operator {
             Operator::FragmentOp { config } => {
                 ("Fragment Operator".to_string(), config.pretty_string()?)
             }
+            Operator::FilterOp { config } => {
+                ("Filter Operator".to_string(), config.pretty_string()?)
+            }
+            Operator::UnionOp { config } => {
+                ("Union Operator".to_string(), config.pretty_string()?)
+            }
+            Operator::AggregateOp { config } => {
+                ("Aggregate Operator".to_string(), config.pretty_string()?)
+            }
+            Operator::UnnestOp { config } => {
+                ("Unnest Operator".to_string(), config.pretty_string()?)
+            }
+            Operator::DistinctOp { config } => {
+                ("Distinct Operator".to_string(), config.pretty_string()?)
+            }
+            Operator::SortOp { config } => {
+                ("Sort Operator".to_string(), config.pretty_string()?)
+            }
         };
 
         Ok(format!("{}\n{}", title_string, content_string))
@@ -81,7 +120,36 @@ where
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Serializes the map in key order, such that equal operators are always
+/// written identically.
+fn serialize_sorted_map<S, K, V>(
+    hash_map: &HashMap<K, V>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    K: Serialize + Ord,
+    V: Serialize,
+{
+    let sorted: BTreeMap<_, _> = hash_map.iter().collect();
+    sorted.serialize(serializer)
+}
+
+/// Serializes the set in sorted order, such that equal operators are always
+/// written identically.
+fn serialize_sorted_set<S, V>(
+    hash_set: &HashSet<V>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    V: Serialize + Ord,
+{
+    let sorted: BTreeSet<_> = hash_set.iter().collect();
+    sorted.serialize(serializer)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Field {
     pub alias:                 String,
     pub reference:             String,
@@ -89,7 +157,9 @@ pub struct Field {
     pub inner_fields:          Vec<Field>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default,
+)]
 pub struct Iterator {
     pub reference:             Option<String>,
     pub reference_formulation: ReferenceFormulation,
@@ -104,9 +174,9 @@ impl Hash for Iterator {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Source {
-    #[serde(flatten)]
+    #[serde(flatten, serialize_with = "serialize_sorted_map")]
     pub config:      HashMap<String, String>,
     pub source_type: IOType,
     pub root_iterator:    Iterator,
@@ -119,7 +189,9 @@ impl PrettyDisplay for Source {
              ",
             self.source_type,
             self.root_iterator,
-            serde_json::to_string_pretty(&self.config)?
+            serde_json::to_string_pretty(
+                &self.config.iter().collect::<BTreeMap<_, _>>()
+            )?
         );
         Ok(result)
     }
@@ -135,67 +207,299 @@ impl Hash for Source {
 
 // Join operators
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
 pub enum JoinType {
     LeftJoin,
     RightJoin,
+    FullOuterJoin,
     InnerJoin,
     CrossJoin,
     NaturalJoin,
+    /// Keeps the left solution mappings with at least one match, without
+    /// the attributes of the right input.
+    SemiJoin,
+    /// Keeps the left solution mappings without any match, without the
+    /// attributes of the right input.
+    AntiJoin,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
 pub enum PredicateType {
     Greater,
     GEqual,
     Less,
     LEqual,
     Equal,
+    NotEqual,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Join {
-    pub left_right_attr_pairs: Vec<(String, String)>,
-    pub join_type:             JoinType,
-    pub predicate_type:        PredicateType,
-    pub join_alias:            String,
+    pub join_conditions: Vec<JoinCondition>,
+    pub join_type:       JoinType,
+    pub join_alias:      String,
+}
+
+/// Compares an attribute of the left input with an attribute of the right
+/// input of a join.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
+pub struct JoinCondition {
+    pub left_attribute:  String,
+    pub right_attribute: String,
+    pub predicate_type:  PredicateType,
+}
+
+impl JoinCondition {
+    pub fn equal(left_attribute: &str, right_attribute: &str) -> Self {
+        Self {
+            left_attribute:  left_attribute.to_string(),
+            right_attribute: right_attribute.to_string(),
+            predicate_type:  PredicateType::Equal,
+        }
+    }
 }
 
 impl Default for Join {
     fn default() -> Self {
         Self {
-            left_right_attr_pairs: Default::default(),
-            join_type:             JoinType::InnerJoin,
-            predicate_type:        PredicateType::Equal,
-            join_alias:            "pseudo_other".to_string(),
+            join_conditions: Default::default(),
+            join_type:       JoinType::InnerJoin,
+            join_alias:      "pseudo_other".to_string(),
         }
     }
 }
 
 impl Hash for Join {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.left_right_attr_pairs.hash(state);
+        self.join_conditions.hash(state);
         self.join_type.hash(state);
-        self.predicate_type.hash(state);
     }
 }
 
 impl PrettyDisplay for Join {
     fn pretty_string(&self) -> Result<String> {
+        let conditions = self
+            .join_conditions
+            .iter()
+            .map(|condition| {
+                format!(
+                    "{} {} {}",
+                    condition.left_attribute,
+                    condition.predicate_type.symbol(),
+                    condition.right_attribute
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
         let result = format!(
-            "type: {:?}\npredicate_type: {:?}\nattribute_pairs: {}\nptm_alias: {}",
-            self.join_type,
-            self.predicate_type,
-            serde_json::to_string_pretty(&self.left_right_attr_pairs)?, 
-            self.join_alias
+            "type: {:?}\nconditions: {}\nptm_alias: {}",
+            self.join_type, conditions, self.join_alias
         );
 
         Ok(result)
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Bag union of the solution sequences of two or more input branches.
+/// The input edges of the union are labelled with the union alias.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
+pub struct Union {
+    pub union_alias: String,
+}
+
+impl PrettyDisplay for Union {
+    fn pretty_string(&self) -> Result<String> {
+        Ok(format!("union_alias: {}", self.union_alias))
+    }
+}
+
+/// Expands a multi-valued attribute into one solution mapping per value.
+/// The other attributes of the solution mapping are kept, and the keys of
+/// nested object values become `{attribute}.{key}` attributes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
+pub struct Unnest {
+    pub attribute: String,
+}
+
+impl PrettyDisplay for Unnest {
+    fn pretty_string(&self) -> Result<String> {
+        Ok(format!("attribute: {}", self.attribute))
+    }
+}
+
+// Aggregation operators
+
+/// Groups the solution mappings by the values of the group-by attributes
+/// and computes the aggregate functions for every group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Aggregate {
+    pub group_by:        Vec<String>,
+    #[serde(serialize_with = "serialize_sorted_map")]
+    pub aggregate_pairs: HashMap<String, AggregateFunction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
+#[serde(tag = "type")]
+pub enum AggregateFunction {
+    CollectList { attribute: String },
+    Count { attribute: Option<String> },
+    Min { attribute: String },
+    Max { attribute: String },
+    StringJoin { attribute: String, separator: String },
+}
+
+impl PrettyDisplay for Aggregate {
+    fn pretty_string(&self) -> Result<String> {
+        let pairs_string = self
+            .aggregate_pairs
+            .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(attribute, func)| format!("{} -> {:?}", attribute, func))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        Ok(format!(
+            "Group by: {}\nAggregate pairs:\n {}",
+            self.group_by.join(", "),
+            pairs_string
+        ))
+    }
+}
+
+impl Hash for Aggregate {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.group_by.hash(state);
+        hash_hashmap(&self.aggregate_pairs, state);
+    }
+}
+
+// Solution modifiers
+
+/// Removes the duplicate solution mappings, keeping the first occurrence.
+/// Two solution mappings are duplicates if they have the same values for
+/// the distinct attributes, or for all of their attributes if no distinct
+/// attributes are given.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Hash,
+    Default,
+)]
+pub struct Distinct {
+    pub attributes: Option<Vec<String>>,
+}
+
+impl PrettyDisplay for Distinct {
+    fn pretty_string(&self) -> Result<String> {
+        let attributes = match &self.attributes {
+            Some(attributes) => attributes.join(", "),
+            None => "*".to_string(),
+        };
+        Ok(format!("attributes: {}", attributes))
+    }
+}
+
+/// Orders the solution mappings by the sort keys, where later keys break
+/// the ties of the earlier ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
+pub struct Sort {
+    pub sort_keys: Vec<SortKey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
+pub struct SortKey {
+    pub attribute: String,
+    #[serde(default)]
+    pub order:     SortOrder,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Hash,
+    Default,
+)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl PrettyDisplay for Sort {
+    fn pretty_string(&self) -> Result<String> {
+        let keys = self
+            .sort_keys
+            .iter()
+            .map(|key| format!("{} {:?}", key.attribute, key.order))
+            .collect::<Vec<String>>()
+            .join(", ");
+        Ok(format!("sort keys: {}", keys))
+    }
+}
+
+// Selection operators
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Filter {
+    pub predicate: Predicate,
+}
+
+impl PrettyDisplay for Filter {
+    fn pretty_string(&self) -> Result<String> {
+        Ok(format!("Predicate: {}", self.predicate))
+    }
+}
+
+/// Boolean expression over the attributes of a solution mapping.
+/// The operands are evaluated as [Function] trees, so both plain attributes
+/// ([Function::Reference]) and computed values can be compared.
+///
+/// Like SPARQL filters, predicates are evaluated with three-valued logic:
+/// comparisons and regex matches with a null operand are unknown, unknown
+/// stays unknown under `Not` and the connectives follow the SQL/SPARQL truth
+/// tables. Solution mappings for which the predicate is unknown don't
+/// satisfy it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum Predicate {
+    Compare {
+        left:           Function,
+        predicate_type: PredicateType,
+        right:          Function,
+    },
+    IsNull {
+        function: Function,
+    },
+    RegexMatch {
+        function: Function,
+        pattern:  String,
+    },
+    And {
+        predicates: Vec<Predicate>,
+    },
+    Or {
+        predicates: Vec<Predicate>,
+    },
+    Not {
+        predicate: Box<Predicate>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Projection {
+    #[serde(serialize_with = "serialize_sorted_set")]
     pub projection_attributes: HashSet<String>,
 }
 
@@ -204,6 +508,8 @@ impl PrettyDisplay for Projection {
         let attributes = self
             .projection_attributes
             .iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
             .fold(String::new(), |acc, val| acc + val + ", ");
 
         Ok(format!("Projected attributes: {}", attributes))
@@ -212,15 +518,15 @@ impl PrettyDisplay for Projection {
 
 impl Hash for Projection {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        for val in self.projection_attributes.iter() {
+        for val in self.projection_attributes.iter().collect::<BTreeSet<_>>() {
             val.hash(state);
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Rename {
-    #[serde(flatten)]
+    #[serde(flatten, serialize_with = "serialize_sorted_map")]
     pub rename_pairs: HashMap<String, String>,
 }
 
@@ -229,6 +535,8 @@ impl PrettyDisplay for Rename {
         let pairs_string = self
             .rename_pairs
             .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
             .map(|kv_pair| format!("{} -> {}", kv_pair.0, kv_pair.1))
             .collect::<Vec<String>>()
             .join("\n");
@@ -243,9 +551,9 @@ impl Hash for Rename {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Extend {
-    #[serde(flatten)]
+    #[serde(flatten, serialize_with = "serialize_sorted_map")]
     pub extend_pairs: HashMap<String, Function>,
 }
 
@@ -267,6 +575,8 @@ impl PrettyDisplay for Extend {
         let vec_pairs: Vec<_> = self
             .extend_pairs
             .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
             .map(|pair| format!("{} -> {:?}", pair.0, pair.1))
             .collect();
 
@@ -274,14 +584,14 @@ impl PrettyDisplay for Extend {
     }
 }
 
-pub type RcExtendFunction = Rc<Function>;
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub type ArcExtendFunction = Arc<Function>;
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum Function {
     Concatenate{
-        left_value: RcExtendFunction,
+        left_value: ArcExtendFunction,
         separator: String, 
-        right_value: RcExtendFunction,
+        right_value: ArcExtendFunction,
     },
     Reference {
         value: String,
@@ -294,57 +604,93 @@ pub enum Function {
     },
 
     Replace{
-        replace_map:    HashMap<String, HashSet<String>>,
-        inner_function: RcExtendFunction, 
+        replace_map:    BTreeMap<String, BTreeSet<String>>,
+        inner_function: ArcExtendFunction, 
     },
 
     TemplateFunctionValue{
         template: String, 
-        variable_function_pairs: Vec<(String, RcExtendFunction)>, 
+        variable_function_pairs: Vec<(String, ArcExtendFunction)>, 
     },
 
     UriEncode {
-        inner_function: RcExtendFunction,
+        inner_function: ArcExtendFunction,
     },
     Iri {
-        inner_function: RcExtendFunction,
+        inner_function: ArcExtendFunction,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base_iri: Option<String>,
     },
     Literal {
-        inner_function: RcExtendFunction,
-        dtype_function: Option<RcExtendFunction>, 
-        langtype_function: Option<RcExtendFunction>,
+        inner_function: ArcExtendFunction,
+        dtype_function: Option<ArcExtendFunction>, 
+        langtype_function: Option<ArcExtendFunction>,
     },
     BlankNode {
-        inner_function: RcExtendFunction,
+        inner_function: ArcExtendFunction,
     },
     Upper {
-        inner_function: RcExtendFunction,
+        inner_function: ArcExtendFunction,
     },
     Lower {
-        inner_function: RcExtendFunction,
+        inner_function: ArcExtendFunction,
     },
     FnO {
         fno_identifier:   String,
         #[serde(flatten)]
-        param_func_pairs: HashMap<String, RcExtendFunction>,
+        param_func_pairs: BTreeMap<String, ArcExtendFunction>,
     },
 }
 
 // Post-mapping operators
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Serializer {
-    pub template: String,
-    #[serde(flatten)]
+    #[serde(deserialize_with = "template::deserialize_template")]
+    #[schemars(with = "Vec<QuadPattern>")]
+    pub template: Vec<QuadPattern>,
+    #[serde(
+        flatten,
+        serialize_with = "serialize_serializer_options",
+        deserialize_with = "deserialize_serializer_options"
+    )]
     pub options:  Option<HashMap<String, String>>,
     pub format:   DataFormat,
 }
 
+fn serialize_serializer_options<S>(
+    options: &Option<HashMap<String, String>>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let sorted: Option<BTreeMap<_, _>> =
+        options.as_ref().map(|options| options.iter().collect());
+    sorted.serialize(serializer)
+}
+
+/// Flattened options are always present when deserializing, so an empty
+/// set of options is read back as `None`.
+fn deserialize_serializer_options<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<HashMap<String, String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let options = HashMap::<String, String>::deserialize(deserializer)?;
+    Ok(Some(options).filter(|options| !options.is_empty()))
+}
+
 impl PrettyDisplay for Serializer {
     fn pretty_string(&self) -> Result<String> {
         let format_type = format!("Format type: {:?}", self.format);
 
-        Ok(format!("{}\nTemplate: {}", format_type, self.template))
+        Ok(format!(
+            "{}\nTemplate: {}",
+            format_type,
+            self.template_string()
+        ))
     }
 }
 
@@ -358,7 +704,7 @@ impl Hash for Serializer {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
 pub enum IOType {
     StdOut,
     File,
@@ -375,9 +721,9 @@ impl Default for IOType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Target {
-    #[serde(flatten)]
+    #[serde(flatten, serialize_with = "serialize_sorted_map")]
     pub configuration: HashMap<String, String>,
     pub target_type:   IOType,
     pub data_format:   DataFormat,
@@ -390,7 +736,9 @@ impl PrettyDisplay for Target {
              ",
             self.target_type,
             self.data_format,
-            serde_json::to_string_pretty(&self.configuration)?
+            serde_json::to_string_pretty(
+                &self.configuration.iter().collect::<BTreeMap<_, _>>()
+            )?
         );
         Ok(result)
     }
@@ -404,7 +752,7 @@ impl Hash for Target {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash)]
 pub struct Fragmenter {
     pub from: String,
     pub to:   Vec<String>,
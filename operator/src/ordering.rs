@@ -0,0 +1,397 @@
+use std::cmp::Ordering;
+
+use crate::datatype::XsdDatatype;
+use crate::value::{Literal, LiteralTag, Number, Value};
+
+/// Numeric value after promotion: integers are compared exactly, all the
+/// other numbers are promoted to doubles.
+#[derive(Debug, Clone, Copy)]
+enum Numeric {
+    Integer(i128),
+    Double(f64),
+}
+
+impl Numeric {
+    fn from_number(number: &Number) -> Self {
+        match number {
+            Number::PosInfinity => Numeric::Double(f64::INFINITY),
+            Number::NegInfinity => Numeric::Double(f64::NEG_INFINITY),
+            Number::Double(double) => Numeric::Double(*double),
+            Number::Float(float) => Numeric::Double((*float).into()),
+            Number::Decimal(decimal) => {
+                Numeric::Double(decimal.parse().unwrap_or(f64::NAN))
+            }
+            Number::Byte(byte) => Numeric::Integer((*byte).into()),
+            Number::Short(short) => Numeric::Integer((*short).into()),
+            Number::UShort(ushort) => Numeric::Integer((*ushort).into()),
+            Number::Int(int) => Numeric::Integer((*int).into()),
+            Number::UInt(uint) => Numeric::Integer((*uint).into()),
+        }
+    }
+
+    /// Untyped strings are numbers if they are valid integers or doubles.
+    fn from_untyped(untyped: &str) -> Option<Self> {
+        if !untyped.chars().any(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        [XsdDatatype::Integer, XsdDatatype::Double]
+            .iter()
+            .find_map(|datatype| datatype.coerce(untyped).ok())
+            .and_then(|value| {
+                match value {
+                    Value::Number(number) => Some(Self::from_number(&number)),
+                    _ => None,
+                }
+            })
+    }
+
+    fn to_double(self) -> f64 {
+        match self {
+            Numeric::Integer(integer) => integer as f64,
+            Numeric::Double(double) => double,
+        }
+    }
+
+    fn partial_cmp(self, other: Self) -> Option<Ordering> {
+        match (self, other) {
+            (Numeric::Integer(left), Numeric::Integer(right)) => {
+                Some(left.cmp(&right))
+            }
+            (left, right) => left.to_double().partial_cmp(&right.to_double()),
+        }
+    }
+
+    fn total_cmp(self, other: Self) -> Ordering {
+        match (self, other) {
+            (Numeric::Integer(left), Numeric::Integer(right)) => {
+                left.cmp(&right)
+            }
+            (left, right) => left.to_double().total_cmp(&right.to_double()),
+        }
+    }
+}
+
+/// View of a value as an operand of the SPARQL operator mapping.
+#[derive(Debug, Clone)]
+enum Operand<'a> {
+    Null,
+    BlankNode(&'a str),
+    Iri(&'a str),
+    Numeric(Numeric),
+    Boolean(bool),
+    /// Plain strings from the data sources, which are untyped and compared
+    /// as numbers against numeric values.
+    Untyped(&'a str),
+    Text(&'a str),
+    LangString(&'a str, String),
+    Typed(&'a str, String),
+    Other,
+}
+
+impl<'a> Operand<'a> {
+    fn new(value: &'a Value) -> Self {
+        match value {
+            Value::Null => Operand::Null,
+            Value::BlankNode(label) => Operand::BlankNode(label),
+            Value::Iri(iri) => Operand::Iri(iri),
+            Value::Number(number) => {
+                Operand::Numeric(Numeric::from_number(number))
+            }
+            Value::Boolean(boolean) => Operand::Boolean(*boolean),
+            Value::String(string) => Operand::Untyped(string),
+            Value::Literal(literal) => Self::from_literal(literal),
+            Value::Array(_) | Value::Object(_) => Operand::Other,
+        }
+    }
+
+    /// Ill-typed literals and literals of other datatypes are only
+    /// comparable to literals of the same datatype, by their lexical forms.
+    fn from_literal(literal: &'a Literal) -> Self {
+        let lexical_form = literal.lexical_form.as_str();
+        let datatype = match &literal.tag {
+            LiteralTag::Language(language) => {
+                return Operand::LangString(
+                    lexical_form,
+                    language.to_lowercase(),
+                )
+            }
+            LiteralTag::Datatype(datatype) => datatype,
+        };
+
+        let typed_value = XsdDatatype::from_iri(datatype)
+            .and_then(|xsd_datatype| xsd_datatype.coerce(lexical_form).ok());
+        match typed_value {
+            Some(Value::String(_)) => Operand::Text(lexical_form),
+            Some(Value::Boolean(boolean)) => Operand::Boolean(boolean),
+            Some(Value::Number(number)) => {
+                Operand::Numeric(Numeric::from_number(&number))
+            }
+            _ => Operand::Typed(lexical_form, datatype.clone()),
+        }
+    }
+
+    /// Ranks the operands in the order of the SPARQL `ORDER BY` clause:
+    /// unbound values, blank nodes, IRIs and then literals. Literals are
+    /// ranked by kind, with untyped strings holding a number ranked as
+    /// numbers.
+    fn rank(&self) -> u8 {
+        match self {
+            Operand::Null => 0,
+            Operand::BlankNode(_) => 1,
+            Operand::Iri(_) => 2,
+            Operand::Numeric(_) => 3,
+            Operand::Untyped(untyped) => {
+                if Numeric::from_untyped(untyped).is_some() {
+                    3
+                } else {
+                    5
+                }
+            }
+            Operand::Boolean(_) => 4,
+            Operand::Text(_) => 5,
+            Operand::LangString(..) => 6,
+            Operand::Typed(..) => 7,
+            Operand::Other => 8,
+        }
+    }
+
+    fn numeric(&self) -> Option<Numeric> {
+        match self {
+            Operand::Numeric(numeric) => Some(*numeric),
+            Operand::Untyped(untyped) => Numeric::from_untyped(untyped),
+            _ => None,
+        }
+    }
+
+    fn text(&self) -> Option<&str> {
+        match self {
+            Operand::Untyped(text) | Operand::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+impl Value {
+    /// Compares the values following the SPARQL operator mapping.
+    ///
+    /// Numbers are compared by value across all the [Number] variants and
+    /// numeric literals, so `Int(5)` and `Short(5)` are equal. Untyped
+    /// strings are compared as numbers against numbers, and two untyped
+    /// strings holding numbers are ordered numerically with ties broken by
+    /// their text, such that they are only equal if their text is equal.
+    /// Booleans order `false` before `true`, strings and literals of the
+    /// same language or datatype are ordered by their lexical forms, and
+    /// IRIs and blank nodes by their identifiers.
+    ///
+    /// Returns [None] for values which can't be compared, like null values,
+    /// NaN or values of different kinds, for which SPARQL raises a type
+    /// error.
+    pub fn partial_compare(&self, other: &Value) -> Option<Ordering> {
+        let (left, right) = (Operand::new(self), Operand::new(other));
+
+        match (&left, &right) {
+            (Operand::Null, _) | (_, Operand::Null) => None,
+            (Operand::Untyped(left_text), Operand::Untyped(right_text)) => {
+                match (left.numeric(), right.numeric()) {
+                    (Some(left_num), Some(right_num)) => {
+                        left_num.partial_cmp(right_num).map(|ordering| {
+                            ordering.then(left_text.cmp(right_text))
+                        })
+                    }
+                    _ => Some(left_text.cmp(right_text)),
+                }
+            }
+            (Operand::Numeric(_), _) | (_, Operand::Numeric(_)) => {
+                left.numeric()?.partial_cmp(right.numeric()?)
+            }
+            (Operand::Boolean(left), Operand::Boolean(right)) => {
+                Some(left.cmp(right))
+            }
+            (
+                Operand::LangString(left, left_tag),
+                Operand::LangString(right, right_tag),
+            )
+            | (
+                Operand::Typed(left, left_tag),
+                Operand::Typed(right, right_tag),
+            ) if left_tag == right_tag => Some(left.cmp(right)),
+            (Operand::Iri(left), Operand::Iri(right))
+            | (Operand::BlankNode(left), Operand::BlankNode(right)) => {
+                Some(left.cmp(right))
+            }
+            (Operand::Other, Operand::Other) => {
+                (self == other).then_some(Ordering::Equal)
+            }
+            _ => Some(left.text()?.cmp(right.text()?)),
+        }
+    }
+
+    /// Orders all the values, e.g. for sorting.
+    ///
+    /// Values are ranked like in the SPARQL `ORDER BY` clause: null values
+    /// come first, followed by blank nodes, IRIs, numbers, booleans,
+    /// strings, language-tagged strings, literals of other datatypes and
+    /// finally arrays and objects. Values of the same rank are ordered as
+    /// in [partial_compare](Value::partial_compare), where NaN is ordered
+    /// after all the other numbers and equal numbers are ordered by their
+    /// string forms.
+    pub fn total_cmp(&self, other: &Value) -> Ordering {
+        let (left, right) = (Operand::new(self), Operand::new(other));
+
+        left.rank().cmp(&right.rank()).then_with(|| {
+            match (&left, &right) {
+                (
+                    Operand::LangString(left, left_tag),
+                    Operand::LangString(right, right_tag),
+                )
+                | (
+                    Operand::Typed(left, left_tag),
+                    Operand::Typed(right, right_tag),
+                ) => left_tag.cmp(right_tag).then(left.cmp(right)),
+                _ => {
+                    let numeric_ordering =
+                        match (left.numeric(), right.numeric()) {
+                            (Some(left_num), Some(right_num)) => {
+                                left_num.total_cmp(right_num)
+                            }
+                            _ => Ordering::Equal,
+                        };
+                    numeric_ordering
+                        .then_with(|| {
+                            self.partial_compare(other)
+                                .unwrap_or(Ordering::Equal)
+                        })
+                        .then_with(|| {
+                            String::from(self).cmp(&String::from(other))
+                        })
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn untyped(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    fn typed(lexical_form: &str, datatype: XsdDatatype) -> Value {
+        Value::Literal(Literal::typed(lexical_form, &datatype.iri()))
+    }
+
+    #[test]
+    fn test_numeric_promotion() {
+        let five = Value::Number(Number::Int(5));
+        assert_eq!(
+            five.partial_compare(&Value::Number(Number::Short(5))),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            five.partial_compare(&Value::Number(Number::Double(4.5))),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            five.partial_compare(&typed("05.0", XsdDatatype::Decimal)),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            five.partial_compare(&Value::Number(Number::PosInfinity)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(five.partial_compare(&untyped("10")), Some(Ordering::Less));
+        assert_eq!(
+            five.partial_compare(&Value::Number(Number::Double(f64::NAN))),
+            None
+        );
+        assert_eq!(five.partial_compare(&untyped("five")), None);
+    }
+
+    #[test]
+    fn test_untyped_strings() {
+        assert_eq!(
+            untyped("10").partial_compare(&untyped("9")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            untyped("5.0").partial_compare(&untyped("5")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            untyped("Venus").partial_compare(&untyped("10")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            untyped("Serena")
+                .partial_compare(&typed("Venus", XsdDatatype::String)),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn test_incomparable_values() {
+        let iri = Value::Iri("http://example.com/Venus".to_string());
+        assert_eq!(iri.partial_compare(&untyped("Venus")), None);
+        assert_eq!(untyped("true").partial_compare(&true.into()), None);
+        assert_eq!(Value::Null.partial_compare(&Value::Null), None);
+        assert_eq!(
+            Value::Literal(Literal::language_tagged("Venus", "en"))
+                .partial_compare(&Value::Literal(Literal::language_tagged(
+                    "Venus", "nl"
+                ))),
+            None
+        );
+        assert_eq!(
+            Value::Literal(Literal::language_tagged("Venus", "EN"))
+                .partial_compare(&Value::Literal(Literal::language_tagged(
+                    "Venus", "en"
+                ))),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            Value::Boolean(false)
+                .partial_compare(&typed("1", XsdDatatype::Boolean)),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn test_total_order() {
+        let mut values = vec![
+            Value::Literal(Literal::language_tagged("Venus", "en")),
+            untyped("Venus"),
+            Value::Boolean(true),
+            untyped("10"),
+            Value::Iri("http://example.com/Venus".to_string()),
+            Value::Number(Number::Double(f64::NAN)),
+            Value::Number(Number::Short(9)),
+            Value::BlankNode("venus".to_string()),
+            Value::Null,
+        ];
+        values.sort_by(|left, right| left.total_cmp(right));
+
+        // NaN isn't equal to itself, so the debug strings are compared.
+        let debug_strings = |values: Vec<Value>| {
+            values
+                .iter()
+                .map(|value| format!("{:?}", value))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            debug_strings(values),
+            debug_strings(vec![
+                Value::Null,
+                Value::BlankNode("venus".to_string()),
+                Value::Iri("http://example.com/Venus".to_string()),
+                Value::Number(Number::Short(9)),
+                untyped("10"),
+                Value::Number(Number::Double(f64::NAN)),
+                Value::Boolean(true),
+                untyped("Venus"),
+                Value::Literal(Literal::language_tagged("Venus", "en")),
+            ])
+        );
+    }
+}
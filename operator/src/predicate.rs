@@ -0,0 +1,444 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+use regex::Regex;
+
+use crate::error::FunctionError;
+use crate::fno::FunctionRegistry;
+use crate::tuples::SolutionMapping;
+use crate::value::Value;
+use crate::{Function, Predicate, PredicateType};
+
+impl PredicateType {
+    /// Compares the two values with [Value::partial_compare]. Values which
+    /// can't be compared are unequal and not ordered.
+    pub fn compare(&self, left: &Value, right: &Value) -> bool {
+        match (self, left.partial_compare(right)) {
+            (PredicateType::Equal, ordering) => {
+                ordering.is_some_and(|ord| ord.is_eq())
+            }
+            (PredicateType::NotEqual, ordering) => {
+                !ordering.is_some_and(|ord| ord.is_eq())
+            }
+            (_, None) => false,
+            (PredicateType::Greater, Some(ord)) => ord.is_gt(),
+            (PredicateType::GEqual, Some(ord)) => ord.is_ge(),
+            (PredicateType::Less, Some(ord)) => ord.is_lt(),
+            (PredicateType::LEqual, Some(ord)) => ord.is_le(),
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            PredicateType::Greater => ">",
+            PredicateType::GEqual => ">=",
+            PredicateType::Less => "<",
+            PredicateType::LEqual => "<=",
+            PredicateType::Equal => "=",
+            PredicateType::NotEqual => "!=",
+        }
+    }
+}
+
+/// A predicate with its regex patterns compiled, so that they are compiled
+/// once rather than for every evaluated solution mapping.
+#[derive(Debug, Clone)]
+pub struct CompiledPredicate<'a> {
+    predicate: &'a Predicate,
+    regexes:   HashMap<&'a str, Regex>,
+}
+
+impl Predicate {
+    /// Evaluates the predicate against the given solution mapping.
+    ///
+    /// Operands which refer to missing or null attributes are null, and
+    /// comparisons or regex matches with a null operand are unknown. The
+    /// predicate is only satisfied if it evaluates to true, so neither
+    /// `a = b` nor `NOT (a = b)` holds if `a` is null.
+    pub fn evaluate(
+        &self,
+        mapping: &SolutionMapping,
+    ) -> Result<bool, FunctionError> {
+        self.evaluate_with(mapping, FunctionRegistry::builtins())
+    }
+
+    /// Evaluates the predicate, calling the [Function::FnO] functions of the
+    /// operands through the given registry.
+    ///
+    /// The regex patterns are compiled on every call, use
+    /// [compile](Predicate::compile) to evaluate the predicate against many
+    /// solution mappings.
+    pub fn evaluate_with(
+        &self,
+        mapping: &SolutionMapping,
+        registry: &FunctionRegistry,
+    ) -> Result<bool, FunctionError> {
+        self.compile()?.evaluate_with(mapping, registry)
+    }
+
+    /// Compiles the regex patterns of the predicate, failing if any of them
+    /// is invalid.
+    pub fn compile(&self) -> Result<CompiledPredicate<'_>, FunctionError> {
+        let mut regexes = HashMap::new();
+        self.compile_regexes(&mut regexes)?;
+        Ok(CompiledPredicate {
+            predicate: self,
+            regexes,
+        })
+    }
+
+    fn compile_regexes<'a>(
+        &'a self,
+        regexes: &mut HashMap<&'a str, Regex>,
+    ) -> Result<(), FunctionError> {
+        match self {
+            Predicate::RegexMatch { pattern, .. } => {
+                if !regexes.contains_key(pattern.as_str()) {
+                    let regex = Regex::new(pattern).map_err(|_| {
+                        FunctionError::InvalidPattern(pattern.clone())
+                    })?;
+                    regexes.insert(pattern, regex);
+                }
+            }
+            Predicate::And { predicates } | Predicate::Or { predicates } => {
+                for predicate in predicates {
+                    predicate.compile_regexes(regexes)?;
+                }
+            }
+            Predicate::Not { predicate } => {
+                predicate.compile_regexes(regexes)?
+            }
+            Predicate::Compare { .. } | Predicate::IsNull { .. } => {}
+        }
+        Ok(())
+    }
+
+    pub fn referenced_attributes(&self) -> HashSet<String> {
+        match self {
+            Predicate::Compare { left, right, .. } => {
+                let mut attributes = left.referenced_attributes();
+                attributes.extend(right.referenced_attributes());
+                attributes
+            }
+            Predicate::IsNull { function }
+            | Predicate::RegexMatch { function, .. } => {
+                function.referenced_attributes()
+            }
+            Predicate::And { predicates } | Predicate::Or { predicates } => {
+                predicates
+                    .iter()
+                    .flat_map(|predicate| predicate.referenced_attributes())
+                    .collect()
+            }
+            Predicate::Not { predicate } => predicate.referenced_attributes(),
+        }
+    }
+}
+
+impl CompiledPredicate<'_> {
+    /// Evaluates the predicate, calling the [Function::FnO] functions of the
+    /// operands through the given registry.
+    pub fn evaluate_with(
+        &self,
+        mapping: &SolutionMapping,
+        registry: &FunctionRegistry,
+    ) -> Result<bool, FunctionError> {
+        Ok(self
+            .evaluate_predicate(self.predicate, mapping, registry)?
+            .unwrap_or(false))
+    }
+
+    /// Evaluates the predicate to true, false or unknown (`None`).
+    fn evaluate_predicate(
+        &self,
+        predicate: &Predicate,
+        mapping: &SolutionMapping,
+        registry: &FunctionRegistry,
+    ) -> Result<Option<bool>, FunctionError> {
+        let operand = |function| operand(function, mapping, registry);
+        match predicate {
+            Predicate::Compare {
+                left,
+                predicate_type,
+                right,
+            } => {
+                match (operand(left)?, operand(right)?) {
+                    (Some(left), Some(right)) => {
+                        Ok(Some(predicate_type.compare(&left, &right)))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            Predicate::IsNull { function } => {
+                Ok(Some(operand(function)?.is_none()))
+            }
+            Predicate::RegexMatch { function, pattern } => {
+                let regex = &self.regexes[pattern.as_str()];
+                Ok(operand(function)?
+                    .map(|value| regex.is_match(&String::from(&value))))
+            }
+            Predicate::And { predicates } => {
+                let mut result = Some(true);
+                for predicate in predicates {
+                    match self
+                        .evaluate_predicate(predicate, mapping, registry)?
+                    {
+                        Some(false) => return Ok(Some(false)),
+                        Some(true) => {}
+                        None => result = None,
+                    }
+                }
+                Ok(result)
+            }
+            Predicate::Or { predicates } => {
+                let mut result = Some(false);
+                for predicate in predicates {
+                    match self
+                        .evaluate_predicate(predicate, mapping, registry)?
+                    {
+                        Some(true) => return Ok(Some(true)),
+                        Some(false) => {}
+                        None => result = None,
+                    }
+                }
+                Ok(result)
+            }
+            Predicate::Not { predicate } => {
+                Ok(self
+                    .evaluate_predicate(predicate, mapping, registry)?
+                    .map(|value| !value))
+            }
+        }
+    }
+}
+
+fn operand(
+    function: &Function,
+    mapping: &SolutionMapping,
+    registry: &FunctionRegistry,
+) -> Result<Option<Value>, FunctionError> {
+    match function.evaluate_with(mapping, registry) {
+        Ok(value) => Ok(Some(value)),
+        Err(FunctionError::MissingAttribute(_))
+        | Err(FunctionError::NullAttribute(_)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+impl Display for Predicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join_predicates = |predicates: &[Predicate], connective: &str| {
+            predicates
+                .iter()
+                .map(|predicate| predicate.to_string())
+                .collect::<Vec<_>>()
+                .join(connective)
+        };
+
+        match self {
+            Predicate::Compare {
+                left,
+                predicate_type,
+                right,
+            } => {
+                write!(f, "{:?} {} {:?}", left, predicate_type.symbol(), right)
+            }
+            Predicate::IsNull { function } => {
+                write!(f, "{:?} IS NULL", function)
+            }
+            Predicate::RegexMatch { function, pattern } => {
+                write!(f, "{:?} MATCHES {:?}", function, pattern)
+            }
+            Predicate::And { predicates } => {
+                write!(f, "({})", join_predicates(predicates, " AND "))
+            }
+            Predicate::Or { predicates } => {
+                write!(f, "({})", join_predicates(predicates, " OR "))
+            }
+            Predicate::Not { predicate } => write!(f, "NOT ({})", predicate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> SolutionMapping {
+        HashMap::from([
+            ("age".to_string(), Value::String("23".to_string())),
+            ("name".to_string(), Value::String("Venus".to_string())),
+            ("sport".to_string(), Value::Null),
+        ])
+    }
+
+    fn reference(attr: &str) -> Function {
+        Function::Reference {
+            value: attr.to_string(),
+        }
+    }
+
+    fn constant(value: &str) -> Function {
+        Function::Constant {
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compare_predicates() -> Result<(), FunctionError> {
+        let older = Predicate::Compare {
+            left:           reference("age"),
+            predicate_type: PredicateType::Greater,
+            right:          constant("9"),
+        };
+        assert!(older.evaluate(&mapping())?);
+
+        let missing = Predicate::Compare {
+            left:           reference("height"),
+            predicate_type: PredicateType::NotEqual,
+            right:          constant("180"),
+        };
+        assert!(!missing.evaluate(&mapping())?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_null_operands_are_unknown() -> Result<(), FunctionError> {
+        let compare = |attr: &str, predicate_type| {
+            Predicate::Compare {
+                left: reference(attr),
+                predicate_type,
+                right: constant("Tennis"),
+            }
+        };
+        let not = |predicate| {
+            Predicate::Not {
+                predicate: Box::new(predicate),
+            }
+        };
+
+        assert!(
+            !compare("sport", PredicateType::NotEqual).evaluate(&mapping())?
+        );
+        assert!(
+            !not(compare("sport", PredicateType::Equal)).evaluate(&mapping())?
+        );
+        assert!(
+            not(compare("name", PredicateType::Equal)).evaluate(&mapping())?
+        );
+
+        let unknown_or_true = Predicate::Or {
+            predicates: vec![
+                compare("sport", PredicateType::Equal),
+                compare("name", PredicateType::NotEqual),
+            ],
+        };
+        assert!(unknown_or_true.evaluate(&mapping())?);
+
+        let unknown_and_false = Predicate::And {
+            predicates: vec![
+                compare("sport", PredicateType::Equal),
+                compare("name", PredicateType::Equal),
+            ],
+        };
+        assert!(not(unknown_and_false).evaluate(&mapping())?);
+
+        let unknown_and_true = Predicate::And {
+            predicates: vec![
+                compare("sport", PredicateType::Equal),
+                compare("name", PredicateType::NotEqual),
+            ],
+        };
+        assert!(!not(unknown_and_true).evaluate(&mapping())?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_boolean_connectives() -> Result<(), FunctionError> {
+        let predicate = Predicate::And {
+            predicates: vec![
+                Predicate::IsNull {
+                    function: reference("sport"),
+                },
+                Predicate::Not {
+                    predicate: Box::new(Predicate::IsNull {
+                        function: reference("name"),
+                    }),
+                },
+                Predicate::Or {
+                    predicates: vec![
+                        Predicate::RegexMatch {
+                            function: reference("name"),
+                            pattern:  "^Ser".to_string(),
+                        },
+                        Predicate::RegexMatch {
+                            function: Function::Upper {
+                                inner_function: reference("name").into(),
+                            },
+                            pattern:  "^VEN".to_string(),
+                        },
+                    ],
+                },
+            ],
+        };
+
+        assert!(predicate.evaluate(&mapping())?);
+        assert_eq!(
+            predicate.referenced_attributes(),
+            HashSet::from(["sport".to_string(), "name".to_string()])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_json() -> Result<(), serde_json::Error> {
+        let filter_op = crate::Operator::FilterOp {
+            config: crate::Filter {
+                predicate: Predicate::IsNull {
+                    function: reference("sport"),
+                },
+            },
+        };
+
+        let json = serde_json::to_value(&filter_op)?;
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "FilterOp",
+                "config": {
+                    "predicate": {
+                        "type": "IsNull",
+                        "function": {"type": "Reference", "value": "sport"}
+                    }
+                }
+            })
+        );
+        assert_eq!(serde_json::from_value::<crate::Operator>(json)?, filter_op);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let predicate = Predicate::RegexMatch {
+            function: reference("name"),
+            pattern:  "(".to_string(),
+        };
+        assert_eq!(
+            predicate.evaluate(&mapping()),
+            Err(FunctionError::InvalidPattern("(".to_string()))
+        );
+
+        let predicate = Predicate::Or {
+            predicates: vec![
+                Predicate::IsNull {
+                    function: reference("name"),
+                },
+                predicate,
+            ],
+        };
+        assert!(matches!(
+            predicate.compile(),
+            Err(FunctionError::InvalidPattern(_))
+        ));
+    }
+}
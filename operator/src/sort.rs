@@ -0,0 +1,112 @@
+use std::cmp::Ordering;
+
+use crate::tuples::SolutionMapping;
+use crate::value::Value;
+use crate::{Sort, SortOrder};
+
+impl Sort {
+    /// Compares the solution mappings by their sort keys.
+    ///
+    /// Missing and null values are ordered before all the other values,
+    /// which are ordered by [Value::total_cmp].
+    pub fn compare(
+        &self,
+        left: &SolutionMapping,
+        right: &SolutionMapping,
+    ) -> Ordering {
+        for key in &self.sort_keys {
+            let left_value = non_null(left.get(&key.attribute));
+            let right_value = non_null(right.get(&key.attribute));
+
+            let ordering = match (left_value, right_value) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Less,
+                (Some(_), None) => Ordering::Greater,
+                (Some(l), Some(r)) => l.total_cmp(r),
+            };
+
+            let ordering = match key.order {
+                SortOrder::Ascending => ordering,
+                SortOrder::Descending => ordering.reverse(),
+            };
+            if ordering.is_ne() {
+                return ordering;
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
+fn non_null(value: Option<&Value>) -> Option<&Value> {
+    value.filter(|value| **value != Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortKey;
+
+    fn mapping(pairs: &[(&str, &str)]) -> SolutionMapping {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), Value::String(v.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let sort = Sort {
+            sort_keys: vec![
+                SortKey {
+                    attribute: "team".to_string(),
+                    order:     SortOrder::Ascending,
+                },
+                SortKey {
+                    attribute: "score".to_string(),
+                    order:     SortOrder::Descending,
+                },
+            ],
+        };
+
+        let mut mappings = vec![
+            mapping(&[("team", "B"), ("score", "9")]),
+            mapping(&[("team", "A"), ("score", "9")]),
+            mapping(&[("team", "A"), ("score", "10")]),
+            mapping(&[("score", "1")]),
+        ];
+        mappings.sort_by(|left, right| sort.compare(left, right));
+
+        assert_eq!(
+            mappings,
+            vec![
+                mapping(&[("score", "1")]),
+                mapping(&[("team", "A"), ("score", "10")]),
+                mapping(&[("team", "A"), ("score", "9")]),
+                mapping(&[("team", "B"), ("score", "9")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_json_default_order() -> Result<(), serde_json::Error> {
+        let sort_op: crate::Operator =
+            serde_json::from_value(serde_json::json!({
+                "type": "SortOp",
+                "config": {"sort_keys": [{"attribute": "team"}]}
+            }))?;
+
+        assert_eq!(
+            sort_op,
+            crate::Operator::SortOp {
+                config: Sort {
+                    sort_keys: vec![SortKey {
+                        attribute: "team".to_string(),
+                        order:     SortOrder::Ascending,
+                    }],
+                },
+            }
+        );
+        Ok(())
+    }
+}
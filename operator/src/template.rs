@@ -0,0 +1,478 @@
+//! Structured serializer templates.
+//!
+//! A [Serializer] template is a list of quad patterns, whose terms are
+//! variables, IRIs, blank nodes or literals. The patterns render to the
+//! N-Triples/N-Quads-like string form, e.g. `?s <http://ex.com/p> ?o@en .`,
+//! with the variables written as `?name`.
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::TemplateError;
+use crate::value::escape_literal;
+use crate::Serializer;
+
+/// The language tag or datatype IRI with which a literal is written.
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub enum LiteralAnnotation {
+    Language(String),
+    Datatype(String),
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(tag = "type")]
+pub enum TermPattern {
+    /// A variable, bound to the value of the attribute with the same name.
+    /// Annotated variables are written as literals with the language tag or
+    /// datatype of the annotation.
+    Variable {
+        name:       String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotation: Option<LiteralAnnotation>,
+    },
+    Iri {
+        iri: String,
+    },
+    BlankNode {
+        label: String,
+    },
+    Literal {
+        lexical_form: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotation:   Option<LiteralAnnotation>,
+    },
+}
+
+/// A triple pattern, or a quad pattern if it has a graph term.
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub struct QuadPattern {
+    pub subject:   TermPattern,
+    pub predicate: TermPattern,
+    pub object:    TermPattern,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph:     Option<TermPattern>,
+}
+
+impl TermPattern {
+    pub fn variable(name: &str) -> TermPattern {
+        TermPattern::Variable {
+            name:       name.to_string(),
+            annotation: None,
+        }
+    }
+
+    pub fn iri(iri: &str) -> TermPattern {
+        TermPattern::Iri {
+            iri: iri.to_string(),
+        }
+    }
+
+    pub fn variable_name(&self) -> Option<&str> {
+        match self {
+            TermPattern::Variable { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Renders the term, writing the variables with the given binding.
+    /// Returns `None` if a variable is unbound.
+    pub fn instantiate<F>(&self, binding: &F) -> Option<String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let (term, annotation) = match self {
+            TermPattern::Variable { name, annotation } => {
+                (binding(name)?, annotation)
+            }
+            TermPattern::Iri { iri } => return Some(format!("<{}>", iri)),
+            TermPattern::BlankNode { label } => {
+                return Some(format!("_:{}", label))
+            }
+            TermPattern::Literal {
+                lexical_form,
+                annotation,
+            } => (format!("\"{}\"", escape_literal(lexical_form)), annotation),
+        };
+
+        let rendered = match annotation {
+            None => term,
+            Some(LiteralAnnotation::Language(language)) => {
+                format!("{}@{}", term, language)
+            }
+            Some(LiteralAnnotation::Datatype(datatype)) => {
+                format!("{}^^<{}>", term, datatype)
+            }
+        };
+        Some(rendered)
+    }
+}
+
+impl QuadPattern {
+    pub fn triple(
+        subject: TermPattern,
+        predicate: TermPattern,
+        object: TermPattern,
+    ) -> QuadPattern {
+        QuadPattern {
+            subject,
+            predicate,
+            object,
+            graph: None,
+        }
+    }
+
+    pub fn terms(&self) -> impl Iterator<Item = &TermPattern> {
+        [&self.subject, &self.predicate, &self.object]
+            .into_iter()
+            .chain(self.graph.as_ref())
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.terms().filter_map(TermPattern::variable_name)
+    }
+
+    /// Renders the pattern as an N-Triples/N-Quads statement, writing the
+    /// variables with the given binding. Returns `None` if a variable is
+    /// unbound.
+    pub fn instantiate<F>(&self, binding: &F) -> Option<String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let terms = self
+            .terms()
+            .map(|term| term.instantiate(binding))
+            .collect::<Option<Vec<_>>>()?;
+        Some(format!("{} .", terms.join(" ")))
+    }
+}
+
+fn variable_binding(name: &str) -> Option<String> {
+    Some(format!("?{}", name))
+}
+
+impl Display for TermPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.instantiate(&variable_binding).unwrap())
+    }
+}
+
+impl Display for QuadPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.instantiate(&variable_binding).unwrap())
+    }
+}
+
+impl Serializer {
+    /// Renders the template to its string form, one pattern per line.
+    pub fn template_string(&self) -> String {
+        self.template
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns the names of the variables used in the template.
+    pub fn variables(&self) -> HashSet<String> {
+        self.template
+            .iter()
+            .flat_map(|pattern| pattern.variables())
+            .map(|variable| variable.to_string())
+            .collect()
+    }
+}
+
+/// Parses the string form of a template, with one pattern per line. Empty
+/// lines are skipped and the terminating `.` of a pattern is optional.
+pub fn parse_template(
+    template: &str,
+) -> Result<Vec<QuadPattern>, TemplateError> {
+    template
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(parse_pattern)
+        .collect()
+}
+
+fn parse_pattern(line: &str) -> Result<QuadPattern, TemplateError> {
+    let malformed = |reason: &str| {
+        TemplateError::MalformedPattern {
+            pattern: line.to_string(),
+            reason:  reason.to_string(),
+        }
+    };
+
+    let mut chars = line.chars().peekable();
+    let mut terms = Vec::new();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.peek() {
+            None => break,
+            Some('.') => {
+                chars.next();
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if chars.peek().is_some() {
+                    return Err(malformed("unexpected text after the '.'"));
+                }
+                break;
+            }
+            Some(_) => terms.push(parse_term(&mut chars).map_err(malformed)?),
+        }
+    }
+
+    let mut terms = terms.into_iter();
+    match (terms.next(), terms.next(), terms.next(), terms.next()) {
+        (Some(subject), Some(predicate), Some(object), graph)
+            if terms.next().is_none() =>
+        {
+            Ok(QuadPattern {
+                subject,
+                predicate,
+                object,
+                graph,
+            })
+        }
+        _ => Err(malformed("expected three or four terms")),
+    }
+}
+
+fn parse_term(
+    chars: &mut Peekable<Chars>,
+) -> Result<TermPattern, &'static str> {
+    let term = match chars.next() {
+        Some('?') => {
+            let name = take_name(chars);
+            if name.is_empty() {
+                return Err("a variable has no name");
+            }
+            TermPattern::Variable {
+                name,
+                annotation: parse_annotation(chars)?,
+            }
+        }
+        Some('<') => {
+            TermPattern::Iri {
+                iri: take_iri(chars)?,
+            }
+        }
+        Some('_') if chars.next_if_eq(&':').is_some() => {
+            let label = take_name(chars);
+            if label.is_empty() {
+                return Err("a blank node has no label");
+            }
+            TermPattern::BlankNode { label }
+        }
+        Some('"') => {
+            let mut lexical_form = String::new();
+            loop {
+                match chars.next() {
+                    None => return Err("a literal is unterminated"),
+                    Some('"') => break,
+                    Some('\\') => {
+                        lexical_form.push(unescape(chars.next())?);
+                    }
+                    Some(c) => lexical_form.push(c),
+                }
+            }
+            TermPattern::Literal {
+                lexical_form,
+                annotation: parse_annotation(chars)?,
+            }
+        }
+        _ => return Err("expected a variable, IRI, blank node or literal"),
+    };
+
+    match chars.peek() {
+        Some(c) if !c.is_whitespace() && *c != '.' => {
+            Err("terms must be separated by whitespace")
+        }
+        _ => Ok(term),
+    }
+}
+
+fn parse_annotation(
+    chars: &mut Peekable<Chars>,
+) -> Result<Option<LiteralAnnotation>, &'static str> {
+    if chars.next_if_eq(&'@').is_some() {
+        let mut language = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '-')
+        {
+            language.push(c);
+        }
+        if language.is_empty() {
+            return Err("a language tag is empty");
+        }
+        return Ok(Some(LiteralAnnotation::Language(language)));
+    }
+
+    if chars.next_if_eq(&'^').is_some() {
+        if chars.next_if_eq(&'^').is_none() || chars.next_if_eq(&'<').is_none()
+        {
+            return Err("a datatype must be written as ^^<iri>");
+        }
+        return Ok(Some(LiteralAnnotation::Datatype(take_iri(chars)?)));
+    }
+
+    Ok(None)
+}
+
+fn take_name(chars: &mut Peekable<Chars>) -> String {
+    let mut name = String::new();
+    while let Some(c) =
+        chars.next_if(|c| c.is_alphanumeric() || "_-".contains(*c))
+    {
+        name.push(c);
+    }
+    name
+}
+
+/// Reads the IRI up to the closing `>`, the opening `<` is already read.
+fn take_iri(chars: &mut Peekable<Chars>) -> Result<String, &'static str> {
+    let mut iri = String::new();
+    for c in chars.by_ref() {
+        if c == '>' {
+            return Ok(iri);
+        }
+        iri.push(c);
+    }
+    Err("an IRI is unterminated")
+}
+
+fn unescape(c: Option<char>) -> Result<char, &'static str> {
+    match c {
+        Some('t') => Ok('\t'),
+        Some('b') => Ok('\u{8}'),
+        Some('n') => Ok('\n'),
+        Some('r') => Ok('\r'),
+        Some('f') => Ok('\u{c}'),
+        Some(c @ ('"' | '\'' | '\\')) => Ok(c),
+        _ => Err("a literal has an invalid escape sequence"),
+    }
+}
+
+/// Serializer templates used to be a newline-joined string of patterns,
+/// which are still accepted and parsed when deserializing.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TemplateForm {
+    Patterns(Vec<QuadPattern>),
+    String(String),
+}
+
+pub fn deserialize_template<'de, D>(
+    deserializer: D,
+) -> Result<Vec<QuadPattern>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match TemplateForm::deserialize(deserializer)? {
+        TemplateForm::Patterns(patterns) => Ok(patterns),
+        TemplateForm::String(template) => {
+            parse_template(&template).map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_and_parse() {
+        let template = "?tm0_sm <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://ex.com/Person> ?tm0_sm_gm0 .\n\
+                        ?tm0_sm ?tm0_p0_0 ?tm0_o0_0@en .\n\
+                        ?s <http://ex.com/p?q> \"what? \\\"x\\\"\"^^<http://ex.com/dt> .\n\
+                        _:b0 <http://ex.com/p> ?o-1 .";
+        let patterns = parse_template(template).unwrap();
+
+        assert_eq!(
+            patterns[1],
+            QuadPattern::triple(
+                TermPattern::variable("tm0_sm"),
+                TermPattern::variable("tm0_p0_0"),
+                TermPattern::Variable {
+                    name:       "tm0_o0_0".to_string(),
+                    annotation: Some(LiteralAnnotation::Language(
+                        "en".to_string()
+                    )),
+                },
+            )
+        );
+        assert_eq!(
+            patterns[0].graph,
+            Some(TermPattern::variable("tm0_sm_gm0"))
+        );
+
+        let rendered: Vec<_> =
+            patterns.iter().map(|pattern| pattern.to_string()).collect();
+        assert_eq!(rendered.join("\n"), template);
+    }
+
+    #[test]
+    fn test_malformed_patterns() {
+        for line in [
+            "?s <http://ex.com/p> .",
+            "?s <http://ex.com/p> ?o ?g ?h .",
+            "?s <http://ex.com/p ?o .",
+            "?s <http://ex.com/p> \"o .",
+            "?s <http://ex.com/p>?o .",
+            "?s <http://ex.com/p> ?o . ?x",
+            "? <http://ex.com/p> ?o .",
+        ] {
+            assert!(
+                matches!(
+                    parse_template(line),
+                    Err(TemplateError::MalformedPattern { .. })
+                ),
+                "{} should be malformed",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_serializer_variables() {
+        let serializer = Serializer {
+            template: parse_template(
+                "?s <http://ex.com/p?q> \"what?\" .\n?s ?p_0 ?o-1 .",
+            )
+            .unwrap(),
+            options:  None,
+            format:   crate::formats::DataFormat::NTriples,
+        };
+        assert_eq!(
+            serializer.variables(),
+            HashSet::from([
+                "s".to_string(),
+                "p_0".to_string(),
+                "o-1".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_deserialize_string_template() {
+        let json = r#"{"template": "?s <http://ex.com/p> ?o .", "format": "NTriples"}"#;
+        let serializer: Serializer = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            serializer.template,
+            vec![QuadPattern::triple(
+                TermPattern::variable("s"),
+                TermPattern::iri("http://ex.com/p"),
+                TermPattern::variable("o"),
+            )]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use crate::formats::ReferenceFormulation;
+use crate::{Field, Iterator};
+
+impl Field {
+    /// Checks if the field may yield more than one value per iteration,
+    /// either because it is a nested iterator or because its JSONPath
+    /// reference crosses an array boundary.
+    pub fn is_multi_valued(&self) -> bool {
+        if !self.inner_fields.is_empty() {
+            return true;
+        }
+
+        match self.reference_formulation {
+            ReferenceFormulation::JSONPath => {
+                let reference = self.reference.trim();
+                reference.contains("[*]")
+                    || reference.contains(".*")
+                    || reference.contains("..")
+                    || reference.contains("[?")
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Iterator {
+    /// Returns the attributes which have to be unnested, such that every
+    /// solution mapping holds a single value per attribute.
+    ///
+    /// Outer attributes precede the attributes of their inner fields, since
+    /// the latter only exist once the outer attribute has been unnested.
+    pub fn unnest_attributes(&self) -> Vec<String> {
+        let mut attributes = Vec::new();
+        collect_unnest_attributes(&self.fields, &self.alias, &mut attributes);
+        attributes
+    }
+}
+
+fn collect_unnest_attributes(
+    fields: &[Field],
+    prefix: &Option<String>,
+    attributes: &mut Vec<String>,
+) {
+    for field in fields {
+        let attribute = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, field.alias),
+            None => field.alias.clone(),
+        };
+
+        if field.is_multi_valued() {
+            attributes.push(attribute.clone());
+        }
+        collect_unnest_attributes(
+            &field.inner_fields,
+            &Some(attribute),
+            attributes,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json_field(alias: &str, reference: &str, inner: Vec<Field>) -> Field {
+        Field {
+            alias:                 alias.to_string(),
+            reference:             reference.to_string(),
+            reference_formulation: ReferenceFormulation::JSONPath,
+            inner_fields:          inner,
+        }
+    }
+
+    #[test]
+    fn test_multi_valued_fields() {
+        assert!(!json_field("name", "name", vec![]).is_multi_valued());
+        assert!(!json_field("first", "names[0]", vec![]).is_multi_valued());
+        assert!(json_field("names", "names[*]", vec![]).is_multi_valued());
+        assert!(json_field("ids", "$..id", vec![]).is_multi_valued());
+
+        let csv_field = Field {
+            reference_formulation: ReferenceFormulation::CSVRows,
+            ..json_field("names[*]", "names[*]", vec![])
+        };
+        assert!(!csv_field.is_multi_valued());
+    }
+
+    #[test]
+    fn test_unnest_attributes_outer_first() {
+        let iterator = Iterator {
+            reference:             Some("$.films[*]".to_string()),
+            reference_formulation: ReferenceFormulation::JSONPath,
+            alias:                 Some("film".to_string()),
+            fields:                vec![
+                json_field("name", "name", vec![]),
+                json_field(
+                    "actors",
+                    "cast[*]",
+                    vec![
+                        json_field("name", "name", vec![]),
+                        json_field("roles", "roles[*]", vec![]),
+                    ],
+                ),
+            ],
+        };
+
+        assert_eq!(
+            iterator.unnest_attributes(),
+            vec!["film.actors".to_string(), "film.actors.roles".to_string()]
+        );
+    }
+}
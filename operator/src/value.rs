@@ -1,6 +1,12 @@
 use std::collections::HashMap;
+use std::fmt::UpperExp;
+use std::hash::{Hash, Hasher};
+use std::mem::discriminant;
 
 use serde::Serialize;
+use vocab::ToString as _;
+
+use crate::datatype::XsdDatatype;
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Value {
@@ -15,6 +21,209 @@ pub enum Value {
     String(String),
 
     Object(HashMap<String, Value>),
+
+    Iri(String),
+
+    BlankNode(String),
+
+    Literal(Literal),
+}
+
+/// An RDF literal consisting of a lexical form and either a datatype IRI or
+/// a language tag.
+///
+/// Simple literals are represented with the `xsd:string` datatype as in
+/// RDF 1.1, so that `"a"` and `"a"^^xsd:string` are the same term.
+#[derive(Debug, Clone, Serialize)]
+pub struct Literal {
+    pub lexical_form: String,
+    pub tag:          LiteralTag,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum LiteralTag {
+    Datatype(String),
+    Language(String),
+}
+
+impl Literal {
+    pub fn simple(lexical_form: &str) -> Self {
+        Self::typed(lexical_form, &vocab::xsd::TYPE::XSD_STRING.to_string())
+    }
+
+    pub fn typed(lexical_form: &str, datatype: &str) -> Self {
+        Self {
+            lexical_form: lexical_form.to_string(),
+            tag:          LiteralTag::Datatype(datatype.to_string()),
+        }
+    }
+
+    pub fn language_tagged(lexical_form: &str, language: &str) -> Self {
+        Self {
+            lexical_form: lexical_form.to_string(),
+            tag:          LiteralTag::Language(language.to_string()),
+        }
+    }
+
+    /// Returns the datatype IRI of the literal, which is `rdf:langString`
+    /// for language-tagged literals.
+    pub fn datatype(&self) -> String {
+        match &self.tag {
+            LiteralTag::Datatype(datatype) => datatype.clone(),
+            LiteralTag::Language(_) => {
+                vocab::rdf::CLASS::RDF_LANG_STRING.to_string()
+            }
+        }
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        match &self.tag {
+            LiteralTag::Datatype(_) => None,
+            LiteralTag::Language(language) => Some(language),
+        }
+    }
+
+    /// Language tags are compared case-insensitively, hence the normalized
+    /// form used for equality and hashing is lowercase.
+    fn normalized_language(&self) -> Option<String> {
+        self.language().map(|language| language.to_lowercase())
+    }
+
+    pub fn to_ntriples(&self) -> String {
+        let lexical_form = escape_literal(&self.lexical_form);
+        match &self.tag {
+            LiteralTag::Language(language) => {
+                format!("\"{}\"@{}", lexical_form, language)
+            }
+            LiteralTag::Datatype(datatype)
+                if *datatype == vocab::xsd::TYPE::XSD_STRING.to_string() =>
+            {
+                format!("\"{}\"", lexical_form)
+            }
+            LiteralTag::Datatype(datatype) => {
+                format!("\"{}\"^^<{}>", lexical_form, datatype)
+            }
+        }
+    }
+}
+
+impl PartialEq for Literal {
+    fn eq(&self, other: &Self) -> bool {
+        self.lexical_form == other.lexical_form
+            && self.datatype() == other.datatype()
+            && self.normalized_language() == other.normalized_language()
+    }
+}
+
+impl Eq for Literal {}
+
+impl Hash for Literal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.lexical_form.hash(state);
+        self.datatype().hash(state);
+        self.normalized_language().hash(state);
+    }
+}
+
+pub(crate) fn escape_literal(lexical_form: &str) -> String {
+    lexical_form
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Returns the blank node label if it is a valid N-Triples label. Otherwise,
+/// the characters which cannot appear in the label are replaced with
+/// underscores, and a hash of the original label is appended so that
+/// distinct labels stay distinct.
+pub(crate) fn ntriples_blank_node_label(label: &str) -> String {
+    let allowed = |idx: usize, c: char| {
+        c.is_ascii_alphanumeric() || c == '_' || (idx > 0 && "-.".contains(c))
+    };
+    if !label.is_empty()
+        && !label.ends_with('.')
+        && label.char_indices().all(|(idx, c)| allowed(idx, c))
+    {
+        return label.to_string();
+    }
+
+    let sanitized: String = label
+        .char_indices()
+        .map(|(idx, c)| if allowed(idx, c) { c } else { '_' })
+        .collect();
+    // 64-bit FNV-1a, which is stable across runs and platforms
+    let hash = label.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{}_{:016x}", sanitized, hash)
+}
+
+impl Value {
+    pub fn is_rdf_term(&self) -> bool {
+        matches!(
+            self,
+            Value::Iri(_) | Value::BlankNode(_) | Value::Literal(_)
+        )
+    }
+
+    /// Writes the RDF term variants in the N-Triples term syntax. Blank
+    /// node labels which aren't valid in N-Triples are sanitized and
+    /// suffixed with a hash of the label.
+    ///
+    /// Returns [None] for values which are not RDF terms.
+    pub fn to_ntriples(&self) -> Option<String> {
+        match self {
+            Value::Iri(iri) => Some(format!("<{}>", iri)),
+            Value::BlankNode(label) => {
+                Some(format!("_:{}", ntriples_blank_node_label(label)))
+            }
+            Value::Literal(literal) => Some(literal.to_ntriples()),
+            _ => None,
+        }
+    }
+
+    /// Converts the values which are not RDF terms into literals with the
+    /// XSD datatype of the value, e.g. booleans into `xsd:boolean` literals
+    /// and strings, arrays and objects into simple literals.
+    ///
+    /// Returns [None] for null values and RDF terms.
+    pub fn to_literal(&self) -> Option<Literal> {
+        let datatype = match self {
+            Value::Null
+            | Value::Iri(_)
+            | Value::BlankNode(_)
+            | Value::Literal(_) => return None,
+            Value::Boolean(_) => XsdDatatype::Boolean,
+            Value::Number(Number::Decimal(_)) => XsdDatatype::Decimal,
+            Value::Number(Number::Float(_)) => XsdDatatype::Float,
+            Value::Number(
+                Number::Double(_) | Number::PosInfinity | Number::NegInfinity,
+            ) => XsdDatatype::Double,
+            Value::Number(_) => XsdDatatype::Integer,
+            Value::Array(_) | Value::String(_) | Value::Object(_) => {
+                XsdDatatype::String
+            }
+        };
+        Some(Literal::typed(&self.to_string(), &datatype.iri()))
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        discriminant(self).hash(state);
+        match self {
+            Value::Null => {}
+            Value::Boolean(bool) => bool.hash(state),
+            Value::Number(num) => num.to_string().hash(state),
+            Value::Array(values) => values.hash(state),
+            Value::String(str) => str.hash(state),
+            Value::Object(obj) => crate::hash_hashmap(obj, state),
+            Value::Iri(iri) => iri.hash(state),
+            Value::BlankNode(label) => label.hash(state),
+            Value::Literal(literal) => literal.hash(state),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -28,20 +237,45 @@ pub enum Number {
     Int(i64),
     UInt(u64),
     Float(f32),
+    /// Canonical lexical form of an `xsd:decimal`, which is kept as a string
+    /// so that no precision is lost.
+    Decimal(String),
 }
 
+/// Numbers are written in the canonical lexical form of the XSD datatype
+/// they are coerced from, see [XsdDatatype](crate::datatype::XsdDatatype).
 impl ToString for Number {
     fn to_string(&self) -> String {
         match self {
-            Number::PosInfinity => "+∞".to_string(),
-            Number::NegInfinity => "-∞".to_string(),
-            Number::Double(db) => db.to_string(),
-            Number::Byte(byte) => format!("{:b}", byte),
+            Number::PosInfinity => "INF".to_string(),
+            Number::NegInfinity => "-INF".to_string(),
+            Number::Double(db) => canonical_floating(*db),
+            Number::Byte(byte) => byte.to_string(),
             Number::Short(sh) => sh.to_string(),
             Number::UShort(ush) => ush.to_string(),
             Number::Int(int) => int.to_string(),
             Number::UInt(uint) => uint.to_string(),
-            Number::Float(float) => float.to_string(),
+            Number::Float(float) => canonical_floating(*float),
+            Number::Decimal(decimal) => decimal.clone(),
+        }
+    }
+}
+
+/// Writes the floating point number in scientific notation with at least
+/// one fractional digit in the mantissa, e.g. `2.3E1` and `1.0E0`.
+fn canonical_floating<F: Into<f64> + UpperExp + Copy>(value: F) -> String {
+    let double: f64 = value.into();
+    if double.is_nan() {
+        "NaN".to_string()
+    } else if double.is_infinite() {
+        if double > 0.0 { "INF" } else { "-INF" }.to_string()
+    } else {
+        let exponential = format!("{:E}", value);
+        match exponential.split_once('E') {
+            Some((mantissa, exponent)) if !mantissa.contains('.') => {
+                format!("{}.0E{}", mantissa, exponent)
+            }
+            _ => exponential,
         }
     }
 }
@@ -75,6 +309,9 @@ impl From<&Value> for String {
             Value::Array(values) => serde_json::to_string(values).unwrap(),
             Value::String(str) => str.to_owned(),
             Value::Object(obj) => serde_json::to_string(obj).unwrap(),
+            Value::Iri(iri) => iri.to_owned(),
+            Value::BlankNode(label) => label.to_owned(),
+            Value::Literal(literal) => literal.lexical_form.to_owned(),
         }
     }
 }
@@ -85,23 +322,10 @@ impl ToString for Value {
     }
 }
 
-macro_rules! from_str_val_parse {
-    ($t:ident, $value:expr) => {
-        if let Ok(parsed) = $value.trim().parse::<$t>() {
-            return parsed.into();
-        }
-    };
-}
-
+/// Strings are kept as they are, typed values are created by coercing the
+/// string with an [XsdDatatype](crate::datatype::XsdDatatype).
 impl From<String> for Value {
     fn from(value: String) -> Self {
-        from_str_val_parse!(u8, value);
-        from_str_val_parse!(u32, value);
-        from_str_val_parse!(u64, value);
-        from_str_val_parse!(i32, value);
-        from_str_val_parse!(i64, value);
-        from_str_val_parse!(f32, value);
-        from_str_val_parse!(f64, value);
         Self::String(value)
     }
 }
@@ -160,6 +384,8 @@ from_num_val_impl!(u8, Byte);
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
 
     #[test]
@@ -185,7 +411,7 @@ mod tests {
         let intoed_map: Value = map.into();
         let map_val = Value::Object(HashMap::from([
             ("name".to_string(), "foobar".into()),
-            ("age".to_string(), 23_u8.into()),
+            ("age".to_string(), "23".into()),
         ]));
 
         assert!(map_val == intoed_map, "{:?} == {:?}", map_val, intoed_map);
@@ -198,4 +424,92 @@ mod tests {
 
         assert!(num_str != f64_val, "{:?} != {:?}", num_str, f64_val);
     }
+
+    #[test]
+    fn test_number_to_string() {
+        assert_eq!(Number::Byte(23).to_string(), "23");
+        assert_eq!(Number::Double(23.0).to_string(), "2.3E1");
+        assert_eq!(Number::Float(-0.5).to_string(), "-5.0E-1");
+        assert_eq!(Number::Double(f64::NAN).to_string(), "NaN");
+        assert_eq!(Number::PosInfinity.to_string(), "INF");
+        assert_eq!(String::from(&Value::from("007")), "007");
+    }
+
+    #[test]
+    fn test_literal_term_equality() {
+        let simple = Value::Literal(Literal::simple("Venus"));
+        let string_typed = Value::Literal(Literal::typed(
+            "Venus",
+            "http://www.w3.org/2001/XMLSchema#string",
+        ));
+        assert_eq!(simple, string_typed);
+        assert_ne!(simple, Value::String("Venus".to_string()));
+
+        let lang = Literal::language_tagged("Venus", "en-GB");
+        let lower_lang = Literal::language_tagged("Venus", "en-gb");
+        assert_eq!(lang, lower_lang);
+        assert_ne!(Value::Literal(lang.clone()), simple);
+
+        let hashed: HashSet<Literal> = HashSet::from([lang, lower_lang]);
+        assert_eq!(hashed.len(), 1);
+    }
+
+    #[test]
+    fn test_ntriples_terms() {
+        let iri = Value::Iri("http://example.com/Venus".to_string());
+        assert_eq!(
+            iri.to_ntriples(),
+            Some("<http://example.com/Venus>".to_string())
+        );
+
+        let bnode = |label: &str| Value::BlankNode(label.to_string());
+        assert_eq!(
+            bnode("student_10-a.b").to_ntriples(),
+            Some("_:student_10-a.b".to_string())
+        );
+        let sanitized: Vec<_> = ["student 10", "student/10", "student_10."]
+            .into_iter()
+            .map(|label| bnode(label).to_ntriples().unwrap())
+            .collect();
+        assert!(sanitized[0].starts_with("_:student_10_"));
+        assert!(sanitized[2].starts_with("_:student_10._"));
+        assert_ne!(sanitized[0], sanitized[1]);
+        assert_eq!(bnode("student 10").to_ntriples().unwrap(), sanitized[0]);
+
+        let literal = Value::Literal(Literal::simple("say \"hi\""));
+        assert_eq!(
+            literal.to_ntriples(),
+            Some("\"say \\\"hi\\\"\"".to_string())
+        );
+
+        let typed = Value::Literal(Literal::typed(
+            "10",
+            "http://www.w3.org/2001/XMLSchema#integer",
+        ));
+        assert_eq!(
+            typed.to_ntriples(),
+            Some(
+                "\"10\"^^<http://www.w3.org/2001/XMLSchema#integer>"
+                    .to_string()
+            )
+        );
+
+        let lang = Value::Literal(Literal::language_tagged("Venus", "en"));
+        assert_eq!(lang.to_ntriples(), Some("\"Venus\"@en".to_string()));
+
+        assert_eq!(Value::String("Venus".to_string()).to_ntriples(), None);
+
+        let to_literal = |value: Value| value.to_literal().unwrap();
+        assert_eq!(to_literal("Venus".into()), Literal::simple("Venus"));
+        assert_eq!(
+            to_literal(true.into()),
+            Literal::typed("true", &XsdDatatype::Boolean.iri())
+        );
+        assert_eq!(
+            to_literal(7_i64.into()),
+            Literal::typed("7", &XsdDatatype::Integer.iri())
+        );
+        assert_eq!(Value::Null.to_literal(), None);
+        assert_eq!(bnode("student_10").to_literal(), None);
+    }
 }
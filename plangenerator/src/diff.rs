@@ -0,0 +1,635 @@
+//! Structural comparison of mapping plans.
+//!
+//! Plans are compared up to isomorphism: node ids are ignored, and so is
+//! the order of the set-valued operator configurations, like the projected
+//! attributes. Nodes are matched by their operator and by the operators up-
+//! and downstream of them. Matched nodes whose configuration differs while
+//! their neighbourhood is the same are reported as changed, the other
+//! nodes as added or removed.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+
+use operator::Operator;
+use petgraph::algo::toposort;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde_json::Value;
+
+use crate::error::PlanError;
+use crate::exchange::DocumentOperator;
+use crate::plan::{DiGraphOperators, Plan};
+
+/// Keys of the operator configurations holding arrays whose order doesn't
+/// change the meaning of the operator.
+const SET_VALUED_KEYS: [&str; 7] = [
+    "projection_attributes",
+    "to",
+    "fields",
+    "inner_fields",
+    "join_conditions",
+    "group_by",
+    "template",
+];
+
+/// A difference between the configurations of two matched operators.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigChange {
+    /// Dot separated path of the changed value in the configuration.
+    pub path:     String,
+    pub expected: Option<Value>,
+    pub actual:   Option<Value>,
+}
+
+impl Display for ConfigChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |value: &Option<Value>| {
+            value
+                .as_ref()
+                .map_or("absent".to_string(), |value| value.to_string())
+        };
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.path,
+            show(&self.expected),
+            show(&self.actual)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanChange {
+    RemovedNode {
+        id:            String,
+        operator_type: String,
+    },
+    AddedNode {
+        id:            String,
+        operator_type: String,
+    },
+    ChangedNode {
+        expected_id:   String,
+        actual_id:     String,
+        operator_type: String,
+        changes:       Vec<ConfigChange>,
+    },
+    /// An edge between matched nodes, by the ids of the expected plan.
+    RemovedEdge {
+        from:     String,
+        to:       String,
+        fragment: String,
+    },
+    /// An edge between matched nodes, by the ids of the actual plan.
+    AddedEdge {
+        from:     String,
+        to:       String,
+        fragment: String,
+    },
+}
+
+impl Display for PlanChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanChange::RemovedNode { id, operator_type } => {
+                write!(f, "- {} ({})", id, operator_type)
+            }
+            PlanChange::AddedNode { id, operator_type } => {
+                write!(f, "+ {} ({})", id, operator_type)
+            }
+            PlanChange::ChangedNode {
+                expected_id,
+                actual_id,
+                operator_type,
+                changes,
+            } => {
+                write!(
+                    f,
+                    "~ {} -> {} ({})",
+                    expected_id, actual_id, operator_type
+                )?;
+                for change in changes {
+                    write!(f, "\n    {}", change)?;
+                }
+                Ok(())
+            }
+            PlanChange::RemovedEdge { from, to, fragment } => {
+                write!(f, "- edge {} -> {} (fragment {})", from, to, fragment)
+            }
+            PlanChange::AddedEdge { from, to, fragment } => {
+                write!(f, "+ edge {} -> {} (fragment {})", from, to, fragment)
+            }
+        }
+    }
+}
+
+/// The changes turning the expected plan into the actual plan. Edges from
+/// or to added and removed nodes are implied by the node changes and
+/// aren't listed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlanDiff {
+    pub changes: Vec<PlanChange>,
+}
+
+impl PlanDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Display for PlanDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines: Vec<String> = self
+            .changes
+            .iter()
+            .map(|change| change.to_string())
+            .collect();
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+impl<T> Plan<T> {
+    /// Compares the actual plan against this plan as the expected one.
+    pub fn diff<O>(&self, actual: &Plan<O>) -> Result<PlanDiff, PlanError> {
+        diff_graphs(&self.graph.borrow(), &actual.graph.borrow())
+    }
+}
+
+struct CanonicalNode {
+    id:            String,
+    operator_type: String,
+    config:        Value,
+    /// Hashes of the node with its upstream and downstream nodes, taking
+    /// the operator configurations into account.
+    full:          (u64, u64),
+    /// Like the full hashes, but only taking the operator types into
+    /// account.
+    shape:         (u64, u64),
+}
+
+struct CanonicalPlan {
+    nodes: Vec<CanonicalNode>,
+    edges: Vec<(usize, usize, String)>,
+}
+
+impl CanonicalPlan {
+    fn new(graph: &DiGraphOperators) -> Result<CanonicalPlan, PlanError> {
+        let sorted_nodes = toposort(graph, None).map_err(|cycle| {
+            PlanError::GenericError(format!(
+                "The plan contains a cycle at node {}",
+                graph[cycle.node_id()].id
+            ))
+        })?;
+
+        let mut nodes = Vec::with_capacity(graph.node_count());
+        for node in graph.node_weights() {
+            let mut operator =
+                serde_json::to_value(DocumentOperator::from(&node.operator))?;
+            let config = operator
+                .get_mut("config")
+                .map(Value::take)
+                .map(canonical_value)
+                .unwrap_or_default();
+            nodes.push(CanonicalNode {
+                id: node.id.clone(),
+                operator_type: operator["type"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                config,
+                full: (0, 0),
+                shape: (0, 0),
+            });
+        }
+
+        // Upstream hashes in topological order, the inputs of a join are
+        // ordered from left to right
+        for &idx in &sorted_nodes {
+            let mut incoming: Vec<_> =
+                graph.edges_directed(idx, Direction::Incoming).collect();
+            incoming.sort_by_key(|edge| edge.id());
+            let mut full_inputs: Vec<_> = incoming
+                .iter()
+                .map(|edge| {
+                    (
+                        nodes[edge.source().index()].full.0,
+                        &edge.weight().fragment,
+                    )
+                })
+                .collect();
+            let mut shape_inputs: Vec<_> = incoming
+                .iter()
+                .map(|edge| {
+                    (
+                        nodes[edge.source().index()].shape.0,
+                        &edge.weight().fragment,
+                    )
+                })
+                .collect();
+            if !matches!(graph[idx].operator, Operator::JoinOp { .. }) {
+                full_inputs.sort();
+                shape_inputs.sort();
+            }
+
+            let node = &nodes[idx.index()];
+            let full = hash_of(&(
+                &node.operator_type,
+                &node.config.to_string(),
+                full_inputs,
+            ));
+            let shape = hash_of(&(&node.operator_type, shape_inputs));
+            nodes[idx.index()].full.0 = full;
+            nodes[idx.index()].shape.0 = shape;
+        }
+
+        for &idx in sorted_nodes.iter().rev() {
+            let mut full_outputs: Vec<_> = graph
+                .edges(idx)
+                .map(|edge| {
+                    (
+                        nodes[edge.target().index()].full.1,
+                        &edge.weight().fragment,
+                    )
+                })
+                .collect();
+            let mut shape_outputs: Vec<_> = graph
+                .edges(idx)
+                .map(|edge| {
+                    (
+                        nodes[edge.target().index()].shape.1,
+                        &edge.weight().fragment,
+                    )
+                })
+                .collect();
+            full_outputs.sort();
+            shape_outputs.sort();
+
+            let node = &nodes[idx.index()];
+            let full = hash_of(&(
+                &node.operator_type,
+                &node.config.to_string(),
+                full_outputs,
+            ));
+            let shape = hash_of(&(&node.operator_type, shape_outputs));
+            nodes[idx.index()].full.1 = full;
+            nodes[idx.index()].shape.1 = shape;
+        }
+
+        let edges = graph
+            .edge_references()
+            .map(|edge| {
+                (
+                    edge.source().index(),
+                    edge.target().index(),
+                    edge.weight().fragment.clone(),
+                )
+            })
+            .collect();
+
+        Ok(CanonicalPlan { nodes, edges })
+    }
+}
+
+fn hash_of<H: Hash>(value: &H) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Sorts the arrays of the [SET_VALUED_KEYS] in the value.
+fn canonical_value(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let mut value = canonical_value(value);
+                        if let (true, Value::Array(items)) = (
+                            SET_VALUED_KEYS.contains(&key.as_str()),
+                            &mut value,
+                        ) {
+                            items.sort_by_key(|item| item.to_string());
+                        }
+                        (key, value)
+                    })
+                    .collect(),
+            )
+        }
+        Value::Array(items) => {
+            Value::Array(items.into_iter().map(canonical_value).collect())
+        }
+        value => value,
+    }
+}
+
+/// Collects the paths in which the two configurations differ, descending
+/// into the objects.
+fn config_changes(
+    path: &str,
+    expected: Option<&Value>,
+    actual: Option<&Value>,
+    changes: &mut Vec<ConfigChange>,
+) {
+    match (expected, actual) {
+        (Some(Value::Object(expected)), Some(Value::Object(actual))) => {
+            let mut keys: Vec<&String> =
+                expected.keys().chain(actual.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                config_changes(
+                    &format!("{}.{}", path, key),
+                    expected.get(key),
+                    actual.get(key),
+                    changes,
+                );
+            }
+        }
+        (expected, actual) if expected != actual => {
+            changes.push(ConfigChange {
+                path:     path.to_string(),
+                expected: expected.cloned(),
+                actual:   actual.cloned(),
+            })
+        }
+        _ => {}
+    }
+}
+
+/// Pairs the unmatched nodes of both plans which have the same key.
+fn match_by(
+    expected: &CanonicalPlan,
+    actual: &CanonicalPlan,
+    matching: &mut [Option<usize>],
+    matched_actual: &mut [bool],
+    key: fn(&CanonicalNode) -> u64,
+) {
+    let mut candidates: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (idx, node) in actual.nodes.iter().enumerate().rev() {
+        if !matched_actual[idx] {
+            candidates.entry(key(node)).or_default().push(idx);
+        }
+    }
+
+    for (idx, node) in expected.nodes.iter().enumerate() {
+        if matching[idx].is_some() {
+            continue;
+        }
+        if let Some(actual_idx) =
+            candidates.get_mut(&key(node)).and_then(|idxs| idxs.pop())
+        {
+            matching[idx] = Some(actual_idx);
+            matched_actual[actual_idx] = true;
+        }
+    }
+}
+
+pub fn diff_graphs(
+    expected: &DiGraphOperators,
+    actual: &DiGraphOperators,
+) -> Result<PlanDiff, PlanError> {
+    let expected = CanonicalPlan::new(expected)?;
+    let actual = CanonicalPlan::new(actual)?;
+
+    // Nodes are matched by the keys in turn, starting with the most
+    // specific ones
+    let keys: [fn(&CanonicalNode) -> u64; 7] = [
+        |node| hash_of(&node.full),
+        |node| hash_of(&node.shape),
+        |node| node.full.0,
+        |node| node.full.1,
+        |node| hash_of(&(&node.operator_type, node.config.to_string())),
+        |node| node.shape.0,
+        |node| node.shape.1,
+    ];
+    let mut matching = vec![None; expected.nodes.len()];
+    let mut matched_actual = vec![false; actual.nodes.len()];
+    for key in keys {
+        match_by(&expected, &actual, &mut matching, &mut matched_actual, key);
+    }
+
+    let mut diff = PlanDiff::default();
+    for (idx, node) in expected.nodes.iter().enumerate() {
+        match matching[idx] {
+            None => {
+                diff.changes.push(PlanChange::RemovedNode {
+                    id:            node.id.clone(),
+                    operator_type: node.operator_type.clone(),
+                })
+            }
+            Some(actual_idx) => {
+                let actual_node = &actual.nodes[actual_idx];
+                let mut changes = Vec::new();
+                config_changes(
+                    "config",
+                    Some(&node.config),
+                    Some(&actual_node.config),
+                    &mut changes,
+                );
+                if !changes.is_empty() {
+                    diff.changes.push(PlanChange::ChangedNode {
+                        expected_id: node.id.clone(),
+                        actual_id: actual_node.id.clone(),
+                        operator_type: node.operator_type.clone(),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    for (idx, node) in actual.nodes.iter().enumerate() {
+        if !matched_actual[idx] {
+            diff.changes.push(PlanChange::AddedNode {
+                id:            node.id.clone(),
+                operator_type: node.operator_type.clone(),
+            });
+        }
+    }
+
+    // Edges between matched nodes, in terms of the actual node indices
+    let mut actual_edges: HashMap<(usize, usize, &String), usize> =
+        HashMap::new();
+    for (from, to, fragment) in &actual.edges {
+        if matched_actual[*from] && matched_actual[*to] {
+            *actual_edges.entry((*from, *to, fragment)).or_default() += 1;
+        }
+    }
+    for (from, to, fragment) in &expected.edges {
+        let (Some(actual_from), Some(actual_to)) =
+            (matching[*from], matching[*to])
+        else {
+            continue;
+        };
+        match actual_edges.get_mut(&(actual_from, actual_to, fragment)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => {
+                diff.changes.push(PlanChange::RemovedEdge {
+                    from:     expected.nodes[*from].id.clone(),
+                    to:       expected.nodes[*to].id.clone(),
+                    fragment: fragment.clone(),
+                })
+            }
+        }
+    }
+    for (from, to, fragment) in &actual.edges {
+        if let Some(count) = actual_edges.get_mut(&(*from, *to, fragment)) {
+            if *count > 0 {
+                *count -= 1;
+                diff.changes.push(PlanChange::AddedEdge {
+                    from:     actual.nodes[*from].id.clone(),
+                    to:       actual.nodes[*to].id.clone(),
+                    fragment: fragment.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use operator::formats::{DataFormat, ReferenceFormulation};
+    use operator::template::parse_template;
+    use operator::{
+        Distinct, IOType, Iterator, Projection, Serializer, Source, Target,
+    };
+
+    use super::*;
+    use crate::plan::Init;
+
+    fn csv_source(path: &str) -> Source {
+        Source {
+            config:        HashMap::from([(
+                "path".to_string(),
+                path.to_string(),
+            )]),
+            source_type:   IOType::File,
+            root_iterator: Iterator {
+                reference:             None,
+                reference_formulation: ReferenceFormulation::CSVRows,
+                fields:                vec![],
+                alias:                 None,
+            },
+        }
+    }
+
+    /// Builds a plan serializing two CSV files, of which the second one is
+    /// projected on the given attributes and optionally made distinct.
+    fn sample_plan(
+        reversed: bool,
+        attributes: &[&str],
+        distinct: bool,
+    ) -> Result<Plan<Init>, PlanError> {
+        let mut plan = Plan::new();
+        let target = Target {
+            configuration: HashMap::new(),
+            target_type:   IOType::StdOut,
+            data_format:   DataFormat::NQuads,
+        };
+        let serializer = Serializer {
+            template: parse_template("?s <http://ex.com/name> ?o .").unwrap(),
+            options:  None,
+            format:   DataFormat::NQuads,
+        };
+        let projection_op = |attributes: &[&str]| {
+            Operator::ProjectOp {
+                config: Projection {
+                    projection_attributes: attributes
+                        .iter()
+                        .map(|attribute| attribute.to_string())
+                        .collect::<HashSet<_>>(),
+                },
+            }
+        };
+
+        let mut paths = vec!["first.csv", "second.csv"];
+        if reversed {
+            paths.reverse();
+        }
+        for path in paths {
+            let mut projected_plan = plan
+                .source(csv_source(path))
+                .apply(&projection_op(&["s", "o"]), "Projection")?;
+            if path == "second.csv" {
+                projected_plan = projected_plan
+                    .apply(&projection_op(attributes), "Projection")?;
+                if distinct {
+                    projected_plan = projected_plan
+                        .distinct(Distinct { attributes: None })?;
+                }
+            }
+            projected_plan
+                .serialize(serializer.clone())?
+                .sink(&target)?;
+        }
+        Ok(plan)
+    }
+
+    #[test]
+    fn test_isomorphic_plans() -> Result<(), PlanError> {
+        let expected = sample_plan(false, &["s", "o", "g"], false)?;
+        let actual = sample_plan(true, &["g", "o", "s"], false)?;
+
+        assert!(expected.diff(&actual)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_changed_plans() -> Result<(), PlanError> {
+        let expected = sample_plan(false, &["s", "o"], false)?;
+        let actual = sample_plan(true, &["s", "o", "g"], true)?;
+
+        let diff = expected.diff(&actual)?;
+        assert_eq!(
+            diff.to_string(),
+            r#"~ Projection_6 -> Projection_2 (ProjectOp)
+    config.projection_attributes: ["o","s"] -> ["g","o","s"]
++ Distinct_3 (DistinctOp)
+- edge Projection_6 -> Serialize_7 (fragment default)"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_removed_nodes_and_added_edges() -> Result<(), PlanError> {
+        let expected = sample_plan(false, &["s", "o"], true)?;
+        let actual = sample_plan(false, &["s", "o"], false)?;
+
+        let diff = expected.diff(&actual)?;
+        assert_eq!(
+            diff.changes,
+            vec![
+                PlanChange::RemovedNode {
+                    id:            "Distinct_7".to_string(),
+                    operator_type: "DistinctOp".to_string(),
+                },
+                PlanChange::AddedEdge {
+                    from:     "Projection_6".to_string(),
+                    to:       "Serialize_7".to_string(),
+                    fragment: "default".to_string(),
+                },
+            ]
+        );
+
+        // An edge carrying another fragment is removed and added
+        {
+            let mut graph = actual.graph.borrow_mut();
+            let edge_idx = graph.edge_indices().next_back().unwrap();
+            graph[edge_idx].fragment = "tm0".to_string();
+        }
+        assert_eq!(
+            expected.diff(&actual)?.to_string(),
+            r#"- Distinct_7 (DistinctOp)
+- edge Serialize_8 -> Sink_9 (fragment default)
++ edge Projection_6 -> Serialize_7 (fragment default)
++ edge Serialize_7 -> Sink_8 (fragment tm0)"#
+        );
+        Ok(())
+    }
+}
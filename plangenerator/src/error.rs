@@ -1,5 +1,8 @@
 use operator::Operator;
 
+use crate::schema::UnknownAttribute;
+use crate::validation::PlanViolation;
+
 #[derive(thiserror::Error, Debug)]
 pub enum PlanError {
     #[error("Invalid to add non-leaf operator to an empty plan")]
@@ -16,6 +19,41 @@ pub enum PlanError {
     #[error("The given operator needs to be connected to a previous operator: \n{0:?}")]
     DanglingApplyOperator(Operator),
 
+    #[error("Invalid JSON plan: {0}")]
+    InvalidJsonPlan(#[from] serde_json::Error),
+
+    #[error(
+        "The plan document format version {0} is not supported, the latest supported version is {}",
+        crate::exchange::PLAN_FORMAT_VERSION
+    )]
+    UnsupportedFormatVersion(u32),
+
+    #[error("The plan refers to unknown attributes:\n{}", display_lines(.0))]
+    UnknownAttributes(Vec<UnknownAttribute>),
+
+    #[error("The plan is invalid:\n{}", display_lines(.0))]
+    InvalidPlan(Vec<PlanViolation>),
+
+    #[error(
+        "Unknown optimizer rule {0}, the rules are: {}",
+        crate::optimizer::OptimizerRule::names().join(", ")
+    )]
+    UnknownOptimizerRule(String),
+
+    #[error(
+        "Unknown plan format {0}, the formats are: {}",
+        crate::visualization::PlanFormat::names().join(", ")
+    )]
+    UnknownPlanFormat(String),
+
     #[error("Something else happened: {0:?}")]
     GenericError(String),
 }
+
+fn display_lines<T: ToString>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,563 @@
+//! Versioned plan document for exchanging mapping plans with executors.
+//!
+//! The document lists the operators as nodes with explicit ids, the edges
+//! between them by node id and a topological order of the node ids, so
+//! executors don't have to depend on the in-memory graph layout. The
+//! operator configurations are spelled out without flattened maps.
+//!
+//! The JSON Schema of the document is generated with
+//! [plan_document_schema] and published at
+//! `plangenerator/resources/plan_document.schema.json`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use operator::formats::DataFormat;
+use operator::template::{deserialize_template, QuadPattern};
+use operator::{
+    Aggregate, Distinct, Extend, Filter, Fragmenter, Function, IOType,
+    Iterator, Join, Operator, Projection, Rename, Serializer, Sort, Source,
+    Target, Union, Unnest,
+};
+use petgraph::algo::toposort;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use schemars::{JsonSchema, Schema};
+use serde::{Deserialize, Serialize};
+
+use crate::error::PlanError;
+use crate::plan::{DiGraphOperators, Init, JoinSide, Plan, PlanEdge, PlanNode};
+
+/// Version of the plan document format written by this crate.
+///
+/// The version is increased whenever a change to the document is not
+/// backward compatible, such that older documents can still be read.
+///
+/// - 1: initial version.
+/// - 2: serializer templates are lists of quad patterns.
+pub const PLAN_FORMAT_VERSION: u32 = 2;
+
+/// Executor-friendly representation of a mapping plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PlanDocument {
+    /// Version of the document format.
+    pub format_version:    u32,
+    /// Operators of the plan, each with a unique id.
+    pub nodes:             Vec<DocumentNode>,
+    /// Edges between the operators.
+    pub edges:             Vec<DocumentEdge>,
+    /// Ids of all the nodes, ordered such that every node comes after its
+    /// inputs.
+    pub topological_order: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DocumentNode {
+    pub id:       String,
+    pub operator: DocumentOperator,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DocumentEdge {
+    /// Id of the node producing the solution mappings.
+    pub from:      String,
+    /// Id of the node consuming the solution mappings.
+    pub to:        String,
+    /// Fragment of the solution mappings sent over the edge, which is the
+    /// alias of the input for joins and unions.
+    pub fragment:  String,
+    /// Input of the join which the edge leads into. Documents without join
+    /// sides list the left input of a join first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub join_side: Option<JoinSide>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", content = "config")]
+pub enum DocumentOperator {
+    SourceOp(SourceConfig),
+    JoinOp(Join),
+    ProjectOp(Projection),
+    ExtendOp(ExtendConfig),
+    RenameOp(RenameConfig),
+    SerializerOp(SerializerConfig),
+    TargetOp(TargetConfig),
+    FragmentOp(Fragmenter),
+    FilterOp(Filter),
+    UnionOp(Union),
+    AggregateOp(Aggregate),
+    UnnestOp(Unnest),
+    DistinctOp(Distinct),
+    SortOp(Sort),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SourceConfig {
+    /// Source specific settings, e.g. the `path` of a file source.
+    pub config:        BTreeMap<String, String>,
+    pub source_type:   IOType,
+    pub root_iterator: Iterator,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RenameConfig {
+    /// Maps the old attribute names to the new ones.
+    pub rename_pairs: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ExtendConfig {
+    /// Maps the new attributes to the functions computing their values.
+    pub extend_pairs: BTreeMap<String, Function>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SerializerConfig {
+    /// The quad patterns of the template. Version 1 documents write the
+    /// template as a newline-joined string of patterns.
+    #[serde(deserialize_with = "deserialize_template")]
+    pub template: Vec<QuadPattern>,
+    pub options:  Option<BTreeMap<String, String>>,
+    pub format:   DataFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TargetConfig {
+    /// Target specific settings, e.g. the `path` of a file target.
+    pub configuration: BTreeMap<String, String>,
+    pub target_type:   IOType,
+    pub data_format:   DataFormat,
+}
+
+/// Generates the JSON Schema of the [PlanDocument].
+pub fn plan_document_schema() -> Schema {
+    schemars::schema_for!(PlanDocument)
+}
+
+impl From<&Operator> for DocumentOperator {
+    fn from(operator: &Operator) -> Self {
+        let operator = operator.clone();
+        match operator {
+            Operator::SourceOp { config } => {
+                DocumentOperator::SourceOp(SourceConfig {
+                    config:        config.config.into_iter().collect(),
+                    source_type:   config.source_type,
+                    root_iterator: config.root_iterator,
+                })
+            }
+            Operator::JoinOp { config } => DocumentOperator::JoinOp(config),
+            Operator::ProjectOp { config } => {
+                DocumentOperator::ProjectOp(config)
+            }
+            Operator::ExtendOp { config } => {
+                DocumentOperator::ExtendOp(ExtendConfig {
+                    extend_pairs: config.extend_pairs.into_iter().collect(),
+                })
+            }
+            Operator::RenameOp { config } => {
+                DocumentOperator::RenameOp(RenameConfig {
+                    rename_pairs: config.rename_pairs.into_iter().collect(),
+                })
+            }
+            Operator::SerializerOp { config } => {
+                DocumentOperator::SerializerOp(SerializerConfig {
+                    template: config.template,
+                    options:  config
+                        .options
+                        .map(|options| options.into_iter().collect()),
+                    format:   config.format,
+                })
+            }
+            Operator::TargetOp { config } => {
+                DocumentOperator::TargetOp(TargetConfig {
+                    configuration: config.configuration.into_iter().collect(),
+                    target_type:   config.target_type,
+                    data_format:   config.data_format,
+                })
+            }
+            Operator::FragmentOp { config } => {
+                DocumentOperator::FragmentOp(config)
+            }
+            Operator::FilterOp { config } => DocumentOperator::FilterOp(config),
+            Operator::UnionOp { config } => DocumentOperator::UnionOp(config),
+            Operator::AggregateOp { config } => {
+                DocumentOperator::AggregateOp(config)
+            }
+            Operator::UnnestOp { config } => DocumentOperator::UnnestOp(config),
+            Operator::DistinctOp { config } => {
+                DocumentOperator::DistinctOp(config)
+            }
+            Operator::SortOp { config } => DocumentOperator::SortOp(config),
+        }
+    }
+}
+
+impl From<DocumentOperator> for Operator {
+    fn from(operator: DocumentOperator) -> Self {
+        match operator {
+            DocumentOperator::SourceOp(config) => {
+                Operator::SourceOp {
+                    config: Source {
+                        config:        config.config.into_iter().collect(),
+                        source_type:   config.source_type,
+                        root_iterator: config.root_iterator,
+                    },
+                }
+            }
+            DocumentOperator::JoinOp(config) => Operator::JoinOp { config },
+            DocumentOperator::ProjectOp(config) => {
+                Operator::ProjectOp { config }
+            }
+            DocumentOperator::ExtendOp(config) => {
+                Operator::ExtendOp {
+                    config: Extend {
+                        extend_pairs: config.extend_pairs.into_iter().collect(),
+                    },
+                }
+            }
+            DocumentOperator::RenameOp(config) => {
+                Operator::RenameOp {
+                    config: Rename {
+                        rename_pairs: config.rename_pairs.into_iter().collect(),
+                    },
+                }
+            }
+            DocumentOperator::SerializerOp(config) => {
+                Operator::SerializerOp {
+                    config: Serializer {
+                        template: config.template,
+                        options:  config
+                            .options
+                            .map(|options| options.into_iter().collect()),
+                        format:   config.format,
+                    },
+                }
+            }
+            DocumentOperator::TargetOp(config) => {
+                Operator::TargetOp {
+                    config: Target {
+                        configuration: config
+                            .configuration
+                            .into_iter()
+                            .collect(),
+                        target_type:   config.target_type,
+                        data_format:   config.data_format,
+                    },
+                }
+            }
+            DocumentOperator::FragmentOp(config) => {
+                Operator::FragmentOp { config }
+            }
+            DocumentOperator::FilterOp(config) => Operator::FilterOp { config },
+            DocumentOperator::UnionOp(config) => Operator::UnionOp { config },
+            DocumentOperator::AggregateOp(config) => {
+                Operator::AggregateOp { config }
+            }
+            DocumentOperator::UnnestOp(config) => Operator::UnnestOp { config },
+            DocumentOperator::DistinctOp(config) => {
+                Operator::DistinctOp { config }
+            }
+            DocumentOperator::SortOp(config) => Operator::SortOp { config },
+        }
+    }
+}
+
+impl<T> Plan<T> {
+    /// Converts the plan into a [PlanDocument] of the current
+    /// [format version](PLAN_FORMAT_VERSION).
+    pub fn to_document(&self) -> Result<PlanDocument, PlanError> {
+        let graph = self.graph.borrow();
+        let topological_order = toposort(&*graph, None)
+            .map_err(|cycle| {
+                PlanError::GenericError(format!(
+                    "The plan contains a cycle at node {}",
+                    graph[cycle.node_id()].id
+                ))
+            })?
+            .into_iter()
+            .map(|idx| graph[idx].id.clone())
+            .collect();
+
+        let nodes = graph
+            .node_weights()
+            .map(|node| {
+                DocumentNode {
+                    id:       node.id.clone(),
+                    operator: (&node.operator).into(),
+                }
+            })
+            .collect();
+
+        let edges = graph
+            .edge_references()
+            .map(|edge| {
+                DocumentEdge {
+                    from:      graph[edge.source()].id.clone(),
+                    to:        graph[edge.target()].id.clone(),
+                    fragment:  edge.weight().fragment.clone(),
+                    join_side: edge.weight().join_side,
+                }
+            })
+            .collect();
+
+        Ok(PlanDocument {
+            format_version: PLAN_FORMAT_VERSION,
+            nodes,
+            edges,
+            topological_order,
+        })
+    }
+}
+
+impl Plan<()> {
+    /// Loads a plan from a [PlanDocument].
+    ///
+    /// The nodes and edges are added in the order of the document, and the
+    /// topological order of the document has to be consistent with its
+    /// edges.
+    pub fn from_document(
+        document: PlanDocument,
+    ) -> Result<Plan<Init>, PlanError> {
+        if document.format_version == 0
+            || document.format_version > PLAN_FORMAT_VERSION
+        {
+            return Err(PlanError::UnsupportedFormatVersion(
+                document.format_version,
+            ));
+        }
+
+        let mut graph = DiGraphOperators::new();
+        let mut node_idxs: HashMap<String, NodeIndex> = HashMap::new();
+        for node in document.nodes {
+            if node_idxs.contains_key(&node.id) {
+                return Err(PlanError::GenericError(format!(
+                    "The plan document contains the node {} more than once",
+                    node.id
+                )));
+            }
+            let idx = graph.add_node(PlanNode {
+                id:       node.id.clone(),
+                operator: node.operator.into(),
+            });
+            node_idxs.insert(node.id, idx);
+        }
+
+        let node_idx = |id: &str| {
+            node_idxs.get(id).copied().ok_or_else(|| {
+                PlanError::GenericError(format!(
+                    "The plan document refers to the unknown node {}",
+                    id
+                ))
+            })
+        };
+
+        let mut positions = HashMap::new();
+        for (position, id) in document.topological_order.iter().enumerate() {
+            node_idx(id)?;
+            if positions.insert(id.as_str(), position).is_some() {
+                return Err(PlanError::GenericError(format!(
+                    "The topological order contains the node {} more than once",
+                    id
+                )));
+            }
+        }
+        if positions.len() != node_idxs.len() {
+            return Err(PlanError::GenericError(
+                "The topological order doesn't contain every node".to_string(),
+            ));
+        }
+
+        for edge in document.edges {
+            let from_idx = node_idx(&edge.from)?;
+            let to_idx = node_idx(&edge.to)?;
+            if positions[edge.from.as_str()] >= positions[edge.to.as_str()] {
+                return Err(PlanError::GenericError(format!(
+                    "The topological order puts node {} before its input {}",
+                    edge.to, edge.from
+                )));
+            }
+            graph.add_edge(
+                from_idx,
+                to_idx,
+                PlanEdge {
+                    fragment:  edge.fragment,
+                    join_side: edge.join_side,
+                },
+            );
+        }
+
+        Self::from_graph(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use operator::template::parse_template;
+    use operator::{Function, Serializer, Source, Target};
+    use petgraph::visit::EdgeRef;
+
+    use super::*;
+    use crate::plan::join;
+
+    const PLAN_DOCUMENT_SCHEMA: &str =
+        include_str!("../resources/plan_document.schema.json");
+    const PLAN_DOCUMENT_V1: &str =
+        include_str!("../resources/tests/plan_document_v1.json");
+    const LEGACY_PLAN: &str =
+        include_str!("../resources/tests/legacy_plan.json");
+
+    fn file_source(path: &str) -> Source {
+        Source {
+            config:        HashMap::from([(
+                "path".to_string(),
+                path.to_string(),
+            )]),
+            source_type:   IOType::File,
+            root_iterator: Iterator::default(),
+        }
+    }
+
+    fn sample_plan() -> Result<Plan<Init>, PlanError> {
+        let mut plan = Plan::new();
+        let left = plan.source(file_source("student.csv"));
+        let right = plan.source(file_source("sport.csv"));
+        join(Rc::new(RefCell::new(left)), Rc::new(RefCell::new(right)))?
+            .alias("join_0")?
+            .where_by(vec!["sport"])?
+            .compared_to(vec!["ID"])?
+            .apply(
+                &Operator::ExtendOp {
+                    config: Extend {
+                        extend_pairs: HashMap::from([(
+                            "?s".to_string(),
+                            Function::Iri {
+                                inner_function: Arc::new(Function::Reference {
+                                    value: "join_0_Name".to_string(),
+                                }),
+                                base_iri:       None,
+                            },
+                        )]),
+                    },
+                },
+                "Extend",
+            )?
+            .serialize(Serializer {
+                template: parse_template("?s <http://example.com/plays> ?s .")
+                    .unwrap(),
+                options:  None,
+                format:   DataFormat::NQuads,
+            })?
+            .sink(&Target {
+                configuration: HashMap::from([(
+                    "path".to_string(),
+                    "output.nq".to_string(),
+                )]),
+                target_type:   IOType::File,
+                data_format:   DataFormat::NQuads,
+            })?;
+        Ok(plan)
+    }
+
+    fn assert_same_graph(left: &Plan<Init>, right: &Plan<Init>) {
+        let edges = |graph: &DiGraphOperators| {
+            graph
+                .edge_references()
+                .map(|edge| {
+                    (edge.source(), edge.target(), edge.weight().clone())
+                })
+                .collect::<Vec<_>>()
+        };
+        let left_graph = left.graph.borrow();
+        let right_graph = right.graph.borrow();
+
+        assert!(left_graph.node_weights().eq(right_graph.node_weights()));
+        assert!(edges(&left_graph) == edges(&right_graph));
+        assert_eq!(*left.sources.borrow(), *right.sources.borrow());
+    }
+
+    #[test]
+    fn test_published_schema_is_up_to_date() -> Result<(), PlanError> {
+        let published: serde_json::Value =
+            serde_json::from_str(PLAN_DOCUMENT_SCHEMA)?;
+
+        assert_eq!(
+            published,
+            serde_json::to_value(plan_document_schema())?,
+            "Regenerate the published schema with `translator schema`"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_document_round_trip() -> Result<(), PlanError> {
+        let plan = sample_plan()?;
+        let document = plan.to_document()?;
+
+        assert_eq!(document.format_version, PLAN_FORMAT_VERSION);
+        assert_eq!(document.topological_order.len(), document.nodes.len());
+        let join_inputs: Vec<_> = document
+            .edges
+            .iter()
+            .filter(|edge| edge.to.starts_with("Join"))
+            .map(|edge| (edge.from.as_str(), edge.join_side))
+            .collect();
+        assert_eq!(
+            join_inputs,
+            vec![
+                ("Fragmenter_3", Some(JoinSide::Left)),
+                ("Fragmenter_2", Some(JoinSide::Right))
+            ]
+        );
+
+        let json = serde_json::to_string(&document)?;
+        assert!(json.contains(r#""config":{"path":"student.csv"}"#));
+        let loaded = Plan::from_json(&json)?;
+        assert_same_graph(&plan, &loaded);
+        assert_eq!(loaded.to_document()?, document);
+        Ok(())
+    }
+
+    #[test]
+    fn test_backward_compatible_documents() -> Result<(), PlanError> {
+        let plan = sample_plan()?;
+
+        assert_same_graph(&plan, &Plan::from_json(PLAN_DOCUMENT_V1)?);
+        assert_same_graph(&plan, &Plan::from_json(LEGACY_PLAN)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_document_rejects_invalid_documents() -> Result<(), PlanError> {
+        let document = sample_plan()?.to_document()?;
+
+        let mut newer_version = document.clone();
+        newer_version.format_version = PLAN_FORMAT_VERSION + 1;
+        assert!(matches!(
+            Plan::from_document(newer_version),
+            Err(PlanError::UnsupportedFormatVersion(_))
+        ));
+
+        let mut duplicate_node = document.clone();
+        duplicate_node.nodes.push(duplicate_node.nodes[0].clone());
+        let mut unknown_node = document.clone();
+        unknown_node.edges[0].from = "Unknown_0".to_string();
+        let mut wrong_order = document.clone();
+        wrong_order.topological_order.reverse();
+        let mut missing_order = document;
+        missing_order.topological_order.pop();
+
+        for invalid in
+            [duplicate_node, unknown_node, wrong_order, missing_order]
+        {
+            assert!(matches!(
+                Plan::from_document(invalid),
+                Err(PlanError::GenericError(_))
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use crate::error::PlanError;
+use crate::plan::{DiGraphOperators, Init, Plan};
+
+/// An immutable snapshot of a mapping plan.
+///
+/// The [Plan] builder shares its graph between the plans of its branches
+/// through `Rc<RefCell<_>>`, so it can't leave the thread it is built on.
+/// A frozen plan owns its graph behind an [Arc] instead, hence it is
+/// `Send + Sync` and can be handed off to, or shared between, other threads.
+#[derive(Debug, Clone)]
+pub struct FrozenPlan {
+    graph: Arc<DiGraphOperators>,
+}
+
+impl FrozenPlan {
+    pub fn graph(&self) -> &DiGraphOperators {
+        &self.graph
+    }
+
+    /// Creates a new plan over a copy of the frozen graph, to write or
+    /// extend it on the current thread.
+    ///
+    /// Like the plans loaded with [from_json](Plan::from_json), the thawed
+    /// plan starts in the default fragment without a current node.
+    pub fn thaw(&self) -> Result<Plan<Init>, PlanError> {
+        Plan::from_graph(DiGraphOperators::clone(&self.graph))
+    }
+}
+
+impl<T> Plan<T> {
+    /// Freezes a copy of the plan graph, later changes to the plan aren't
+    /// visible in the frozen plan.
+    pub fn freeze(&self) -> FrozenPlan {
+        FrozenPlan {
+            graph: Arc::new(self.graph.borrow().clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::thread;
+
+    use operator::formats::{DataFormat, ReferenceFormulation};
+    use operator::template::parse_template;
+    use operator::{
+        IOType, Iterator, Operator, Projection, Serializer, Source, Target,
+    };
+
+    use super::*;
+
+    fn csv_source() -> Source {
+        Source {
+            config:        HashMap::from([(
+                "path".to_string(),
+                "student.csv".to_string(),
+            )]),
+            source_type:   IOType::File,
+            root_iterator: Iterator {
+                reference:             None,
+                reference_formulation: ReferenceFormulation::CSVRows,
+                fields:                vec![],
+                alias:                 None,
+            },
+        }
+    }
+
+    fn sunk_plan() -> Result<Plan<Init>, PlanError> {
+        let mut plan = Plan::new();
+        let projection_op = Operator::ProjectOp {
+            config: Projection {
+                projection_attributes: HashSet::from([
+                    "s".to_string(),
+                    "o".to_string(),
+                ]),
+            },
+        };
+        plan.source(csv_source())
+            .apply(&projection_op, "Projection")?
+            .serialize(Serializer {
+                template: parse_template("?s <http://ex.com/name> ?o .")
+                    .unwrap(),
+                options:  None,
+                format:   DataFormat::NQuads,
+            })?
+            .sink(&Target {
+                configuration: HashMap::new(),
+                target_type:   IOType::StdOut,
+                data_format:   DataFormat::NQuads,
+            })?;
+        Ok(plan)
+    }
+
+    #[test]
+    fn test_send_frozen_plan() -> Result<(), PlanError> {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FrozenPlan>();
+
+        let plan = sunk_plan()?;
+        let frozen_plan = plan.freeze();
+        let thawed_plan =
+            thread::spawn(move || frozen_plan).join().unwrap().thaw()?;
+
+        assert!(plan.diff(&thawed_plan)?.is_empty());
+        assert_eq!(thawed_plan.sources.borrow().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_freeze_snapshot() -> Result<(), PlanError> {
+        let mut plan = sunk_plan()?;
+        let frozen_plan = plan.freeze();
+        plan.source(csv_source());
+
+        assert_eq!(frozen_plan.graph().node_count(), 4);
+        assert_eq!(plan.graph.borrow().node_count(), 5);
+        Ok(())
+    }
+}
@@ -1,2 +1,9 @@
+pub mod diff;
 pub mod error;
+pub mod exchange;
+pub mod frozen;
+pub mod optimizer;
 pub mod plan;
+pub mod schema;
+pub mod validation;
+pub mod visualization;
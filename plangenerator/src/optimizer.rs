@@ -0,0 +1,819 @@
+//! Rewrite rules which simplify the plans generated by the translators
+//! without changing the solution mappings they produce.
+//!
+//! The [Optimizer] applies its [rules](OptimizerRule) until none of them
+//! rewrites the plan any further.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use operator::formats::ReferenceFormulation;
+use operator::{Extend, Field, Operator, Projection, Rename, Source};
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+
+use crate::error::PlanError;
+use crate::plan::{DiGraphOperators, Init, Plan};
+use crate::schema::{infer_schemas, Schema};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptimizerRule {
+    /// Merges an extend operator into the extend operator in front of it,
+    /// if its functions don't refer to the attributes extended by the
+    /// first one.
+    MergeExtends,
+    /// Composes consecutive rename operators, and moves the renaming of
+    /// attributes which are extended by the operator in front of the rename
+    /// into that extend operator.
+    MergeRenames,
+    /// Replaces the projection right after a source by the iterator fields
+    /// of the source.
+    PushProjections,
+    /// Removes fragmenters which declare a single fragment, and hence only
+    /// pass their input on. The outgoing edges take over the fragment of the
+    /// fragmenter's input edge.
+    DropNoopFragmenters,
+}
+
+impl OptimizerRule {
+    pub const ALL: [OptimizerRule; 4] = [
+        OptimizerRule::MergeExtends,
+        OptimizerRule::MergeRenames,
+        OptimizerRule::PushProjections,
+        OptimizerRule::DropNoopFragmenters,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OptimizerRule::MergeExtends => "merge-extends",
+            OptimizerRule::MergeRenames => "merge-renames",
+            OptimizerRule::PushProjections => "push-projections",
+            OptimizerRule::DropNoopFragmenters => "drop-noop-fragmenters",
+        }
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        OptimizerRule::ALL.iter().map(|rule| rule.name()).collect()
+    }
+
+    /// Rewrites one occurrence of the pattern matched by the rule, and
+    /// returns whether the graph has been changed.
+    fn apply(&self, graph: &mut DiGraphOperators) -> Result<bool, PlanError> {
+        let rewritten = match self {
+            OptimizerRule::MergeExtends => {
+                fuse_first_chain(graph, merge_extends)
+            }
+            OptimizerRule::MergeRenames => {
+                if let Some(rename_idx) = find_empty_rename(graph) {
+                    bypass_node(graph, rename_idx);
+                    return Ok(true);
+                }
+                let inference = infer_schemas(graph)?;
+                fuse_first_chain(graph, |graph, first_idx, first, second| {
+                    merge_renames(first, second).or_else(|| {
+                        let input_schema = single_input(graph, first_idx)
+                            .and_then(|idx| {
+                                inference.schemas.get(&graph[idx].id)
+                            })?;
+                        fold_rename_into_extend(input_schema, first, second)
+                    })
+                })
+            }
+            OptimizerRule::PushProjections => {
+                fuse_first_chain(graph, push_projection)
+            }
+            OptimizerRule::DropNoopFragmenters => {
+                match find_noop_fragmenter(graph) {
+                    Some(fragmenter_idx) => {
+                        relabel_output_edges(graph, fragmenter_idx);
+                        bypass_node(graph, fragmenter_idx);
+                        true
+                    }
+                    None => false,
+                }
+            }
+        };
+        Ok(rewritten)
+    }
+}
+
+impl Display for OptimizerRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for OptimizerRule {
+    type Err = PlanError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OptimizerRule::ALL
+            .into_iter()
+            .find(|rule| rule.name() == s)
+            .ok_or(PlanError::UnknownOptimizerRule(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Optimizer {
+    rules: Vec<OptimizerRule>,
+}
+
+impl Default for Optimizer {
+    /// Creates an optimizer with all the rules.
+    fn default() -> Self {
+        Optimizer::new(OptimizerRule::ALL.to_vec())
+    }
+}
+
+impl Optimizer {
+    pub fn new(rules: Vec<OptimizerRule>) -> Optimizer {
+        Optimizer { rules }
+    }
+
+    pub fn rules(&self) -> &[OptimizerRule] {
+        &self.rules
+    }
+
+    pub fn without_rule(mut self, rule: OptimizerRule) -> Optimizer {
+        self.rules.retain(|enabled| *enabled != rule);
+        self
+    }
+
+    /// Rewrites the plan until none of the rules applies anymore, and
+    /// returns the number of rewrites.
+    ///
+    /// The rules remove plan nodes, so the node indices into the graph of
+    /// the plan aren't stable. The source list of the plan is recomputed
+    /// afterwards.
+    pub fn optimize(&self, plan: &mut Plan<Init>) -> Result<usize, PlanError> {
+        let mut rewrites = 0;
+        {
+            let graph = &mut *plan.graph.borrow_mut();
+            loop {
+                let mut rewritten = false;
+                for rule in &self.rules {
+                    while rule.apply(graph)? {
+                        rewritten = true;
+                        rewrites += 1;
+                    }
+                }
+                if !rewritten {
+                    break;
+                }
+            }
+        }
+
+        let graph = plan.graph.borrow();
+        *plan.sources.borrow_mut() = graph
+            .node_indices()
+            .filter(|idx| {
+                matches!(graph[*idx].operator, Operator::SourceOp { .. })
+            })
+            .collect();
+        Ok(rewrites)
+    }
+}
+
+/// The replacement operators of two chained nodes.
+enum Fusion {
+    /// Both nodes are replaced by a single node.
+    Single(Operator),
+    Pair(Operator, Operator),
+}
+
+fn single_input(
+    graph: &DiGraphOperators,
+    node_idx: NodeIndex,
+) -> Option<NodeIndex> {
+    let mut inputs = graph.neighbors_directed(node_idx, Direction::Incoming);
+    match (inputs.next(), inputs.next()) {
+        (Some(input_idx), None) => Some(input_idx),
+        _ => None,
+    }
+}
+
+fn single_output(
+    graph: &DiGraphOperators,
+    node_idx: NodeIndex,
+) -> Option<NodeIndex> {
+    let mut outputs = graph.neighbors_directed(node_idx, Direction::Outgoing);
+    match (outputs.next(), outputs.next()) {
+        (Some(output_idx), None) => Some(output_idx),
+        _ => None,
+    }
+}
+
+/// Finds the first pair of nodes, connected by the only output edge of the
+/// first node and the only input edge of the second node, for which `fuse`
+/// returns the replacement operators, and replaces them.
+fn fuse_first_chain<F>(graph: &mut DiGraphOperators, mut fuse: F) -> bool
+where
+    F: FnMut(
+        &DiGraphOperators,
+        NodeIndex,
+        &Operator,
+        &Operator,
+    ) -> Option<Fusion>,
+{
+    let found = graph.node_indices().find_map(|second_idx| {
+        let first_idx = single_input(graph, second_idx)?;
+        if single_output(graph, first_idx) != Some(second_idx) {
+            return None;
+        }
+        fuse(
+            graph,
+            first_idx,
+            &graph[first_idx].operator,
+            &graph[second_idx].operator,
+        )
+        .map(|fusion| (first_idx, second_idx, fusion))
+    });
+
+    match found {
+        Some((first_idx, second_idx, Fusion::Single(operator))) => {
+            graph[first_idx].operator = operator;
+            bypass_node(graph, second_idx);
+            true
+        }
+        Some((first_idx, second_idx, Fusion::Pair(first, second))) => {
+            graph[first_idx].operator = first;
+            graph[second_idx].operator = second;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Removes the node, which has a single input node, and connects its input
+/// node to its output nodes over the same fragments.
+///
+/// The graph is rebuilt in the order of the original node and edge indices,
+/// since removing from a petgraph graph moves the last index into the
+/// removed one. This keeps the order of the join inputs, which is given by
+/// the edge indices.
+fn bypass_node(graph: &mut DiGraphOperators, node_idx: NodeIndex) {
+    let input_idx = single_input(graph, node_idx)
+        .expect("The bypassed node should have a single input");
+    let (nodes, edges) = std::mem::take(graph).into_nodes_edges();
+
+    let mut rebuilt = DiGraph::with_capacity(nodes.len() - 1, edges.len());
+    let new_idxs: HashMap<NodeIndex, NodeIndex> = nodes
+        .into_iter()
+        .enumerate()
+        .map(|(idx, node)| (NodeIndex::new(idx), node.weight))
+        .filter(|(idx, _)| *idx != node_idx)
+        .map(|(idx, node)| (idx, rebuilt.add_node(node)))
+        .collect();
+
+    for edge in edges {
+        if edge.target() == node_idx {
+            continue;
+        }
+        let source = if edge.source() == node_idx {
+            input_idx
+        } else {
+            edge.source()
+        };
+        rebuilt.add_edge(
+            new_idxs[&source],
+            new_idxs[&edge.target()],
+            edge.weight,
+        );
+    }
+
+    *graph = rebuilt;
+}
+
+fn merge_extends(
+    _graph: &DiGraphOperators,
+    _first_idx: NodeIndex,
+    first: &Operator,
+    second: &Operator,
+) -> Option<Fusion> {
+    let (
+        Operator::ExtendOp { config: first },
+        Operator::ExtendOp { config: second },
+    ) = (first, second)
+    else {
+        return None;
+    };
+
+    // The merged functions are evaluated on the same solution mapping
+    let independent = second.extend_pairs.iter().all(|(attribute, func)| {
+        !first.extend_pairs.contains_key(attribute)
+            && func
+                .referenced_attributes()
+                .iter()
+                .all(|reference| !first.extend_pairs.contains_key(reference))
+    });
+    if !independent {
+        return None;
+    }
+
+    Some(Fusion::Single(Operator::ExtendOp {
+        config: first.clone().extend_with(second.clone()),
+    }))
+}
+
+fn merge_renames(first: &Operator, second: &Operator) -> Option<Fusion> {
+    let (
+        Operator::RenameOp { config: first },
+        Operator::RenameOp { config: second },
+    ) = (first, second)
+    else {
+        return None;
+    };
+
+    // The second rename applies to the attributes renamed by the first one
+    // through the composition, and to the attributes which the first rename
+    // leaves untouched
+    let renamed: HashSet<_> = first.rename_pairs.values().collect();
+    let second_only = second.rename_pairs.iter().filter(|(attribute, _)| {
+        !first.rename_pairs.contains_key(*attribute)
+            && !renamed.contains(attribute)
+    });
+
+    let rename_pairs = first
+        .rename_pairs
+        .iter()
+        .map(|(attribute, renamed)| {
+            let renamed = second.rename_pairs.get(renamed).unwrap_or(renamed);
+            (attribute.clone(), renamed.clone())
+        })
+        .chain(
+            second_only.into_iter().map(|(attribute, renamed)| {
+                (attribute.clone(), renamed.clone())
+            }),
+        )
+        .filter(|(attribute, renamed)| attribute != renamed)
+        .collect();
+
+    Some(Fusion::Single(Operator::RenameOp {
+        config: Rename { rename_pairs },
+    }))
+}
+
+/// Moves the rename pairs of the attributes, which are added by the extend
+/// operator and don't occur in its input, into the extend operator. The
+/// rename operator is left without those pairs.
+fn fold_rename_into_extend(
+    input_schema: &Schema,
+    first: &Operator,
+    second: &Operator,
+) -> Option<Fusion> {
+    let (
+        Operator::ExtendOp { config: extend },
+        Operator::RenameOp { config: rename },
+    ) = (first, second)
+    else {
+        return None;
+    };
+
+    let (folded, kept): (HashMap<_, _>, HashMap<_, _>) =
+        rename.rename_pairs.clone().into_iter().partition(
+            |(attribute, renamed)| {
+                extend.extend_pairs.contains_key(attribute)
+                    && !extend.extend_pairs.contains_key(renamed)
+                    && !input_schema.contains(attribute)
+                    && !input_schema.contains(renamed)
+            },
+        );
+    if folded.is_empty() {
+        return None;
+    }
+
+    let extend_pairs = extend
+        .extend_pairs
+        .iter()
+        .map(|(attribute, func)| {
+            let attribute = folded.get(attribute).unwrap_or(attribute);
+            (attribute.clone(), func.clone())
+        })
+        .collect();
+    Some(Fusion::Pair(
+        Operator::ExtendOp {
+            config: Extend { extend_pairs },
+        },
+        Operator::RenameOp {
+            config: Rename { rename_pairs: kept },
+        },
+    ))
+}
+
+fn find_empty_rename(graph: &DiGraphOperators) -> Option<NodeIndex> {
+    graph.node_indices().find(|idx| {
+        matches!(
+            &graph[*idx].operator,
+            Operator::RenameOp { config } if config.rename_pairs.is_empty()
+        ) && single_input(graph, *idx).is_some()
+    })
+}
+
+fn push_projection(
+    _graph: &DiGraphOperators,
+    _first_idx: NodeIndex,
+    first: &Operator,
+    second: &Operator,
+) -> Option<Fusion> {
+    let (
+        Operator::SourceOp { config: source },
+        Operator::ProjectOp { config: projection },
+    ) = (first, second)
+    else {
+        return None;
+    };
+
+    let iterator = &source.root_iterator;
+    let fields = if iterator.fields.is_empty() {
+        csv_projection_fields(source, projection)?
+    } else {
+        let attribute_name = |field: &Field| {
+            match &iterator.alias {
+                Some(alias) => format!("{}.{}", alias, field.alias),
+                None => field.alias.clone(),
+            }
+        };
+        let fields: Vec<Field> = iterator
+            .fields
+            .iter()
+            .filter(|field| {
+                projection
+                    .projection_attributes
+                    .contains(&attribute_name(field))
+            })
+            .cloned()
+            .collect();
+
+        // The projection refers to attributes the source doesn't have
+        if fields.len() < projection.projection_attributes.len() {
+            return None;
+        }
+        fields
+    };
+
+    let mut source = source.clone();
+    source.root_iterator.fields = fields;
+    Some(Fusion::Single(Operator::SourceOp { config: source }))
+}
+
+/// Creates a field for every projected column of a CSV source, which
+/// otherwise yields all its columns.
+fn csv_projection_fields(
+    source: &Source,
+    projection: &Projection,
+) -> Option<Vec<Field>> {
+    let iterator = &source.root_iterator;
+    if iterator.reference_formulation != ReferenceFormulation::CSVRows
+        || projection.projection_attributes.is_empty()
+    {
+        return None;
+    }
+
+    let mut fields = projection
+        .projection_attributes
+        .iter()
+        .map(|attribute| {
+            let column = match &iterator.alias {
+                Some(alias) => {
+                    attribute.strip_prefix(&format!("{}.", alias))?
+                }
+                None => attribute,
+            };
+            Some(Field {
+                alias:                 column.to_string(),
+                reference:             column.to_string(),
+                reference_formulation: ReferenceFormulation::CSVRows,
+                inner_fields:          Vec::new(),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    fields.sort_by(|left, right| left.alias.cmp(&right.alias));
+    Some(fields)
+}
+
+/// Finds a fragmenter with a single input node and a single output node,
+/// which declares a single fragment to send its input to. Fragmenters with
+/// several output nodes are kept, since only fragmenters may fan out.
+fn find_noop_fragmenter(graph: &DiGraphOperators) -> Option<NodeIndex> {
+    graph.node_indices().find(|idx| {
+        let Operator::FragmentOp { config } = &graph[*idx].operator else {
+            return false;
+        };
+        config.to.len() == 1
+            && single_input(graph, *idx).is_some()
+            && graph.edges(*idx).count() == 1
+    })
+}
+
+/// Labels the output edges of the node, which has a single input edge, with
+/// the fragment of its input edge.
+fn relabel_output_edges(graph: &mut DiGraphOperators, node_idx: NodeIndex) {
+    let Some(input_fragment) = graph
+        .edges_directed(node_idx, Direction::Incoming)
+        .next()
+        .map(|edge| edge.weight().fragment.clone())
+    else {
+        return;
+    };
+    let output_edges: Vec<_> = graph
+        .edges_directed(node_idx, Direction::Outgoing)
+        .map(|edge| edge.id())
+        .collect();
+    for edge_idx in output_edges {
+        graph[edge_idx].fragment = input_fragment.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use operator::template::parse_template;
+    use operator::{Function, IOType, Iterator, Serializer, Target};
+
+    use super::*;
+    use crate::plan::{PlanEdge, PlanNode};
+
+    fn node(id: &str, operator: Operator) -> PlanNode {
+        PlanNode {
+            id: id.to_string(),
+            operator,
+        }
+    }
+
+    fn edge(fragment: &str) -> PlanEdge {
+        PlanEdge {
+            fragment:  fragment.to_string(),
+            join_side: None,
+        }
+    }
+
+    fn csv_source() -> Operator {
+        Operator::SourceOp {
+            config: Source {
+                config:        HashMap::from([(
+                    "path".to_string(),
+                    "students.csv".to_string(),
+                )]),
+                source_type:   IOType::File,
+                root_iterator: Iterator::default(),
+            },
+        }
+    }
+
+    fn extend(pairs: &[(&str, Function)]) -> Operator {
+        Operator::ExtendOp {
+            config: Extend {
+                extend_pairs: pairs
+                    .iter()
+                    .map(|(attribute, func)| {
+                        (attribute.to_string(), func.clone())
+                    })
+                    .collect(),
+            },
+        }
+    }
+
+    fn rename(pairs: &[(&str, &str)]) -> Operator {
+        Operator::RenameOp {
+            config: Rename {
+                rename_pairs: pairs
+                    .iter()
+                    .map(|(from, to)| (from.to_string(), to.to_string()))
+                    .collect(),
+            },
+        }
+    }
+
+    fn reference(attribute: &str) -> Function {
+        Function::Reference {
+            value: attribute.to_string(),
+        }
+    }
+
+    fn serializer() -> Operator {
+        Operator::SerializerOp {
+            config: Serializer {
+                template: parse_template("?s <http://ex.com/p> ?o .").unwrap(),
+                options:  None,
+                format:   operator::formats::DataFormat::NQuads,
+            },
+        }
+    }
+
+    fn sink() -> Operator {
+        Operator::TargetOp {
+            config: Target {
+                configuration: HashMap::new(),
+                target_type:   IOType::StdOut,
+                data_format:   operator::formats::DataFormat::NQuads,
+            },
+        }
+    }
+
+    fn operators(plan: &Plan<Init>) -> Vec<Operator> {
+        let graph = plan.graph.borrow();
+        petgraph::algo::toposort(&*graph, None)
+            .unwrap()
+            .into_iter()
+            .map(|idx| graph[idx].operator.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_extends_and_renames() -> Result<(), PlanError> {
+        let mut graph = DiGraph::new();
+        let nodes = [
+            node("Source_0", csv_source()),
+            node(
+                "Projection_1",
+                Operator::ProjectOp {
+                    config: Projection {
+                        projection_attributes: HashSet::from([
+                            "id".to_string(),
+                            "name".to_string(),
+                        ]),
+                    },
+                },
+            ),
+            node("Extend_2", extend(&[("iri", reference("id"))])),
+            node("Extend_3", extend(&[("label", reference("name"))])),
+            node("Rename_4", rename(&[("iri", "s"), ("name", "n")])),
+            node("Rename_5", rename(&[("label", "o"), ("n", "name")])),
+            node("Serialize_6", serializer()),
+            node("Sink_7", sink()),
+        ]
+        .map(|node| graph.add_node(node));
+        for pair in nodes.windows(2) {
+            graph.add_edge(pair[0], pair[1], edge("default"));
+        }
+        let mut plan = Plan::from_graph(graph)?;
+
+        Optimizer::default()
+            .without_rule(OptimizerRule::PushProjections)
+            .optimize(&mut plan)?;
+
+        assert_eq!(
+            &operators(&plan)[2..4],
+            &[
+                extend(&[("s", reference("id")), ("o", reference("name"))]),
+                serializer()
+            ]
+        );
+
+        // The second extend refers to the attribute of the first one
+        let mut graph = DiGraph::new();
+        let nodes = [
+            node("Source_0", csv_source()),
+            node("Extend_1", extend(&[("s", reference("id"))])),
+            node("Extend_2", extend(&[("o", reference("s"))])),
+        ]
+        .map(|node| graph.add_node(node));
+        for pair in nodes.windows(2) {
+            graph.add_edge(pair[0], pair[1], edge("default"));
+        }
+        let mut plan = Plan::from_graph(graph)?;
+
+        assert_eq!(Optimizer::default().optimize(&mut plan)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_projections_and_drop_fragmenters() -> Result<(), PlanError> {
+        let mut graph = DiGraph::new();
+        let source = graph.add_node(node("Source_0", csv_source()));
+        let projection = graph.add_node(node(
+            "Projection_1",
+            Operator::ProjectOp {
+                config: Projection {
+                    projection_attributes: HashSet::from([
+                        "name".to_string(),
+                        "id".to_string(),
+                    ]),
+                },
+            },
+        ));
+        let fragmenter = graph.add_node(node(
+            "Fragmenter_2",
+            Operator::FragmentOp {
+                config: operator::Fragmenter {
+                    from: "default".to_string(),
+                    to:   vec!["default".to_string(), "join_0".to_string()],
+                },
+            },
+        ));
+        let other_source = graph.add_node(node("Source_3", csv_source()));
+        let join = graph.add_node(node(
+            "Join_4",
+            Operator::JoinOp {
+                config: operator::Join {
+                    join_conditions: vec![operator::JoinCondition::equal(
+                        "id", "id",
+                    )],
+                    join_type:       operator::JoinType::InnerJoin,
+                    join_alias:      "join_0".to_string(),
+                },
+            },
+        ));
+        let extend = graph
+            .add_node(node("Extend_5", extend(&[("s", reference("name"))])));
+        graph.add_edge(source, projection, edge("default"));
+        graph.add_edge(projection, fragmenter, edge("default"));
+        graph.add_edge(other_source, join, edge("join_0"));
+        graph.add_edge(fragmenter, join, edge("join_0"));
+        graph.add_edge(fragmenter, extend, edge("default"));
+        let mut plan = Plan::from_graph(graph)?;
+
+        // The fragmenter is kept, it sends its input to two fragments
+        assert_eq!(Optimizer::default().optimize(&mut plan)?, 1);
+
+        let graph = plan.graph.borrow();
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(plan.sources.borrow().len(), 2);
+        let Operator::SourceOp { config } = &graph[NodeIndex::new(0)].operator
+        else {
+            panic!("The first node should be the source");
+        };
+        let fields: Vec<_> = config
+            .root_iterator
+            .fields
+            .iter()
+            .map(|field| (field.alias.as_str(), field.reference.as_str()))
+            .collect();
+        assert_eq!(fields, vec![("id", "id"), ("name", "name")]);
+
+        // The join inputs keep their order
+        let join_inputs: Vec<_> = graph
+            .edge_indices()
+            .filter_map(|idx| graph.edge_endpoints(idx))
+            .filter(|(_, target)| graph[*target].id == "Join_4")
+            .map(|(source, _)| graph[source].id.as_str())
+            .collect();
+        assert_eq!(join_inputs, vec!["Source_3", "Fragmenter_2"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_drop_single_fragment_fragmenters() -> Result<(), PlanError> {
+        let single_fragmenter = |id: &str, fragment: &str| {
+            node(
+                id,
+                Operator::FragmentOp {
+                    config: operator::Fragmenter {
+                        from: "default".to_string(),
+                        to:   vec![fragment.to_string()],
+                    },
+                },
+            )
+        };
+        let mut graph = DiGraph::new();
+        let source = graph.add_node(node("Source_0", csv_source()));
+        let fragmenter =
+            graph.add_node(single_fragmenter("Fragmenter_1", "tm0"));
+        let name_extend = graph
+            .add_node(node("Extend_2", extend(&[("s", reference("name"))])));
+        let fanning_fragmenter =
+            graph.add_node(single_fragmenter("Fragmenter_3", "tm1"));
+        let id_extend =
+            graph.add_node(node("Extend_4", extend(&[("o", reference("id"))])));
+        let label_extend = graph
+            .add_node(node("Extend_5", extend(&[("l", reference("name"))])));
+        graph.add_edge(source, fragmenter, edge("default"));
+        graph.add_edge(fragmenter, name_extend, edge("tm0"));
+        graph.add_edge(name_extend, fanning_fragmenter, edge("default"));
+        graph.add_edge(fanning_fragmenter, id_extend, edge("tm1"));
+        graph.add_edge(fanning_fragmenter, label_extend, edge("tm1"));
+        let mut plan = Plan::from_graph(graph)?;
+
+        // The second fragmenter is kept, dropping it would make the extend
+        // fan out
+        let optimizer =
+            Optimizer::new(vec![OptimizerRule::DropNoopFragmenters]);
+        assert_eq!(optimizer.optimize(&mut plan)?, 1);
+
+        let graph = plan.graph.borrow();
+        let mut edges: Vec<_> = graph
+            .edge_indices()
+            .filter_map(|idx| {
+                let (source, target) = graph.edge_endpoints(idx)?;
+                Some((
+                    graph[source].id.as_str(),
+                    graph[target].id.as_str(),
+                    graph[idx].fragment.as_str(),
+                ))
+            })
+            .collect();
+        edges.sort();
+        assert_eq!(
+            edges,
+            vec![
+                ("Extend_2", "Fragmenter_3", "default"),
+                ("Fragmenter_3", "Extend_4", "tm1"),
+                ("Fragmenter_3", "Extend_5", "tm1"),
+                ("Source_0", "Extend_2", "default"),
+            ]
+        );
+        Ok(())
+    }
+}
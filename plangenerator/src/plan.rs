@@ -1,18 +1,25 @@
 use std::cell::RefCell;
 use std::fmt::{Debug, Display};
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{BufWriter, Read, Write};
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::rc::Rc;
 
 use anyhow::Result;
 use operator::display::PrettyDisplay;
-use operator::{Fragmenter, Join, Operator, Serializer, Source, Target};
+use operator::{
+    Aggregate, Distinct, Filter, Fragmenter, Join, JoinCondition, JoinType,
+    Operator, PredicateType, Serializer, Sort, Source, Target, Union, Unnest,
+};
+use petgraph::algo::toposort;
 use petgraph::dot::Dot;
 use petgraph::graph::{DiGraph, NodeIndex};
-use serde::Serialize;
-use serde_json::json;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
 use crate::error::PlanError;
 
@@ -24,7 +31,7 @@ pub type RcRefCellVSourceIdxs = Rc<RefCell<VSourceIdxs>>;
 
 pub type RcRefCellPlan<T> = Rc<RefCell<Plan<T>>>;
 
-const DEFAULT_FRAGMENT: &'static str = "default";
+pub(crate) const DEFAULT_FRAGMENT: &'static str = "default";
 // Plan states in unit structs
 
 #[derive(Debug, Clone)]
@@ -57,6 +64,90 @@ impl Plan<()> {
             last_node_idx:     None,
         }
     }
+
+    /// Loads a plan from the JSON output of [write_json](Plan::write_json).
+    ///
+    /// Versioned [plan documents](crate::exchange::PlanDocument) are
+    /// recognized by their `format_version`, any other JSON is read as the
+    /// unversioned graph layout written by earlier versions of the
+    /// translator.
+    ///
+    /// Like the plans returned by the translators, the loaded plan starts in
+    /// the default fragment without a current node, while its source list
+    /// refers to all the source nodes of the graph. The builder state of the
+    /// plan which produced the graph isn't rebuilt, so the loaded nodes are
+    /// read-only for the builder methods: the plan can be validated,
+    /// optimized, executed and written, and new branches can only be added
+    /// starting at a [source](Plan::source).
+    pub fn from_json(json: &str) -> Result<Plan<Init>, PlanError> {
+        Self::from_json_value(serde_json::from_str(json)?)
+    }
+
+    pub fn from_reader<R: Read>(reader: R) -> Result<Plan<Init>, PlanError> {
+        Self::from_json_value(serde_json::from_reader(reader)?)
+    }
+
+    fn from_json_value(value: Value) -> Result<Plan<Init>, PlanError> {
+        if value.get("format_version").is_some() {
+            Self::from_document(serde_json::from_value(value)?)
+        } else {
+            Self::from_graph(serde_json::from_value(value)?)
+        }
+    }
+
+    /// Wraps a loaded graph in a plan without a current node or fragmenter,
+    /// see [from_json](Plan::from_json).
+    pub(crate) fn from_graph(
+        mut graph: DiGraphOperators,
+    ) -> Result<Plan<Init>, PlanError> {
+        toposort(&graph, None).map_err(|cycle| {
+            PlanError::GenericError(format!(
+                "The plan contains a cycle at node {}",
+                graph[cycle.node_id()].id
+            ))
+        })?;
+
+        let sources = graph
+            .node_indices()
+            .filter(|idx| {
+                matches!(graph[*idx].operator, Operator::SourceOp { .. })
+            })
+            .collect();
+        assign_missing_join_sides(&mut graph);
+
+        Ok(Plan {
+            _t:                PhantomData,
+            graph:             Rc::new(RefCell::new(graph)),
+            sources:           Rc::new(RefCell::new(sources)),
+            fragment_string:   Rc::new(DEFAULT_FRAGMENT.to_string()),
+            fragment_node_idx: None,
+            last_node_idx:     None,
+        })
+    }
+}
+
+/// Assigns the join sides to the inputs of the joins which don't have any,
+/// as plans written without join sides list the left input of a join before
+/// the right input.
+fn assign_missing_join_sides(graph: &mut DiGraphOperators) {
+    let join_idxs: Vec<_> = graph
+        .node_indices()
+        .filter(|idx| matches!(graph[*idx].operator, Operator::JoinOp { .. }))
+        .collect();
+
+    for join_idx in join_idxs {
+        let mut edge_idxs: Vec<_> = graph
+            .edges_directed(join_idx, Direction::Incoming)
+            .filter(|edge| edge.weight().join_side.is_none())
+            .map(|edge| edge.id())
+            .collect();
+        if edge_idxs.len() != 2 {
+            continue;
+        }
+        edge_idxs.sort();
+        graph[edge_idxs[0]].join_side = Some(JoinSide::Left);
+        graph[edge_idxs[1]].join_side = Some(JoinSide::Right);
+    }
 }
 
 impl<T> Plan<T> {
@@ -197,14 +288,85 @@ impl<T> Plan<T> {
         Ok(())
     }
     
+    /// Writes the plan as a versioned
+    /// [plan document](crate::exchange::PlanDocument).
     pub fn write_json(&self, path: PathBuf) -> Result<()> {
-        let graph = &*self.graph.borrow();
-        let json_string = serde_json::to_string(&graph).unwrap();
+        let document = self
+            .to_document()
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+        let json_string = serde_json::to_string(&document)?;
         write_string_to_file(path, json_string)
     }
+
+    /// Inserts a distinct operator in front of every serializer, over the
+    /// attributes bound to the template variables, so that the template is
+    /// instantiated at most once per distinct binding of its variables.
+    pub fn distinct_serializer_inputs(&mut self) -> Result<(), PlanError> {
+        let inference = self.infer_schemas()?;
+        let graph = &mut *self.graph.borrow_mut();
+
+        // The graph is rebuilt instead of removing the input edges of the
+        // serializers, since removing an edge moves the last edge of the
+        // graph into its index
+        let (nodes, edges) = std::mem::take(graph).into_nodes_edges();
+        let mut rebuilt = DiGraph::with_capacity(nodes.len(), edges.len());
+        for node in nodes {
+            rebuilt.add_node(node.weight);
+        }
+
+        for edge in edges {
+            let (input_idx, serializer_idx) = (edge.source(), edge.target());
+            let variables = match &rebuilt[serializer_idx].operator {
+                Operator::SerializerOp { config }
+                    if !matches!(
+                        rebuilt[input_idx].operator,
+                        Operator::DistinctOp { .. }
+                    ) =>
+                {
+                    config.variables()
+                }
+                _ => {
+                    rebuilt.add_edge(input_idx, serializer_idx, edge.weight);
+                    continue;
+                }
+            };
+
+            let input_schema = inference
+                .schemas
+                .get(&rebuilt[input_idx].id)
+                .cloned()
+                .unwrap_or_default();
+            let mut attributes: Vec<String> = variables
+                .iter()
+                .map(|variable| {
+                    if input_schema.attributes.contains(variable) {
+                        variable.clone()
+                    } else {
+                        format!("?{}", variable)
+                    }
+                })
+                .collect();
+            attributes.sort();
+
+            let plan_node = PlanNode {
+                id:       format!("Distinct_{}", rebuilt.node_count()),
+                operator: Operator::DistinctOp {
+                    config: Distinct {
+                        attributes: Some(attributes),
+                    },
+                },
+            };
+            let distinct_idx = rebuilt.add_node(plan_node);
+            rebuilt.add_edge(input_idx, distinct_idx, edge.weight.clone());
+            rebuilt.add_edge(distinct_idx, serializer_idx, edge.weight);
+        }
+
+        *graph = rebuilt;
+        Ok(())
+    }
 }
 
-fn write_string_to_file(
+pub(crate) fn write_string_to_file(
     path: PathBuf,
     content: String,
 ) -> Result<(), anyhow::Error> {
@@ -250,6 +412,7 @@ impl Plan<Processed> {
             Operator::SourceOp { .. }
             | Operator::FragmentOp { .. }
             | Operator::TargetOp { .. }
+            | Operator::UnionOp { .. }
             | Operator::SerializerOp { .. } => {
                 return Err(PlanError::WrongApplyOperator(operator.clone()))
             }
@@ -264,7 +427,8 @@ impl Plan<Processed> {
         };
 
         let plan_edge = PlanEdge {
-            fragment: fragment_str.to_string(),
+            fragment:  fragment_str.to_string(),
+            join_side: None,
         };
 
         let new_node_idx = self.add_node_with_edge(plan_node, plan_edge);
@@ -272,6 +436,23 @@ impl Plan<Processed> {
         Ok(self.next_idx_fragment(Some(new_node_idx), fragment_str))
     }
 
+    /// Starts a branch of the plan with the operator, which is applied to
+    /// the given output fragment of the previous fragmenter. Like a plan
+    /// which starts at a source, the branch continues in the default
+    /// fragment.
+    pub fn branch(
+        &mut self,
+        operator: &Operator,
+        node_id_prefix: &str,
+        fragment_str: &str,
+    ) -> Result<Plan<Processed>, PlanError> {
+        let mut branch =
+            self.apply_to_fragment(operator, node_id_prefix, fragment_str)?;
+        branch.fragment_string = Rc::new(DEFAULT_FRAGMENT.to_string());
+        branch.fragment_node_idx = None;
+        Ok(branch)
+    }
+
     pub fn apply(
         &mut self,
         operator: &Operator,
@@ -281,6 +462,38 @@ impl Plan<Processed> {
         self.apply_to_fragment(operator, node_id_prefix, fragment_str)
     }
 
+    pub fn filter(
+        &mut self,
+        filter: Filter,
+    ) -> Result<Plan<Processed>, PlanError> {
+        self.apply(&Operator::FilterOp { config: filter }, "Filter")
+    }
+
+    pub fn aggregate(
+        &mut self,
+        aggregate: Aggregate,
+    ) -> Result<Plan<Processed>, PlanError> {
+        self.apply(&Operator::AggregateOp { config: aggregate }, "Aggregate")
+    }
+
+    pub fn unnest(
+        &mut self,
+        unnest: Unnest,
+    ) -> Result<Plan<Processed>, PlanError> {
+        self.apply(&Operator::UnnestOp { config: unnest }, "Unnest")
+    }
+
+    pub fn distinct(
+        &mut self,
+        distinct: Distinct,
+    ) -> Result<Plan<Processed>, PlanError> {
+        self.apply(&Operator::DistinctOp { config: distinct }, "Distinct")
+    }
+
+    pub fn sort(&mut self, sort: Sort) -> Result<Plan<Processed>, PlanError> {
+        self.apply(&Operator::SortOp { config: sort }, "Sort")
+    }
+
     pub fn fragment(
         &mut self,
         fragmenter: Fragmenter,
@@ -303,7 +516,8 @@ impl Plan<Processed> {
         };
 
         let edge = PlanEdge {
-            fragment: fragmenter.from.clone(),
+            fragment:  fragmenter.from.clone(),
+            join_side: None,
         };
         let node_idx = self.add_node_with_edge(fragment_node, edge);
 
@@ -312,6 +526,28 @@ impl Plan<Processed> {
         Ok(self.next_idx(Some(node_idx)))
     }
 
+    /// Adds the fragment to the outputs of the fragmenter which ends the
+    /// plan, or ends the plan with a fragmenter sending the current fragment
+    /// to itself and to the new fragment. The returned plan continues in the
+    /// current fragment, the new fragment is consumed with
+    /// [branch](Plan::branch) or a join.
+    pub fn split_fragment(
+        &mut self,
+        fragment_str: &str,
+    ) -> Result<Plan<Processed>, PlanError> {
+        if self.fragment_node_idx == self.last_node_idx {
+            self.update_prev_fragment_node(fragment_str);
+            Ok(self.clone())
+        } else {
+            let current_fragment = self.get_fragment_str();
+            let fragmenter = Fragmenter {
+                from: current_fragment.clone(),
+                to:   vec![current_fragment, fragment_str.to_string()],
+            };
+            self.fragment(fragmenter)
+        }
+    }
+
     pub fn serialize_with_fragment(
         &mut self,
         serializer: Serializer,
@@ -332,7 +568,8 @@ impl Plan<Processed> {
         };
 
         let plan_edge = PlanEdge {
-            fragment: fragment_str.to_string(),
+            fragment:  fragment_str.to_string(),
+            join_side: None,
         };
 
         let node_idx = self.add_node_with_edge(plan_node, plan_edge);
@@ -357,23 +594,6 @@ pub fn join(
     })
 }
 
-fn add_join_fragmenter(
-    plan: &mut Plan<Processed>,
-    alias: &str,
-) -> Result<Plan<Processed>, PlanError> {
-    if plan.fragment_node_idx == plan.last_node_idx {
-        plan.update_prev_fragment_node(alias);
-        Ok(plan.clone())
-    } else {
-        let default_fragment = plan.get_fragment_str();
-        let fragmenter = Fragmenter {
-            from: default_fragment.clone(),
-            to:   vec![default_fragment, alias.to_string()],
-        };
-        plan.fragment(fragmenter)
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct NotAliasedJoinedPlan<T> {
     left_plan:  RcRefCellPlan<T>,
@@ -386,17 +606,18 @@ impl NotAliasedJoinedPlan<Processed> {
     ) -> Result<AliasedJoinedPlan<Processed>, PlanError> {
         {
             let right_plan = &mut *self.right_plan.borrow_mut();
-            *right_plan = add_join_fragmenter(right_plan, alias)?;
+            *right_plan = right_plan.split_fragment(alias)?;
         }
         {
             let left_plan = &mut *self.left_plan.borrow_mut();
-            *left_plan = add_join_fragmenter(left_plan, alias)?;
+            *left_plan = left_plan.split_fragment(alias)?;
         }
 
         Ok(AliasedJoinedPlan {
             left_plan:  Rc::clone(&self.left_plan),
             right_plan: Rc::clone(&self.right_plan),
             alias:      alias.to_string(),
+            join_type:  JoinType::InnerJoin,
         })
     }
 }
@@ -406,6 +627,7 @@ pub struct AliasedJoinedPlan<T> {
     left_plan:  RcRefCellPlan<T>,
     right_plan: RcRefCellPlan<T>,
     alias:      String,
+    join_type:  JoinType,
 }
 
 impl AliasedJoinedPlan<Processed> {
@@ -425,7 +647,8 @@ impl AliasedJoinedPlan<Processed> {
 
             let left_node = left_plan.last_node_idx.unwrap();
             let left_edge = PlanEdge {
-                fragment: fragment_str.to_string(),
+                fragment:  fragment_str.to_string(),
+                join_side: Some(JoinSide::Left),
             };
 
             graph.add_edge(left_node, node_idx, left_edge);
@@ -438,14 +661,16 @@ impl AliasedJoinedPlan<Processed> {
             if let Ok(plan) = self.right_plan.try_borrow_mut() {
                 let right_node = plan.last_node_idx.unwrap();
                 let right_edge = PlanEdge {
-                    fragment: fragment_str.to_string(),
+                    fragment:  fragment_str.to_string(),
+                    join_side: Some(JoinSide::Right),
                 };
 
                 graph.add_edge(right_node, node_idx, right_edge);
             } else {
                 let right_node = left_plan.last_node_idx.unwrap();
                 let right_edge = PlanEdge {
-                    fragment: fragment_str.to_string(),
+                    fragment:  fragment_str.to_string(),
+                    join_side: Some(JoinSide::Right),
                 };
 
                 graph.add_edge(right_node, node_idx, right_edge);
@@ -455,6 +680,15 @@ impl AliasedJoinedPlan<Processed> {
         let left_plan = self.left_plan.borrow_mut();
         left_plan.next_idx(Some(node_idx))
     }
+
+    /// Sets the type of the join conditioned by
+    /// [where_by](AliasedJoinedPlan::where_by), which is an inner join by
+    /// default.
+    pub fn join_type(&mut self, join_type: JoinType) -> &mut Self {
+        self.join_type = join_type;
+        self
+    }
+
     pub fn where_by<A>(
         &mut self,
         attributes: Vec<A>,
@@ -475,10 +709,9 @@ impl AliasedJoinedPlan<Processed> {
 
         let join_op = Operator::JoinOp {
             config: Join {
-                left_right_attr_pairs: vec![],
-                join_type:             operator::JoinType::CrossJoin,
-                predicate_type:        operator::PredicateType::Equal,
-                join_alias:            join_alias.to_string(),
+                join_conditions: vec![],
+                join_type:       JoinType::CrossJoin,
+                join_alias:      join_alias.to_string(),
             },
         };
 
@@ -488,10 +721,9 @@ impl AliasedJoinedPlan<Processed> {
     pub fn natural_join(&mut self) -> Result<Plan<Processed>, PlanError> {
         let join_op = Operator::JoinOp {
             config: Join {
-                left_right_attr_pairs: vec![],
-                join_type:             operator::JoinType::NaturalJoin,
-                predicate_type:        operator::PredicateType::Equal,
-                join_alias:            self.alias.clone(),
+                join_conditions: vec![],
+                join_type:       JoinType::NaturalJoin,
+                join_alias:      self.alias.clone(),
             },
         };
 
@@ -506,10 +738,25 @@ pub struct WhereByPlan<T> {
 }
 
 impl WhereByPlan<Processed> {
+    /// Joins on the equality of the left and right attributes, pairwise.
     pub fn compared_to<A>(
         &mut self,
         attributes: Vec<A>,
     ) -> Result<Plan<Processed>, PlanError>
+    where
+        A: Into<String>,
+    {
+        let predicate_types = vec![PredicateType::Equal; attributes.len()];
+        self.compared_with(attributes, predicate_types)
+    }
+
+    /// Joins on the comparison of the left and right attributes, pairwise,
+    /// using the predicate type of every pair.
+    pub fn compared_with<A>(
+        &mut self,
+        attributes: Vec<A>,
+        predicate_types: Vec<PredicateType>,
+    ) -> Result<Plan<Processed>, PlanError>
     where
         A: Into<String>,
     {
@@ -518,19 +765,36 @@ impl WhereByPlan<Processed> {
         let right_attributes: Vec<String> =
             attributes.into_iter().map(|a| a.into()).collect();
 
-        let left_right_attr_pairs: Vec<(String, String)> = left_attributes
-            .clone()
+        if left_attributes.len() != right_attributes.len()
+            || right_attributes.len() != predicate_types.len()
+        {
+            return Err(PlanError::GenericError(format!(
+                "Join {} needs the same number of left attributes, right attributes and predicates but got {}, {} and {}",
+                joined_plan.alias,
+                left_attributes.len(),
+                right_attributes.len(),
+                predicate_types.len()
+            )));
+        }
+
+        let join_conditions: Vec<JoinCondition> = left_attributes
             .into_iter()
-            .zip(right_attributes.clone().into_iter())
+            .zip(right_attributes)
+            .zip(predicate_types)
+            .map(|((left_attribute, right_attribute), predicate_type)| {
+                JoinCondition {
+                    left_attribute,
+                    right_attribute,
+                    predicate_type,
+                }
+            })
             .collect();
 
-        // TODO: Enable specification of join type and predicate type  <12-03-24, yourname> //
         let join_op = Operator::JoinOp {
             config: Join {
                 join_alias: joined_plan.alias.clone(),
-                left_right_attr_pairs,
-                join_type: operator::JoinType::InnerJoin,
-                predicate_type: operator::PredicateType::Equal,
+                join_conditions,
+                join_type: joined_plan.join_type.clone(),
             },
         };
 
@@ -538,6 +802,68 @@ impl WhereByPlan<Processed> {
     }
 }
 
+pub fn union(
+    plans: Vec<RcRefCellPlan<Processed>>,
+) -> Result<NotAliasedUnionPlan<Processed>, PlanError> {
+    if plans.len() < 2 {
+        return Err(PlanError::GenericError(format!(
+            "A union needs at least 2 plans but got {}",
+            plans.len()
+        )));
+    }
+
+    Ok(NotAliasedUnionPlan { plans })
+}
+
+#[derive(Debug, Clone)]
+pub struct NotAliasedUnionPlan<T> {
+    plans: Vec<RcRefCellPlan<T>>,
+}
+
+impl NotAliasedUnionPlan<Processed> {
+    pub fn alias(&mut self, alias: &str) -> Result<Plan<Processed>, PlanError> {
+        let mut branch_node_idxs = Vec::new();
+        for plan in &self.plans {
+            let plan = &mut *plan.borrow_mut();
+            // The same branch can be part of the union multiple times, it
+            // only needs to be fragmented once
+            if plan.fragment_node_idx != plan.last_node_idx
+                || !plan
+                    .get_fragment_op()
+                    .is_some_and(|frag| frag.target_fragment_exist(alias))
+            {
+                *plan = plan.split_fragment(alias)?;
+            }
+            branch_node_idxs.push(plan.last_node_idx.unwrap());
+        }
+
+        let first_plan = self.plans[0].borrow();
+        let node_idx;
+        {
+            let graph = &mut first_plan.graph.borrow_mut();
+            let union_node = PlanNode {
+                id:       format!("Union_{}", graph.node_count()),
+                operator: Operator::UnionOp {
+                    config: Union {
+                        union_alias: alias.to_string(),
+                    },
+                },
+            };
+            node_idx = graph.add_node(union_node);
+
+            for branch_node_idx in branch_node_idxs {
+                let edge = PlanEdge {
+                    fragment:  alias.to_string(),
+                    join_side: None,
+                };
+                graph.add_edge(branch_node_idx, node_idx, edge);
+            }
+        }
+
+        Ok(first_plan.next_idx(Some(node_idx)))
+    }
+}
+
 impl Plan<Serialized> {
     pub fn sink(&mut self, sink: &Target) -> Result<Plan<Sunk>, PlanError> {
         if self.last_node_idx.is_none() {
@@ -556,7 +882,8 @@ impl Plan<Serialized> {
         let prev_node_idx = self.last_node_idx.unwrap();
 
         let plan_edge = PlanEdge {
-            fragment: self.get_fragment_str().to_string(),
+            fragment:  self.get_fragment_str().to_string(),
+            join_side: None,
         };
         graph.add_edge(prev_node_idx, node_idx, plan_edge);
 
@@ -564,15 +891,39 @@ impl Plan<Serialized> {
     }
 }
 
-#[derive(Clone, Serialize)]
+/// Input of a join which is sent over an edge.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+pub enum JoinSide {
+    Left,
+    Right,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlanEdge {
-    pub fragment: String,
+    pub fragment:  String,
+    /// Input of the join which the edge leads into, the edges into other
+    /// operators don't have a join side.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub join_side: Option<JoinSide>,
 }
 
 impl Default for PlanEdge {
     fn default() -> Self {
         Self {
-            fragment: DEFAULT_FRAGMENT.to_string(),
+            fragment:  DEFAULT_FRAGMENT.to_string(),
+            join_side: None,
         }
     }
 }
@@ -589,7 +940,7 @@ impl Debug for PlanEdge {
     }
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlanNode {
     pub id:       String,
     pub operator: Operator,
@@ -624,8 +975,14 @@ impl Display for PlanNode {
 #[cfg(test)]
 mod tests {
     use std::collections::{HashMap, HashSet};
+    use std::sync::Arc;
 
-    use operator::{Iterator, Projection, Rename};
+    use operator::formats::DataFormat;
+    use operator::template::parse_template;
+    use operator::{
+        AggregateFunction, Extend, Function, IOType, Iterator, Predicate,
+        Projection, Rename, SortKey, SortOrder,
+    };
 
     use super::*;
 
@@ -692,4 +1049,406 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_plan_union() -> std::result::Result<(), PlanError> {
+        let mut plan = Plan::new();
+        let source = Source {
+            config:        HashMap::new(),
+            source_type:   operator::IOType::File,
+            root_iterator: Iterator::default(),
+        };
+        let first = Rc::new(RefCell::new(plan.source(source.clone())));
+        let second = Rc::new(RefCell::new(plan.source(source)));
+
+        let unioned = union(vec![first, second])?.alias("union_0")?;
+
+        let graph = plan.graph.borrow();
+        let union_idx = unioned.last_node_idx.unwrap();
+        assert_eq!(graph[union_idx].id, "Union_4");
+
+        let incoming: Vec<_> = graph
+            .edges_directed(union_idx, petgraph::Direction::Incoming)
+            .map(|edge| edge.weight().fragment.clone())
+            .collect();
+        assert_eq!(incoming, vec!["union_0", "union_0"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_join_conditions() -> std::result::Result<(), PlanError> {
+        let mut plan = Plan::new();
+        let source = Source {
+            config:        HashMap::new(),
+            source_type:   operator::IOType::File,
+            root_iterator: Iterator::default(),
+        };
+        let left = Rc::new(RefCell::new(plan.source(source.clone())));
+        let right = Rc::new(RefCell::new(plan.source(source)));
+
+        let joined = join(Rc::clone(&left), Rc::clone(&right))?
+            .alias("join_0")?
+            .join_type(JoinType::AntiJoin)
+            .where_by(vec!["id", "age"])?
+            .compared_with(
+                vec!["id", "min_age"],
+                vec![PredicateType::Equal, PredicateType::GEqual],
+            )?;
+
+        let graph = plan.graph.borrow();
+        let join_node = &graph[joined.last_node_idx.unwrap()];
+        let Operator::JoinOp { config } = &join_node.operator else {
+            panic!("Expected a join operator but got {:?}", join_node);
+        };
+        assert_eq!(config.join_type, JoinType::AntiJoin);
+        assert_eq!(
+            config.join_conditions,
+            vec![
+                JoinCondition::equal("id", "id"),
+                JoinCondition {
+                    left_attribute:  "age".to_string(),
+                    right_attribute: "min_age".to_string(),
+                    predicate_type:  PredicateType::GEqual,
+                },
+            ]
+        );
+        drop(graph);
+
+        let mismatched = join(left, right)?
+            .alias("join_1")?
+            .where_by(vec!["id", "age"])?
+            .compared_to(vec!["id"]);
+        assert!(mismatched.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_filter() -> std::result::Result<(), PlanError> {
+        let mut plan = Plan::new();
+        let source = Source {
+            config:        HashMap::new(),
+            source_type:   operator::IOType::File,
+            root_iterator: Iterator::default(),
+        };
+        let filter = Filter {
+            predicate: Predicate::IsNull {
+                function: Function::Reference {
+                    value: "first".to_string(),
+                },
+            },
+        };
+
+        let filtered = plan.source(source).filter(filter.clone())?;
+
+        let graph = plan.graph.borrow();
+        let filter_node = &graph[filtered.last_node_idx.unwrap()];
+        assert_eq!(filter_node.id, "Filter_1");
+        assert!(filter_node.operator == Operator::FilterOp { config: filter });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_distinct_serializer_inputs() -> std::result::Result<(), PlanError> {
+        let mut plan = Plan::new();
+        let source = Source {
+            config:        HashMap::new(),
+            source_type:   operator::IOType::File,
+            root_iterator: Iterator::default(),
+        };
+        let serializer = Serializer {
+            template: parse_template("?s <http://ex.com/p> ?o .").unwrap(),
+            options:  None,
+            format:   operator::formats::DataFormat::NQuads,
+        };
+        let serialized = plan.source(source).serialize(serializer)?;
+
+        plan.distinct_serializer_inputs()?;
+        // Serializers which already have a distinct input are skipped
+        plan.distinct_serializer_inputs()?;
+
+        let graph = plan.graph.borrow();
+        let inputs: Vec<_> = graph
+            .neighbors_directed(
+                serialized.last_node_idx.unwrap(),
+                petgraph::Direction::Incoming,
+            )
+            .map(|idx| &graph[idx])
+            .collect();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].id, "Distinct_2");
+        assert!(
+            inputs[0].operator
+                == Operator::DistinctOp {
+                    config: Distinct {
+                        attributes: Some(vec![
+                            "?o".to_string(),
+                            "?s".to_string()
+                        ]),
+                    },
+                }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_distinct_serializer_inputs_keep_edge_order(
+    ) -> std::result::Result<(), PlanError> {
+        let mut plan = Plan::new();
+        let serializer = Serializer {
+            template: parse_template("?s <http://ex.com/p> ?o .").unwrap(),
+            options:  None,
+            format:   DataFormat::NQuads,
+        };
+        let target = Target {
+            configuration: HashMap::new(),
+            target_type:   IOType::StdOut,
+            data_format:   DataFormat::NQuads,
+        };
+        let mut fragmented_plan =
+            plan.source(file_source("a.csv")).fragment(Fragmenter {
+                from: "default".to_string(),
+                to:   vec!["a".to_string(), "b".to_string()],
+            })?;
+        for fragment in ["a", "b"] {
+            fragmented_plan
+                .serialize_with_fragment(serializer.clone(), fragment)?
+                .sink(&target)?;
+        }
+
+        plan.distinct_serializer_inputs()?;
+
+        let graph = plan.graph.borrow();
+        let edges: Vec<_> = graph
+            .edge_references()
+            .map(|edge| {
+                (
+                    graph[edge.source()].id.as_str(),
+                    graph[edge.target()].id.as_str(),
+                    edge.weight().fragment.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("Source_0", "Fragmenter_1", "default"),
+                ("Fragmenter_1", "Distinct_6", "a"),
+                ("Distinct_6", "Serialize_2", "a"),
+                ("Serialize_2", "Sink_3", "a"),
+                ("Fragmenter_1", "Distinct_7", "b"),
+                ("Distinct_7", "Serialize_4", "b"),
+                ("Serialize_4", "Sink_5", "b"),
+            ]
+        );
+        Ok(())
+    }
+
+    fn file_source(path: &str) -> Source {
+        Source {
+            config:        HashMap::from([(
+                "path".to_string(),
+                path.to_string(),
+            )]),
+            source_type:   IOType::File,
+            root_iterator: Iterator::default(),
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() -> std::result::Result<(), PlanError> {
+        let mut plan = Plan::new();
+        let reference = |attr: &str| {
+            Function::Reference {
+                value: attr.to_string(),
+            }
+        };
+
+        let left = plan
+            .source(file_source("student.csv"))
+            .unnest(Unnest {
+                attribute: "courses".to_string(),
+            })?
+            .filter(Filter {
+                predicate: Predicate::IsNull {
+                    function: reference("name"),
+                },
+            })?
+            .apply(
+                &Operator::ExtendOp {
+                    config: Extend {
+                        extend_pairs: HashMap::from([(
+                            "?s".to_string(),
+                            Function::Iri {
+                                inner_function: Arc::new(reference("id")),
+                                base_iri:       None,
+                            },
+                        )]),
+                    },
+                },
+                "Extend",
+            )?
+            .apply(
+                &Operator::RenameOp {
+                    config: Rename {
+                        rename_pairs: HashMap::from([(
+                            "sport".to_string(),
+                            "sport_id".to_string(),
+                        )]),
+                    },
+                },
+                "Rename",
+            )?
+            .apply(
+                &Operator::ProjectOp {
+                    config: Projection {
+                        projection_attributes: HashSet::from([
+                            "?s".to_string(),
+                            "sport_id".to_string(),
+                        ]),
+                    },
+                },
+                "Projection",
+            )?
+            .distinct(Distinct::default())?
+            .sort(Sort {
+                sort_keys: vec![SortKey {
+                    attribute: "?s".to_string(),
+                    order:     SortOrder::Descending,
+                }],
+            })?
+            .aggregate(Aggregate {
+                group_by:        vec!["sport_id".to_string()],
+                aggregate_pairs: HashMap::from([(
+                    "?s".to_string(),
+                    AggregateFunction::Min {
+                        attribute: "?s".to_string(),
+                    },
+                )]),
+            })?;
+        let right = plan.source(file_source("sport.csv"));
+        let joined =
+            join(Rc::new(RefCell::new(left)), Rc::new(RefCell::new(right)))?
+                .alias("join_0")?
+                .join_type(JoinType::LeftJoin)
+                .where_by(vec!["sport_id"])?
+                .compared_to(vec!["ID"])?;
+        let other = plan.source(file_source("coach.csv"));
+        union(vec![
+            Rc::new(RefCell::new(joined)),
+            Rc::new(RefCell::new(other)),
+        ])?
+        .alias("union_0")?
+        .serialize(Serializer {
+            template: parse_template("?s <http://ex.com/p> ?o .").unwrap(),
+            options:  None,
+            format:   DataFormat::NQuads,
+        })?
+        .sink(&Target {
+            configuration: HashMap::from([(
+                "path".to_string(),
+                "output.nq".to_string(),
+            )]),
+            target_type:   IOType::StdOut,
+            data_format:   DataFormat::NQuads,
+        })?;
+
+        let json = serde_json::to_string(&*plan.graph.borrow())?;
+        let loaded = Plan::from_json(&json)?;
+
+        let graph = plan.graph.borrow();
+        let loaded_graph = loaded.graph.borrow();
+        let operator_kinds: HashSet<_> = graph
+            .node_weights()
+            .map(|node| std::mem::discriminant(&node.operator))
+            .collect();
+        assert_eq!(operator_kinds.len(), 14, "Not every operator is covered");
+
+        assert!(graph.node_weights().eq(loaded_graph.node_weights()));
+        let edges = |graph: &DiGraphOperators| {
+            graph
+                .edge_references()
+                .map(|edge| {
+                    (edge.source(), edge.target(), edge.weight().clone())
+                })
+                .collect::<Vec<_>>()
+        };
+        assert!(edges(&graph) == edges(&loaded_graph));
+        assert_eq!(*plan.sources.borrow(), *loaded.sources.borrow());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_json_rejects_cycles() {
+        let json = r#"{
+            "nodes": [
+                {"id": "Distinct_0", "operator": {"type": "DistinctOp", "config": {"attributes": null}}},
+                {"id": "Distinct_1", "operator": {"type": "DistinctOp", "config": {"attributes": null}}}
+            ],
+            "node_holes": [],
+            "edge_property": "directed",
+            "edges": [[0, 1, {"fragment": "default"}], [1, 0, {"fragment": "default"}]]
+        }"#;
+
+        assert!(matches!(
+            Plan::from_json(json),
+            Err(PlanError::GenericError(_))
+        ));
+        assert!(matches!(
+            Plan::from_json("{}"),
+            Err(PlanError::InvalidJsonPlan(_))
+        ));
+    }
+
+    #[test]
+    fn test_loaded_plan_starts_new_branches(
+    ) -> std::result::Result<(), PlanError> {
+        let serializer = Serializer {
+            template: parse_template("?s <http://ex.com/p> ?o .").unwrap(),
+            options:  None,
+            format:   DataFormat::NQuads,
+        };
+        let target = Target {
+            configuration: HashMap::new(),
+            target_type:   IOType::StdOut,
+            data_format:   DataFormat::NQuads,
+        };
+        let mut plan = Plan::new();
+        let mut fragmented_plan = plan
+            .source(file_source("student.csv"))
+            .fragment(Fragmenter {
+                from: "default".to_string(),
+                to:   vec!["tm0".to_string(), "tm1".to_string()],
+            })?;
+        for fragment in ["tm0", "tm1"] {
+            fragmented_plan
+                .serialize_with_fragment(serializer.clone(), fragment)?
+                .sink(&target)?;
+        }
+
+        let json = serde_json::to_string(&*plan.graph.borrow())?;
+        let mut loaded = Plan::from_json(&json)?;
+        assert_eq!(loaded.last_node_idx, None);
+        assert_eq!(loaded.fragment_node_idx, None);
+        assert_eq!(*loaded.fragment_string, DEFAULT_FRAGMENT);
+
+        // The new branch doesn't touch the loaded fragmenter
+        loaded
+            .source(file_source("sport.csv"))
+            .serialize(serializer)?
+            .sink(&target)?;
+        loaded.validate()?;
+        let graph = loaded.graph.borrow();
+        assert_eq!(graph.node_count(), 9);
+        assert_eq!(graph[NodeIndex::new(6)].id, "Source_6");
+        assert_eq!(
+            graph[NodeIndex::new(1)].operator,
+            plan.graph.borrow()[NodeIndex::new(1)].operator
+        );
+        Ok(())
+    }
 }
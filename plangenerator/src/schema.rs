@@ -0,0 +1,609 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+use operator::{Field, JoinType, Operator};
+use petgraph::algo::toposort;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+
+use crate::error::PlanError;
+use crate::plan::{DiGraphOperators, Plan};
+
+/// The set of attributes produced by a plan node.
+///
+/// A schema is open when the attributes can only be known at runtime, e.g.
+/// a CSV source without explicit fields produces all the columns of the
+/// file. Any attribute is assumed to exist in an open schema.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    pub attributes: HashSet<String>,
+    pub open:       bool,
+    /// The attributes of the nested items of an attribute, which become
+    /// available once the attribute is unnested.
+    pub nested:     HashMap<String, HashSet<String>>,
+}
+
+impl Schema {
+    pub fn contains(&self, attribute: &str) -> bool {
+        self.open || self.attributes.contains(attribute)
+    }
+
+    /// Replaces the `attribute` prefix of the nested attributes by
+    /// `new_attribute`.
+    fn renamed_nested(
+        &self,
+        attribute: &str,
+        new_attribute: &str,
+    ) -> Option<HashSet<String>> {
+        self.nested.get(attribute).map(|inner| {
+            inner
+                .iter()
+                .map(|inner_attribute| {
+                    format!(
+                        "{}{}",
+                        new_attribute,
+                        &inner_attribute[attribute.len()..]
+                    )
+                })
+                .collect()
+        })
+    }
+}
+
+/// How an attribute is used by the plan node that refers to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeUsage {
+    Projection,
+    FunctionReference { extend_attribute: String },
+    FilterPredicate,
+    GroupByKey,
+    AggregateArgument { aggregate_attribute: String },
+    JoinLeft,
+    JoinRight,
+    Unnest,
+    DistinctAttribute,
+    SortKey,
+    SerializerVariable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownAttribute {
+    pub node_id:   String,
+    pub attribute: String,
+    pub usage:     AttributeUsage,
+}
+
+impl Display for UnknownAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let usage = match &self.usage {
+            AttributeUsage::Projection => "projected".to_string(),
+            AttributeUsage::FunctionReference { extend_attribute } => {
+                format!("referenced by the function of {}", extend_attribute)
+            }
+            AttributeUsage::FilterPredicate => {
+                "referenced by the filter predicate".to_string()
+            }
+            AttributeUsage::GroupByKey => "used as group-by key".to_string(),
+            AttributeUsage::AggregateArgument {
+                aggregate_attribute,
+            } => format!("aggregated into {}", aggregate_attribute),
+            AttributeUsage::JoinLeft => {
+                "used as left join attribute".to_string()
+            }
+            AttributeUsage::JoinRight => {
+                "used as right join attribute".to_string()
+            }
+            AttributeUsage::Unnest => "unnested".to_string(),
+            AttributeUsage::DistinctAttribute => {
+                "used as distinct attribute".to_string()
+            }
+            AttributeUsage::SortKey => "used as sort key".to_string(),
+            AttributeUsage::SerializerVariable => {
+                "used as serializer variable".to_string()
+            }
+        };
+
+        write!(
+            f,
+            "Node {}: the attribute {} is {} but it is never produced by the upstream nodes",
+            self.node_id, self.attribute, usage
+        )
+    }
+}
+
+/// The result of the schema inference pass over a plan.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaInference {
+    /// The output schema of every plan node, keyed by the node id.
+    pub schemas:            HashMap<String, Schema>,
+    pub unknown_attributes: Vec<UnknownAttribute>,
+}
+
+impl<T> Plan<T> {
+    pub fn infer_schemas(&self) -> Result<SchemaInference, PlanError> {
+        infer_schemas(&self.graph.borrow())
+    }
+
+    /// Checks that every attribute referred to by the plan nodes is
+    /// produced by their upstream nodes.
+    pub fn check_attribute_references(&self) -> Result<(), PlanError> {
+        let inference = self.infer_schemas()?;
+        if inference.unknown_attributes.is_empty() {
+            Ok(())
+        } else {
+            Err(PlanError::UnknownAttributes(inference.unknown_attributes))
+        }
+    }
+}
+
+/// Computes the output schema of every node in topological order and
+/// collects the attribute references which cannot be resolved against the
+/// schemas of the input nodes.
+pub fn infer_schemas(
+    graph: &DiGraphOperators,
+) -> Result<SchemaInference, PlanError> {
+    let sorted_nodes = toposort(graph, None).map_err(|cycle| {
+        PlanError::GenericError(format!(
+            "The plan contains a cycle at node {}",
+            graph[cycle.node_id()].id
+        ))
+    })?;
+
+    let mut inference = SchemaInference::default();
+    for node_idx in sorted_nodes {
+        let node = &graph[node_idx];
+
+        let mut incoming: Vec<_> = graph
+            .edges_directed(node_idx, Direction::Incoming)
+            .collect();
+        incoming.sort_by_key(|edge| edge.id());
+        let inputs: Vec<Schema> = incoming
+            .iter()
+            .map(|edge| {
+                inference
+                    .schemas
+                    .get(&graph[edge.source()].id)
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect();
+        let input = inputs.first().cloned().unwrap_or_default();
+
+        let mut report = |attribute: &str, usage: AttributeUsage| {
+            inference.unknown_attributes.push(UnknownAttribute {
+                node_id: node.id.clone(),
+                attribute: attribute.to_string(),
+                usage,
+            })
+        };
+
+        let schema = match &node.operator {
+            Operator::SourceOp { config } => {
+                let iterator = &config.root_iterator;
+                let mut nested = HashMap::new();
+                let attributes = collect_field_attributes(
+                    &iterator.fields,
+                    &iterator.alias,
+                    &mut nested,
+                );
+                Schema {
+                    attributes,
+                    open: iterator.fields.is_empty(),
+                    nested,
+                }
+            }
+            Operator::ProjectOp { config } => {
+                for attribute in &config.projection_attributes {
+                    if !input.contains(attribute) {
+                        report(attribute, AttributeUsage::Projection);
+                    }
+                }
+                Schema {
+                    attributes: config.projection_attributes.clone(),
+                    open:       false,
+                    nested:     input.nested,
+                }
+            }
+            Operator::ExtendOp { config } => {
+                let mut schema = input.clone();
+                for (extend_attribute, func) in &config.extend_pairs {
+                    for attribute in func.referenced_attributes() {
+                        if !input.contains(&attribute) {
+                            report(
+                                &attribute,
+                                AttributeUsage::FunctionReference {
+                                    extend_attribute: extend_attribute.clone(),
+                                },
+                            );
+                        }
+                    }
+                    schema.attributes.insert(extend_attribute.clone());
+                }
+                schema
+            }
+            Operator::FilterOp { config } => {
+                for attribute in config.predicate.referenced_attributes() {
+                    if !input.contains(&attribute) {
+                        report(&attribute, AttributeUsage::FilterPredicate);
+                    }
+                }
+                input
+            }
+            Operator::RenameOp { config } => {
+                let mut attributes: HashSet<String> = input
+                    .attributes
+                    .iter()
+                    .map(|attribute| {
+                        config
+                            .rename_pairs
+                            .get(attribute)
+                            .unwrap_or(attribute)
+                            .clone()
+                    })
+                    .collect();
+
+                // The renamed attributes of an open schema aren't known
+                // upfront
+                if input.open {
+                    attributes.extend(config.rename_pairs.values().cloned());
+                }
+
+                let mut nested = input.nested.clone();
+                for (attribute, new_attribute) in &config.rename_pairs {
+                    if let Some(inner) =
+                        input.renamed_nested(attribute, new_attribute)
+                    {
+                        nested.remove(attribute);
+                        nested.insert(new_attribute.clone(), inner);
+                    }
+                }
+                Schema {
+                    attributes,
+                    open: input.open,
+                    nested,
+                }
+            }
+            Operator::JoinOp { config } => {
+                let right = inputs.get(1).cloned().unwrap_or_default();
+                for condition in &config.join_conditions {
+                    if !input.contains(&condition.left_attribute) {
+                        report(
+                            &condition.left_attribute,
+                            AttributeUsage::JoinLeft,
+                        );
+                    }
+                    if !right.contains(&condition.right_attribute) {
+                        report(
+                            &condition.right_attribute,
+                            AttributeUsage::JoinRight,
+                        );
+                    }
+                }
+
+                // Semi and anti joins only keep the left solution mappings
+                if matches!(
+                    config.join_type,
+                    JoinType::SemiJoin | JoinType::AntiJoin
+                ) {
+                    input
+                } else {
+                    let prefixed = |attribute: &String| {
+                        format!("{}_{}", config.join_alias, attribute)
+                    };
+                    let mut schema = input.clone();
+                    schema
+                        .attributes
+                        .extend(right.attributes.iter().map(prefixed));
+                    schema.nested.extend(right.nested.iter().map(
+                        |(attribute, inner)| {
+                            (
+                                prefixed(attribute),
+                                inner.iter().map(prefixed).collect(),
+                            )
+                        },
+                    ));
+                    schema.open |= right.open;
+                    schema
+                }
+            }
+            Operator::AggregateOp { config } => {
+                for attribute in &config.group_by {
+                    if !input.contains(attribute) {
+                        report(attribute, AttributeUsage::GroupByKey);
+                    }
+                }
+                for (aggregate_attribute, func) in &config.aggregate_pairs {
+                    match func.attribute() {
+                        Some(attribute) if !input.contains(attribute) => {
+                            report(
+                                attribute,
+                                AttributeUsage::AggregateArgument {
+                                    aggregate_attribute: aggregate_attribute
+                                        .clone(),
+                                },
+                            )
+                        }
+                        _ => (),
+                    }
+                }
+
+                let mut attributes: HashSet<String> =
+                    config.group_by.iter().cloned().collect();
+                attributes.extend(config.aggregate_pairs.keys().cloned());
+                Schema {
+                    attributes,
+                    open: false,
+                    nested: HashMap::new(),
+                }
+            }
+            Operator::UnionOp { .. } => {
+                inputs.iter().fold(Schema::default(), |mut schema, input| {
+                    schema.attributes.extend(input.attributes.iter().cloned());
+                    for (attribute, inner) in &input.nested {
+                        schema
+                            .nested
+                            .entry(attribute.clone())
+                            .or_default()
+                            .extend(inner.iter().cloned());
+                    }
+                    schema.open |= input.open;
+                    schema
+                })
+            }
+            Operator::UnnestOp { config } => {
+                if !input.contains(&config.attribute) {
+                    report(&config.attribute, AttributeUsage::Unnest);
+                }
+
+                // Unnesting an attribute without nested items keeps it
+                // as a single valued attribute
+                let mut schema = input;
+                if let Some(inner) = schema.nested.remove(&config.attribute) {
+                    schema.attributes.remove(&config.attribute);
+                    schema.attributes.extend(inner);
+                }
+                schema
+            }
+            Operator::DistinctOp { config } => {
+                for attribute in config.attributes.iter().flatten() {
+                    if !input.contains(attribute) {
+                        report(attribute, AttributeUsage::DistinctAttribute);
+                    }
+                }
+                input
+            }
+            Operator::SortOp { config } => {
+                for key in &config.sort_keys {
+                    if !input.contains(&key.attribute) {
+                        report(&key.attribute, AttributeUsage::SortKey);
+                    }
+                }
+                input
+            }
+            Operator::SerializerOp { config } => {
+                for variable in config.variables() {
+                    let prefixed = format!("?{}", variable);
+                    if !input.contains(&variable) && !input.contains(&prefixed)
+                    {
+                        report(&variable, AttributeUsage::SerializerVariable);
+                    }
+                }
+                Schema::default()
+            }
+            Operator::FragmentOp { .. } => input,
+            Operator::TargetOp { .. } => Schema::default(),
+        };
+
+        inference.schemas.insert(node.id.clone(), schema);
+    }
+
+    Ok(inference)
+}
+
+/// Collects the attributes of the fields. The attributes of the inner
+/// fields of a nested field are only registered as its nested attributes,
+/// since they require the nested field to be unnested first.
+fn collect_field_attributes(
+    fields: &[Field],
+    prefix: &Option<String>,
+    nested: &mut HashMap<String, HashSet<String>>,
+) -> HashSet<String> {
+    let mut attributes = HashSet::new();
+    for field in fields {
+        let attribute = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, field.alias),
+            None => field.alias.clone(),
+        };
+
+        if !field.inner_fields.is_empty() {
+            let inner = collect_field_attributes(
+                &field.inner_fields,
+                &Some(attribute.clone()),
+                nested,
+            );
+            nested.insert(attribute.clone(), inner);
+        }
+        attributes.insert(attribute);
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use operator::formats::{DataFormat, ReferenceFormulation};
+    use operator::template::parse_template;
+    use operator::{
+        Extend, Function, IOType, Iterator, Projection, Serializer, Source,
+        Unnest,
+    };
+
+    use super::*;
+
+    fn json_source(fields: &[&str]) -> Source {
+        let fields = fields
+            .iter()
+            .map(|field| {
+                Field {
+                    alias:                 field.to_string(),
+                    reference:             field.to_string(),
+                    reference_formulation: ReferenceFormulation::JSONPath,
+                    inner_fields:          vec![],
+                }
+            })
+            .collect();
+        Source {
+            config:        HashMap::new(),
+            source_type:   IOType::File,
+            root_iterator: Iterator {
+                reference: Some("$.students[*]".to_string()),
+                reference_formulation: ReferenceFormulation::JSONPath,
+                fields,
+                alias: Some("student".to_string()),
+            },
+        }
+    }
+
+    #[test]
+    fn test_infer_source_extend_schema(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut plan = Plan::new();
+        let extend_op = Operator::ExtendOp {
+            config: Extend {
+                extend_pairs: HashMap::from([(
+                    "?s".to_string(),
+                    Function::Iri {
+                        inner_function: Arc::new(Function::TemplateString {
+                            value: "http://ex.com/{student.id}".to_string(),
+                        }),
+                        base_iri:       None,
+                    },
+                )]),
+            },
+        };
+        plan.source(json_source(&["id", "name"]))
+            .apply(&extend_op, "Extend")?;
+
+        let inference = plan.infer_schemas()?;
+        assert!(inference.unknown_attributes.is_empty());
+        assert_eq!(
+            inference
+                .schemas
+                .get("Extend_1")
+                .map(|s| s.attributes.clone()),
+            Some(HashSet::from([
+                "student.id".to_string(),
+                "student.name".to_string(),
+                "?s".to_string()
+            ]))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_unknown_references() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let mut plan = Plan::new();
+        let projection_op = Operator::ProjectOp {
+            config: Projection {
+                projection_attributes: HashSet::from(["id".to_string()]),
+            },
+        };
+        let extend_op = Operator::ExtendOp {
+            config: Extend {
+                extend_pairs: HashMap::from([(
+                    "o".to_string(),
+                    Function::Literal {
+                        inner_function:    Arc::new(Function::Reference {
+                            value: "name".to_string(),
+                        }),
+                        dtype_function:    None,
+                        langtype_function: None,
+                    },
+                )]),
+            },
+        };
+        let serializer = Serializer {
+            template: parse_template("?s <http://ex.com/name> ?o .").unwrap(),
+            options:  None,
+            format:   DataFormat::NQuads,
+        };
+
+        let mut csv_source = json_source(&[]);
+        csv_source.root_iterator.alias = None;
+        plan.source(csv_source)
+            .apply(&projection_op, "Projection")?
+            .apply(&extend_op, "Extend")?
+            .serialize(serializer)?;
+
+        let result = plan.check_attribute_references();
+        let Err(PlanError::UnknownAttributes(unknown)) = result else {
+            panic!("Expected unknown attributes but got {:?}", result);
+        };
+        assert_eq!(
+            unknown,
+            vec![
+                UnknownAttribute {
+                    node_id:   "Extend_2".to_string(),
+                    attribute: "name".to_string(),
+                    usage:     AttributeUsage::FunctionReference {
+                        extend_attribute: "o".to_string(),
+                    },
+                },
+                UnknownAttribute {
+                    node_id:   "Serialize_3".to_string(),
+                    attribute: "s".to_string(),
+                    usage:     AttributeUsage::SerializerVariable,
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unnest_nested_fields() -> Result<(), Box<dyn std::error::Error>> {
+        let mut plan = Plan::new();
+        let mut source = json_source(&["id"]);
+        let courses = json_source(&["title"]).root_iterator.fields;
+        source.root_iterator.fields.push(Field {
+            alias:                 "courses".to_string(),
+            reference:             "courses[*]".to_string(),
+            reference_formulation: ReferenceFormulation::JSONPath,
+            inner_fields:          courses,
+        });
+        let projection_op = Operator::ProjectOp {
+            config: Projection {
+                projection_attributes: HashSet::from([
+                    "student.id".to_string(),
+                    "student.courses.title".to_string(),
+                ]),
+            },
+        };
+
+        plan.source(source)
+            .unnest(Unnest {
+                attribute: "student.courses".to_string(),
+            })?
+            .apply(&projection_op, "Projection")?;
+
+        let inference = plan.infer_schemas()?;
+        assert!(inference.unknown_attributes.is_empty());
+        assert_eq!(
+            inference.schemas["Source_0"].nested,
+            HashMap::from([(
+                "student.courses".to_string(),
+                HashSet::from(["student.courses.title".to_string()])
+            )])
+        );
+        assert_eq!(
+            inference.schemas["Unnest_1"].attributes,
+            HashSet::from([
+                "student.id".to_string(),
+                "student.courses.title".to_string()
+            ])
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,496 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+
+use operator::Operator;
+use petgraph::algo::toposort;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::{Dfs, EdgeRef, Reversed};
+use petgraph::Direction;
+
+use crate::error::PlanError;
+use crate::plan::{DiGraphOperators, JoinSide, Plan};
+
+/// A structural rule of the plan graph which is broken by a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// The node is part of a cycle.
+    Cycle,
+    /// The source node doesn't reach any target node.
+    UnreachableTarget,
+    /// A source node has input nodes.
+    SourceInput,
+    /// A non-source node has no input nodes.
+    MissingInput,
+    /// The fragmenter node sends a fragment to a node, which isn't declared
+    /// in its `to` list.
+    UndeclaredFragment { fragment: String, node_id: String },
+    /// A node which isn't a fragmenter outputs to more than one node.
+    FanOut { count: usize },
+    /// A join node doesn't have exactly two input nodes.
+    JoinInputs { count: usize },
+    /// The inputs of a join node aren't a left and a right input.
+    JoinSides,
+    /// A serializer node outputs to a node which isn't a target node.
+    SerializerOutput { node_id: String },
+    /// A target node gets its input from a node which isn't a serializer
+    /// node.
+    TargetInput { node_id: String },
+    /// A target node outputs to another node.
+    TargetOutput { node_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanViolation {
+    pub node_id: String,
+    pub kind:    ViolationKind,
+}
+
+impl Display for PlanViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match &self.kind {
+            ViolationKind::Cycle => "is part of a cycle".to_string(),
+            ViolationKind::UnreachableTarget => {
+                "is a source which doesn't reach any target".to_string()
+            }
+            ViolationKind::SourceInput => {
+                "is a source but it has input nodes".to_string()
+            }
+            ViolationKind::MissingInput => "has no input nodes".to_string(),
+            ViolationKind::UndeclaredFragment { fragment, node_id } => {
+                format!(
+                    "sends the fragment {} to {} but it doesn't declare the fragment",
+                    fragment, node_id
+                )
+            }
+            ViolationKind::FanOut { count } => {
+                format!("isn't a fragmenter but it outputs to {} nodes", count)
+            }
+            ViolationKind::JoinInputs { count } => {
+                format!("is a join with {} input nodes instead of 2", count)
+            }
+            ViolationKind::JoinSides => {
+                "is a join without a left and a right input".to_string()
+            }
+            ViolationKind::SerializerOutput { node_id } => {
+                format!(
+                    "is a serializer which outputs to the non-target node {}",
+                    node_id
+                )
+            }
+            ViolationKind::TargetInput { node_id } => {
+                format!(
+                    "is a target which gets its input from the non-serializer node {}",
+                    node_id
+                )
+            }
+            ViolationKind::TargetOutput { node_id } => {
+                format!("is a target which outputs to the node {}", node_id)
+            }
+        };
+
+        write!(f, "Node {} {}", self.node_id, message)
+    }
+}
+
+impl<T> Plan<T> {
+    /// Checks the structure of the plan graph before it is written or
+    /// executed.
+    pub fn validate(&self) -> Result<(), PlanError> {
+        let violations = find_violations(&self.graph.borrow());
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PlanError::InvalidPlan(violations))
+        }
+    }
+}
+
+/// Checks that the inputs of the join node are a left and a right input.
+pub fn has_join_sides(graph: &DiGraphOperators, join_idx: NodeIndex) -> bool {
+    let mut join_sides: Vec<_> = graph
+        .edges_directed(join_idx, Direction::Incoming)
+        .map(|edge| edge.weight().join_side)
+        .collect();
+    join_sides.sort();
+    join_sides == [Some(JoinSide::Left), Some(JoinSide::Right)]
+}
+
+/// Collects the violations of the structural rules of a plan graph in node
+/// order.
+pub fn find_violations(graph: &DiGraphOperators) -> Vec<PlanViolation> {
+    let mut violations = Vec::new();
+    let node_id = |idx: NodeIndex| graph[idx].id.clone();
+
+    if let Err(cycle) = toposort(graph, None) {
+        violations.push(PlanViolation {
+            node_id: node_id(cycle.node_id()),
+            kind:    ViolationKind::Cycle,
+        });
+    }
+
+    // Nodes from which a target node can be reached
+    let mut reaching_target = HashSet::new();
+    let reversed = Reversed(graph);
+    for target_idx in graph
+        .node_indices()
+        .filter(|idx| matches!(graph[*idx].operator, Operator::TargetOp { .. }))
+    {
+        let mut dfs = Dfs::new(reversed, target_idx);
+        while let Some(idx) = dfs.next(reversed) {
+            reaching_target.insert(idx);
+        }
+    }
+
+    for idx in graph.node_indices() {
+        let mut report = |kind| {
+            violations.push(PlanViolation {
+                node_id: node_id(idx),
+                kind,
+            })
+        };
+        let inputs: Vec<_> =
+            graph.neighbors_directed(idx, Direction::Incoming).collect();
+        let outputs: Vec<_> = graph.edges(idx).collect();
+
+        match &graph[idx].operator {
+            Operator::SourceOp { .. } => {
+                if !inputs.is_empty() {
+                    report(ViolationKind::SourceInput);
+                }
+                if !reaching_target.contains(&idx) {
+                    report(ViolationKind::UnreachableTarget);
+                }
+            }
+            _ if inputs.is_empty() => report(ViolationKind::MissingInput),
+            Operator::JoinOp { .. } if inputs.len() != 2 => {
+                report(ViolationKind::JoinInputs {
+                    count: inputs.len(),
+                })
+            }
+            Operator::JoinOp { .. } if !has_join_sides(graph, idx) => {
+                report(ViolationKind::JoinSides)
+            }
+            Operator::FragmentOp { config } => {
+                for edge in &outputs {
+                    if !config.to.contains(&edge.weight().fragment) {
+                        report(ViolationKind::UndeclaredFragment {
+                            fragment: edge.weight().fragment.clone(),
+                            node_id:  node_id(edge.target()),
+                        });
+                    }
+                }
+            }
+            Operator::SerializerOp { .. } => {
+                for edge in &outputs {
+                    if !matches!(
+                        graph[edge.target()].operator,
+                        Operator::TargetOp { .. }
+                    ) {
+                        report(ViolationKind::SerializerOutput {
+                            node_id: node_id(edge.target()),
+                        });
+                    }
+                }
+            }
+            Operator::TargetOp { .. } => {
+                for &input_idx in &inputs {
+                    if !matches!(
+                        graph[input_idx].operator,
+                        Operator::SerializerOp { .. }
+                    ) {
+                        report(ViolationKind::TargetInput {
+                            node_id: node_id(input_idx),
+                        });
+                    }
+                }
+                for edge in &outputs {
+                    report(ViolationKind::TargetOutput {
+                        node_id: node_id(edge.target()),
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        if outputs.len() > 1
+            && !matches!(graph[idx].operator, Operator::FragmentOp { .. })
+        {
+            report(ViolationKind::FanOut {
+                count: outputs.len(),
+            });
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use operator::formats::{DataFormat, ReferenceFormulation};
+    use operator::template::parse_template;
+    use operator::{
+        Extend, Fragmenter, IOType, Iterator, Join, JoinType, Projection,
+        Serializer, Source, Target,
+    };
+
+    use super::*;
+    use crate::plan::{join, PlanEdge, PlanNode};
+
+    fn csv_source() -> Source {
+        Source {
+            config:        HashMap::new(),
+            source_type:   IOType::File,
+            root_iterator: Iterator {
+                reference:             None,
+                reference_formulation: ReferenceFormulation::CSVRows,
+                fields:                vec![],
+                alias:                 None,
+            },
+        }
+    }
+
+    fn serializer() -> Serializer {
+        Serializer {
+            template: parse_template("?s <http://ex.com/name> ?o .").unwrap(),
+            options:  None,
+            format:   DataFormat::NQuads,
+        }
+    }
+
+    fn target() -> Target {
+        Target {
+            configuration: HashMap::new(),
+            target_type:   IOType::StdOut,
+            data_format:   DataFormat::NQuads,
+        }
+    }
+
+    #[test]
+    fn test_valid_plan() -> Result<(), PlanError> {
+        let mut plan = Plan::new();
+        let mut fragmented_plan =
+            plan.source(csv_source()).fragment(Fragmenter {
+                from: "default".to_string(),
+                to:   vec!["a".to_string(), "b".to_string()],
+            })?;
+        fragmented_plan
+            .serialize_with_fragment(serializer(), "a")?
+            .sink(&target())?;
+        fragmented_plan
+            .serialize_with_fragment(serializer(), "b")?
+            .sink(&target())?;
+
+        plan.validate()
+    }
+
+    #[test]
+    fn test_invalid_plan() -> Result<(), PlanError> {
+        let mut plan = Plan::new();
+        let projection_op = Operator::ProjectOp {
+            config: Projection {
+                projection_attributes: HashSet::new(),
+            },
+        };
+        plan.source(csv_source())
+            .fragment(Fragmenter {
+                from: "default".to_string(),
+                to:   vec!["a".to_string()],
+            })?
+            .apply_to_fragment(&projection_op, "Projection", "a")?
+            .serialize(serializer())?;
+        plan.source(csv_source());
+
+        // Fragmenter_1 sends an undeclared fragment to Projection_2, and
+        // Serialize_3 feeds the projection, closing a cycle
+        let mut graph = plan.graph.borrow_mut();
+        let fragment_edge = graph.edge_indices().nth(1).unwrap();
+        graph[fragment_edge].fragment = "b".to_string();
+        let serializer_idx = graph.node_indices().nth(3).unwrap();
+        let projection_idx = graph.node_indices().nth(2).unwrap();
+        graph.add_edge(serializer_idx, projection_idx, PlanEdge::default());
+        drop(graph);
+
+        let result = plan.validate();
+        let Err(PlanError::InvalidPlan(violations)) = result else {
+            panic!("Expected plan violations but got {:?}", result);
+        };
+        assert_eq!(
+            violations,
+            vec![
+                PlanViolation {
+                    node_id: "Serialize_3".to_string(),
+                    kind:    ViolationKind::Cycle,
+                },
+                PlanViolation {
+                    node_id: "Source_0".to_string(),
+                    kind:    ViolationKind::UnreachableTarget,
+                },
+                PlanViolation {
+                    node_id: "Fragmenter_1".to_string(),
+                    kind:    ViolationKind::UndeclaredFragment {
+                        fragment: "b".to_string(),
+                        node_id:  "Projection_2".to_string(),
+                    },
+                },
+                PlanViolation {
+                    node_id: "Serialize_3".to_string(),
+                    kind:    ViolationKind::SerializerOutput {
+                        node_id: "Projection_2".to_string(),
+                    },
+                },
+                PlanViolation {
+                    node_id: "Source_4".to_string(),
+                    kind:    ViolationKind::UnreachableTarget,
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_sides() -> Result<(), PlanError> {
+        let mut plan = Plan::new();
+        let left_plan = Rc::new(RefCell::new(plan.source(csv_source())));
+        let right_plan = Rc::new(RefCell::new(plan.source(csv_source())));
+        join(left_plan, right_plan)?
+            .alias("join_0")?
+            .natural_join()?
+            .serialize(serializer())?
+            .sink(&target())?;
+        plan.validate()?;
+
+        // Both inputs of the join become right inputs
+        let mut graph = plan.graph.borrow_mut();
+        graph
+            .edge_weights_mut()
+            .filter(|edge| edge.join_side.is_some())
+            .for_each(|edge| edge.join_side = Some(JoinSide::Right));
+        let join_id = graph
+            .node_weights()
+            .find(|node| matches!(node.operator, Operator::JoinOp { .. }))
+            .map(|node| node.id.clone())
+            .unwrap();
+
+        assert_eq!(
+            find_violations(&graph),
+            vec![PlanViolation {
+                node_id: join_id,
+                kind:    ViolationKind::JoinSides,
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_fan_out() -> Result<(), PlanError> {
+        let mut plan = Plan::new();
+        let projection_op = Operator::ProjectOp {
+            config: Projection {
+                projection_attributes: HashSet::new(),
+            },
+        };
+        let mut projected_plan = plan
+            .source(csv_source())
+            .apply(&projection_op, "Projection")?;
+        projected_plan.serialize(serializer())?.sink(&target())?;
+        projected_plan.serialize(serializer())?.sink(&target())?;
+
+        let result = plan.validate();
+        let Err(PlanError::InvalidPlan(violations)) = result else {
+            panic!("Expected plan violations but got {:?}", result);
+        };
+        assert_eq!(
+            violations,
+            vec![PlanViolation {
+                node_id: "Projection_1".to_string(),
+                kind:    ViolationKind::FanOut { count: 2 },
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_misplaced_inputs_and_outputs() {
+        let mut graph = DiGraphOperators::new();
+        let mut add_node = |id: &str, operator: Operator| {
+            graph.add_node(PlanNode {
+                id: id.to_string(),
+                operator,
+            })
+        };
+        let extend_op = || {
+            Operator::ExtendOp {
+                config: Extend {
+                    extend_pairs: Default::default(),
+                },
+            }
+        };
+        let source = add_node(
+            "Source_0",
+            Operator::SourceOp {
+                config: csv_source(),
+            },
+        );
+        let join = add_node(
+            "Join_1",
+            Operator::JoinOp {
+                config: Join {
+                    join_conditions: vec![],
+                    join_type:       JoinType::CrossJoin,
+                    join_alias:      "join_0".to_string(),
+                },
+            },
+        );
+        let dangling_extend = add_node("Extend_2", extend_op());
+        let fed_source = add_node(
+            "Source_3",
+            Operator::SourceOp {
+                config: csv_source(),
+            },
+        );
+        let sink = add_node("Sink_4", Operator::TargetOp { config: target() });
+        let extend = add_node("Extend_5", extend_op());
+        graph.add_edge(source, join, PlanEdge::default());
+        graph.add_edge(dangling_extend, fed_source, PlanEdge::default());
+        graph.add_edge(fed_source, sink, PlanEdge::default());
+        graph.add_edge(join, sink, PlanEdge::default());
+        graph.add_edge(sink, extend, PlanEdge::default());
+
+        let violation = |node_id: &str, kind| {
+            PlanViolation {
+                node_id: node_id.to_string(),
+                kind,
+            }
+        };
+        assert_eq!(
+            find_violations(&graph),
+            vec![
+                violation("Join_1", ViolationKind::JoinInputs { count: 1 }),
+                violation("Extend_2", ViolationKind::MissingInput),
+                violation("Source_3", ViolationKind::SourceInput),
+                violation(
+                    "Sink_4",
+                    ViolationKind::TargetInput {
+                        node_id: "Join_1".to_string(),
+                    }
+                ),
+                violation(
+                    "Sink_4",
+                    ViolationKind::TargetInput {
+                        node_id: "Source_3".to_string(),
+                    }
+                ),
+                violation(
+                    "Sink_4",
+                    ViolationKind::TargetOutput {
+                        node_id: "Extend_5".to_string(),
+                    }
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,777 @@
+//! Visualizations of mapping plans in Mermaid, GraphML and self-contained
+//! HTML, next to the Graphviz DOT output of [Plan::write].
+//!
+//! The nodes of a plan are clustered by the triples map they originate
+//! from. Plans don't record the triples maps of their nodes, so the origin
+//! of a node is derived from the source it reads through its left input,
+//! and from the branch of the source fragmenter for sources shared between
+//! triples maps. Edges are colored by their fragment.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+use std::fmt::{Display, Write as _};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use operator::Operator;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde_json::Value;
+
+use crate::error::PlanError;
+use crate::exchange::DocumentOperator;
+use crate::plan::{
+    write_string_to_file, DiGraphOperators, Plan, DEFAULT_FRAGMENT,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlanFormat {
+    /// Graphviz DOT, written in a plain and a pretty version.
+    Dot,
+    Mermaid,
+    GraphML,
+    /// A single HTML page with an interactive SVG drawing of the plan.
+    Html,
+}
+
+impl PlanFormat {
+    pub const ALL: [PlanFormat; 4] = [
+        PlanFormat::Dot,
+        PlanFormat::Mermaid,
+        PlanFormat::GraphML,
+        PlanFormat::Html,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlanFormat::Dot => "dot",
+            PlanFormat::Mermaid => "mermaid",
+            PlanFormat::GraphML => "graphml",
+            PlanFormat::Html => "html",
+        }
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        PlanFormat::ALL.iter().map(|format| format.name()).collect()
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PlanFormat::Dot => "dot",
+            PlanFormat::Mermaid => "mmd",
+            PlanFormat::GraphML => "graphml",
+            PlanFormat::Html => "html",
+        }
+    }
+}
+
+impl Display for PlanFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for PlanFormat {
+    type Err = PlanError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PlanFormat::ALL
+            .into_iter()
+            .find(|format| format.name() == s)
+            .ok_or(PlanError::UnknownPlanFormat(s.to_string()))
+    }
+}
+
+/// Colors of the fragments, assigned in the order of the fragment names.
+const PALETTE: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#59a14f", "#e15759", "#b07aa1", "#76b7b2",
+    "#edc948", "#9c755f",
+];
+
+struct NodeView {
+    id:            String,
+    operator_type: String,
+    /// The configuration of the operator as pretty-printed JSON.
+    config:        String,
+    /// Index of the triples map origin in [PlanView::origins].
+    origin:        usize,
+    /// Length of the longest path from a source to the node.
+    depth:         usize,
+}
+
+struct EdgeView {
+    from:     usize,
+    to:       usize,
+    fragment: String,
+}
+
+/// The nodes of a plan in [order](ordered_nodes), with their origin and
+/// depth.
+struct PlanView {
+    nodes:     Vec<NodeView>,
+    edges:     Vec<EdgeView>,
+    origins:   Vec<String>,
+    fragments: Vec<String>,
+}
+
+impl PlanView {
+    fn new(graph: &DiGraphOperators) -> Result<PlanView, PlanError> {
+        let sorted_nodes = ordered_nodes(graph)?;
+        let positions: HashMap<NodeIndex, usize> = sorted_nodes
+            .iter()
+            .enumerate()
+            .map(|(position, idx)| (*idx, position))
+            .collect();
+
+        let mut origins: Vec<String> = Vec::new();
+        let mut nodes: Vec<NodeView> = Vec::new();
+        for &idx in &sorted_nodes {
+            let node = &graph[idx];
+            let mut incoming: Vec<_> =
+                graph.edges_directed(idx, Direction::Incoming).collect();
+            incoming.sort_by_key(|edge| edge.id());
+
+            let depth = incoming
+                .iter()
+                .map(|edge| nodes[positions[&edge.source()]].depth + 1)
+                .max()
+                .unwrap_or_default();
+            let origin = match incoming.first() {
+                Some(edge) => {
+                    let input = &nodes[positions[&edge.source()]];
+                    if is_source_fragmenter(graph, edge.source())
+                        && edge.weight().fragment != DEFAULT_FRAGMENT
+                    {
+                        format!(
+                            "{}/{}",
+                            origins[input.origin],
+                            edge.weight().fragment
+                        )
+                    } else {
+                        origins[input.origin].clone()
+                    }
+                }
+                None => node.id.clone(),
+            };
+            let origin = match origins.iter().position(|o| *o == origin) {
+                Some(position) => position,
+                None => {
+                    origins.push(origin);
+                    origins.len() - 1
+                }
+            };
+
+            let operator =
+                serde_json::to_value(DocumentOperator::from(&node.operator))?;
+            nodes.push(NodeView {
+                id: node.id.clone(),
+                operator_type: operator["type"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                config: serde_json::to_string_pretty(
+                    operator.get("config").unwrap_or(&Value::Null),
+                )?,
+                origin,
+                depth,
+            });
+        }
+
+        let mut edges: Vec<EdgeView> = graph
+            .edge_references()
+            .map(|edge| {
+                EdgeView {
+                    from:     positions[&edge.source()],
+                    to:       positions[&edge.target()],
+                    fragment: edge.weight().fragment.clone(),
+                }
+            })
+            .collect();
+        edges.sort_by_key(|edge| (edge.from, edge.to));
+        let fragments = edges
+            .iter()
+            .map(|edge| edge.fragment.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        Ok(PlanView {
+            nodes,
+            edges,
+            origins,
+            fragments,
+        })
+    }
+
+    /// Index of the color of the fragment in the [PALETTE].
+    fn color_idx(&self, fragment: &str) -> usize {
+        let idx = self
+            .fragments
+            .iter()
+            .position(|known| known == fragment)
+            .unwrap_or_default();
+        idx % PALETTE.len()
+    }
+
+    fn color(&self, fragment: &str) -> &'static str {
+        PALETTE[self.color_idx(fragment)]
+    }
+
+    fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart TD\n");
+        for (origin_idx, origin) in self.origins.iter().enumerate() {
+            let _ = writeln!(
+                mermaid,
+                "    subgraph origin_{}[\"{}\"]",
+                origin_idx,
+                escape_mermaid(origin)
+            );
+            for (node_idx, node) in self
+                .nodes
+                .iter()
+                .enumerate()
+                .filter(|(_, n)| n.origin == origin_idx)
+            {
+                let _ = writeln!(
+                    mermaid,
+                    "        node_{}[\"{}<br/>{}\"]",
+                    node_idx,
+                    escape_mermaid(&node.id),
+                    node.operator_type
+                );
+            }
+            mermaid.push_str("    end\n");
+        }
+
+        for edge in &self.edges {
+            let _ = writeln!(
+                mermaid,
+                "    node_{} -->|\"{}\"| node_{}",
+                edge.from,
+                escape_mermaid(&edge.fragment),
+                edge.to
+            );
+        }
+        for (edge_idx, edge) in self.edges.iter().enumerate() {
+            let _ = writeln!(
+                mermaid,
+                "    linkStyle {} stroke:{}",
+                edge_idx,
+                self.color(&edge.fragment)
+            );
+        }
+        mermaid
+    }
+
+    fn to_graphml(&self) -> String {
+        let mut graphml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+             <key id=\"operator\" for=\"node\" attr.name=\"operator\" attr.type=\"string\"/>\n  \
+             <key id=\"config\" for=\"node\" attr.name=\"config\" attr.type=\"string\"/>\n  \
+             <key id=\"origin\" for=\"node\" attr.name=\"origin\" attr.type=\"string\"/>\n  \
+             <key id=\"fragment\" for=\"edge\" attr.name=\"fragment\" attr.type=\"string\"/>\n  \
+             <key id=\"color\" for=\"edge\" attr.name=\"color\" attr.type=\"string\"/>\n  \
+             <graph id=\"plan\" edgedefault=\"directed\">\n",
+        );
+
+        // Every triples map origin is a node with a nested graph
+        for (origin_idx, origin) in self.origins.iter().enumerate() {
+            let _ = write!(
+                graphml,
+                "    <node id=\"origin_{0}\">\n      \
+                 <data key=\"origin\">{1}</data>\n      \
+                 <graph id=\"origin_{0}:\" edgedefault=\"directed\">\n",
+                origin_idx,
+                escape_xml(origin)
+            );
+            for node in self.nodes.iter().filter(|n| n.origin == origin_idx) {
+                let _ = write!(
+                    graphml,
+                    "        <node id=\"{}\">\n          \
+                     <data key=\"operator\">{}</data>\n          \
+                     <data key=\"config\">{}</data>\n          \
+                     <data key=\"origin\">{}</data>\n        \
+                     </node>\n",
+                    escape_xml(&node.id),
+                    node.operator_type,
+                    escape_xml(&node.config),
+                    escape_xml(origin)
+                );
+            }
+            graphml.push_str("      </graph>\n    </node>\n");
+        }
+
+        for edge in &self.edges {
+            let _ = write!(
+                graphml,
+                "    <edge source=\"{}\" target=\"{}\">\n      \
+                 <data key=\"fragment\">{}</data>\n      \
+                 <data key=\"color\">{}</data>\n    \
+                 </edge>\n",
+                escape_xml(&self.nodes[edge.from].id),
+                escape_xml(&self.nodes[edge.to].id),
+                escape_xml(&edge.fragment),
+                self.color(&edge.fragment)
+            );
+        }
+        graphml.push_str("  </graph>\n</graphml>\n");
+        graphml
+    }
+
+    fn to_html(&self) -> Result<String, PlanError> {
+        const NODE_WIDTH: usize = 170;
+        const NODE_HEIGHT: usize = 40;
+        const COLUMN_WIDTH: usize = NODE_WIDTH + 30;
+        const ROW_HEIGHT: usize = NODE_HEIGHT + 50;
+        const CLUSTER_PADDING: usize = 10;
+        const TOP: usize = 30;
+
+        // Every origin gets a band of columns, as wide as its widest layer
+        let mut layer_columns: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut node_columns = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let column =
+                layer_columns.entry((node.origin, node.depth)).or_default();
+            node_columns.push(*column);
+            *column += 1;
+        }
+        let mut band_offsets = Vec::with_capacity(self.origins.len());
+        let mut offset = 0;
+        for origin_idx in 0..self.origins.len() {
+            band_offsets.push(offset);
+            offset += layer_columns
+                .iter()
+                .filter(|((origin, _), _)| *origin == origin_idx)
+                .map(|(_, columns)| *columns)
+                .max()
+                .unwrap_or(1);
+        }
+        let max_depth =
+            self.nodes.iter().map(|node| node.depth).max().unwrap_or(0);
+        let width = offset * COLUMN_WIDTH + 2 * CLUSTER_PADDING;
+        let height = TOP + (max_depth + 1) * ROW_HEIGHT + CLUSTER_PADDING;
+        let position = |idx: usize| {
+            let node = &self.nodes[idx];
+            let x = CLUSTER_PADDING
+                + (band_offsets[node.origin] + node_columns[idx])
+                    * COLUMN_WIDTH
+                + (COLUMN_WIDTH - NODE_WIDTH) / 2;
+            let y = TOP + node.depth * ROW_HEIGHT + CLUSTER_PADDING;
+            (x, y)
+        };
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+            width, height
+        );
+        svg.push_str("<defs>\n");
+        for (color_idx, color) in PALETTE.iter().enumerate() {
+            let _ = writeln!(
+                svg,
+                "<marker id=\"arrow-{}\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"7\" markerHeight=\"7\" orient=\"auto\"><path d=\"M0,0 L10,5 L0,10 z\" fill=\"{}\"/></marker>",
+                color_idx, color
+            );
+        }
+        svg.push_str("</defs>\n");
+
+        for (origin_idx, origin) in self.origins.iter().enumerate() {
+            let depths = self
+                .nodes
+                .iter()
+                .filter(|node| node.origin == origin_idx)
+                .map(|node| node.depth);
+            let (Some(first), Some(last)) =
+                (depths.clone().min(), depths.max())
+            else {
+                continue;
+            };
+            let columns = band_offsets.get(origin_idx + 1).unwrap_or(&offset)
+                - band_offsets[origin_idx];
+            let _ = writeln!(
+                svg,
+                "<g class=\"cluster\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"6\"/><text x=\"{}\" y=\"{}\">{}</text></g>",
+                CLUSTER_PADDING + band_offsets[origin_idx] * COLUMN_WIDTH + 4,
+                TOP + first * ROW_HEIGHT,
+                columns * COLUMN_WIDTH - 8,
+                (last - first + 1) * ROW_HEIGHT - 10,
+                CLUSTER_PADDING + band_offsets[origin_idx] * COLUMN_WIDTH + 10,
+                TOP + first * ROW_HEIGHT + 14,
+                escape_xml(origin)
+            );
+        }
+
+        for edge in &self.edges {
+            let (from_x, from_y) = position(edge.from);
+            let (to_x, to_y) = position(edge.to);
+            let color_idx = self.color_idx(&edge.fragment);
+            let _ = writeln!(
+                svg,
+                "<line class=\"edge\" data-from=\"{}\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"2\" marker-end=\"url(#arrow-{})\"><title>{}</title></line>",
+                escape_xml(&self.nodes[edge.from].id),
+                from_x + NODE_WIDTH / 2,
+                from_y + NODE_HEIGHT,
+                to_x + NODE_WIDTH / 2,
+                to_y,
+                self.color(&edge.fragment),
+                color_idx,
+                escape_xml(&edge.fragment)
+            );
+        }
+
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let (x, y) = position(idx);
+            let _ = writeln!(
+                svg,
+                "<g class=\"node\" data-node=\"{}\"><title>{}</title><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\"/><text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text><text x=\"{}\" y=\"{}\" text-anchor=\"middle\" class=\"type\">{}</text></g>",
+                escape_xml(&node.id),
+                escape_xml(&node.config),
+                x,
+                y,
+                NODE_WIDTH,
+                NODE_HEIGHT,
+                x + NODE_WIDTH / 2,
+                y + 17,
+                escape_xml(&node.id),
+                x + NODE_WIDTH / 2,
+                y + 32,
+                node.operator_type
+            );
+        }
+        svg.push_str("</svg>\n");
+
+        let mut legend = String::new();
+        for fragment in &self.fragments {
+            let _ = write!(
+                legend,
+                "<span><i style=\"background:{}\"></i>{}</span>",
+                self.color(fragment),
+                escape_xml(fragment)
+            );
+        }
+
+        let edges: Vec<(&str, &str)> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    self.nodes[edge.from].id.as_str(),
+                    self.nodes[edge.to].id.as_str(),
+                )
+            })
+            .collect();
+        let edges_json = serde_json::to_string(&edges)?.replace("</", "<\\/");
+
+        Ok(HTML_TEMPLATE
+            .replace("{legend}", &legend)
+            .replace("{svg}", &svg)
+            .replace("{edges}", &edges_json))
+    }
+}
+
+/// Orders the nodes such that every node comes after its inputs, and
+/// otherwise in the order in which they were added to the plan.
+fn ordered_nodes(
+    graph: &DiGraphOperators,
+) -> Result<Vec<NodeIndex>, PlanError> {
+    let mut in_degrees: Vec<usize> = graph
+        .node_indices()
+        .map(|idx| graph.edges_directed(idx, Direction::Incoming).count())
+        .collect();
+    let mut ready: BinaryHeap<Reverse<NodeIndex>> = graph
+        .node_indices()
+        .filter(|idx| in_degrees[idx.index()] == 0)
+        .map(Reverse)
+        .collect();
+
+    let mut sorted_nodes = Vec::with_capacity(graph.node_count());
+    while let Some(Reverse(idx)) = ready.pop() {
+        sorted_nodes.push(idx);
+        for edge in graph.edges(idx) {
+            let in_degree = &mut in_degrees[edge.target().index()];
+            *in_degree -= 1;
+            if *in_degree == 0 {
+                ready.push(Reverse(edge.target()));
+            }
+        }
+    }
+
+    match graph.node_indices().find(|idx| in_degrees[idx.index()] > 0) {
+        Some(idx) => {
+            Err(PlanError::GenericError(format!(
+                "The plan contains a cycle at node {}",
+                graph[idx].id
+            )))
+        }
+        None => Ok(sorted_nodes),
+    }
+}
+
+/// Whether the node is a fragmenter which directly splits the output of a
+/// source between several triples maps.
+fn is_source_fragmenter(graph: &DiGraphOperators, idx: NodeIndex) -> bool {
+    matches!(graph[idx].operator, Operator::FragmentOp { .. })
+        && graph
+            .neighbors_directed(idx, Direction::Incoming)
+            .any(|input| {
+                matches!(graph[input].operator, Operator::SourceOp { .. })
+            })
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Escapes the text of a quoted Mermaid label with entity codes, since
+/// labels may contain HTML.
+fn escape_mermaid(value: &str) -> String {
+    value
+        .replace('#', "#35;")
+        .replace('&', "#amp;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace('"', "#quot;")
+        .replace('|', "#124;")
+}
+
+const HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Mapping plan</title>
+<style>
+body { font-family: sans-serif; margin: 16px; }
+.legend span { margin-right: 16px; font-size: 13px; }
+.legend i { display: inline-block; width: 12px; height: 12px; margin-right: 4px; vertical-align: middle; }
+.cluster rect { fill: #f5f5f5; stroke: #bbbbbb; }
+.cluster text { font-size: 11px; fill: #666666; }
+.node { cursor: pointer; }
+.node rect { fill: #ffffff; stroke: #333333; }
+.node text { font-size: 12px; }
+.node .type { fill: #666666; font-size: 11px; }
+.node.collapsed rect { fill: #e8e8e8; stroke-dasharray: 4 2; }
+.hidden { display: none; }
+</style>
+</head>
+<body>
+<p>Hover a node to show the configuration of its operator, click a node to collapse or expand the nodes downstream of it.</p>
+<div class="legend">Fragments: {legend}</div>
+{svg}<script>
+const edges = {edges};
+const collapsed = new Set();
+const nodes = Array.from(document.querySelectorAll(".node"));
+
+function update() {
+  const targets = new Set(edges.map(([, to]) => to));
+  const stack = nodes.map((node) => node.dataset.node).filter((id) => !targets.has(id));
+  const visible = new Set();
+  while (stack.length > 0) {
+    const id = stack.pop();
+    if (visible.has(id)) continue;
+    visible.add(id);
+    if (collapsed.has(id)) continue;
+    edges.filter(([from]) => from === id).forEach(([, to]) => stack.push(to));
+  }
+  nodes.forEach((node) => {
+    node.classList.toggle("hidden", !visible.has(node.dataset.node));
+    node.classList.toggle("collapsed", collapsed.has(node.dataset.node));
+  });
+  document.querySelectorAll(".edge").forEach((edge) => {
+    const from = edge.dataset.from;
+    edge.classList.toggle("hidden", !visible.has(from) || collapsed.has(from));
+  });
+}
+
+nodes.forEach((node) => node.addEventListener("click", () => {
+  const id = node.dataset.node;
+  if (!collapsed.delete(id)) collapsed.add(id);
+  update();
+}));
+</script>
+</body>
+</html>
+"#;
+
+impl<T> Plan<T> {
+    pub fn to_mermaid(&self) -> Result<String, PlanError> {
+        Ok(PlanView::new(&self.graph.borrow())?.to_mermaid())
+    }
+
+    pub fn to_graphml(&self) -> Result<String, PlanError> {
+        Ok(PlanView::new(&self.graph.borrow())?.to_graphml())
+    }
+
+    /// Renders the plan as a single HTML page without external resources.
+    /// The configuration of an operator is shown when hovering its node,
+    /// and clicking a node collapses the nodes downstream of it.
+    pub fn to_html(&self) -> Result<String, PlanError> {
+        PlanView::new(&self.graph.borrow())?.to_html()
+    }
+
+    pub fn write_mermaid(&self, path: PathBuf) -> anyhow::Result<()> {
+        let mermaid = self
+            .to_mermaid()
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+        write_string_to_file(path, mermaid)
+    }
+
+    pub fn write_graphml(&self, path: PathBuf) -> anyhow::Result<()> {
+        let graphml = self
+            .to_graphml()
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+        write_string_to_file(path, graphml)
+    }
+
+    pub fn write_html(&self, path: PathBuf) -> anyhow::Result<()> {
+        let html = self
+            .to_html()
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+        write_string_to_file(path, html)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use operator::formats::{DataFormat, ReferenceFormulation};
+    use operator::template::parse_template;
+    use operator::{Fragmenter, IOType, Iterator, Serializer, Source, Target};
+    use petgraph::dot::Dot;
+
+    use super::*;
+
+    fn shared_source_plan() -> Result<Plan<crate::plan::Init>, PlanError> {
+        let mut plan = Plan::new();
+        let source = Source {
+            config:        HashMap::from([(
+                "path".to_string(),
+                "<students>.csv".to_string(),
+            )]),
+            source_type:   IOType::File,
+            root_iterator: Iterator {
+                reference:             None,
+                reference_formulation: ReferenceFormulation::CSVRows,
+                fields:                vec![],
+                alias:                 None,
+            },
+        };
+        let serializer = Serializer {
+            template: parse_template("?s <http://ex.com/name> ?o .").unwrap(),
+            options:  None,
+            format:   DataFormat::NQuads,
+        };
+        let target = Target {
+            configuration: HashMap::new(),
+            target_type:   IOType::StdOut,
+            data_format:   DataFormat::NQuads,
+        };
+
+        let mut fragmented_plan = plan.source(source).fragment(Fragmenter {
+            from: "default".to_string(),
+            to:   vec!["tm0".to_string(), "tm1".to_string()],
+        })?;
+        for fragment in ["tm0", "tm1"] {
+            fragmented_plan
+                .serialize_with_fragment(serializer.clone(), fragment)?
+                .sink(&target)?;
+        }
+        Ok(plan)
+    }
+
+    #[test]
+    fn test_mermaid_clusters() -> Result<(), PlanError> {
+        let mermaid = shared_source_plan()?.to_mermaid()?;
+        assert_eq!(
+            mermaid,
+            "flowchart TD
+    subgraph origin_0[\"Source_0\"]
+        node_0[\"Source_0<br/>SourceOp\"]
+        node_1[\"Fragmenter_1<br/>FragmentOp\"]
+    end
+    subgraph origin_1[\"Source_0/tm0\"]
+        node_2[\"Serialize_2<br/>SerializerOp\"]
+        node_3[\"Sink_3<br/>TargetOp\"]
+    end
+    subgraph origin_2[\"Source_0/tm1\"]
+        node_4[\"Serialize_4<br/>SerializerOp\"]
+        node_5[\"Sink_5<br/>TargetOp\"]
+    end
+    node_0 -->|\"default\"| node_1
+    node_1 -->|\"tm0\"| node_2
+    node_1 -->|\"tm1\"| node_4
+    node_2 -->|\"tm0\"| node_3
+    node_4 -->|\"tm1\"| node_5
+    linkStyle 0 stroke:#4e79a7
+    linkStyle 1 stroke:#f28e2b
+    linkStyle 2 stroke:#59a14f
+    linkStyle 3 stroke:#f28e2b
+    linkStyle 4 stroke:#59a14f
+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_escaped_configs() -> Result<(), PlanError> {
+        let plan = shared_source_plan()?;
+
+        let graphml = plan.to_graphml()?;
+        assert!(graphml
+            .contains("&quot;path&quot;: &quot;&lt;students&gt;.csv&quot;"));
+        assert!(!graphml.contains("<students>"));
+
+        let html = plan.to_html()?;
+        assert_eq!(html.matches("<g class=\"node\"").count(), 6);
+        assert_eq!(html.matches("<g class=\"cluster\"").count(), 3);
+        assert!(!html.contains("<students>"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_escaped_ids_and_labels() -> Result<(), PlanError> {
+        let plan = shared_source_plan()?;
+        {
+            let mut graph = plan.graph.borrow_mut();
+            graph[NodeIndex::new(0)].id = "Source <\"0\"> & #1".to_string();
+            graph[NodeIndex::new(2)].id = "Serialize|2".to_string();
+            let edge_idx = graph.find_edge(1.into(), 2.into()).unwrap();
+            graph[edge_idx].fragment = "tm0|<a>".to_string();
+        }
+
+        let mermaid = plan.to_mermaid()?;
+        assert!(mermaid.contains(
+            "subgraph origin_0[\"Source #lt;#quot;0#quot;#gt; #amp; #35;1\"]"
+        ));
+        assert!(mermaid.contains(
+            "node_0[\"Source #lt;#quot;0#quot;#gt; #amp; #35;1<br/>SourceOp\"]"
+        ));
+        assert!(
+            mermaid.contains("node_2[\"Serialize#124;2<br/>SerializerOp\"]")
+        );
+        assert!(mermaid.contains("node_1 -->|\"tm0#124;#lt;a#gt;\"| node_2"));
+
+        let graphml = plan.to_graphml()?;
+        assert!(graphml
+            .contains("<node id=\"Source &lt;&quot;0&quot;&gt; &amp; #1\">"));
+        assert!(graphml.contains("<data key=\"fragment\">tm0|&lt;a&gt;</data>"));
+
+        let html = plan.to_html()?;
+        assert!(html
+            .contains("data-node=\"Source &lt;&quot;0&quot;&gt; &amp; #1\""));
+        assert!(html.contains("<title>tm0|&lt;a&gt;</title>"));
+        assert!(!html.contains("<a>"));
+
+        let dot = format!("{:?}", Dot::with_config(&*plan.graph.borrow(), &[]));
+        assert!(dot.contains(r#"{\"id\":\"Source <\\\"0\\\"> & #1\""#));
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fs::read_to_string;
+use std::io::BufRead;
 use std::path::PathBuf;
 
+use lazy_static::lazy_static;
+use regex::Regex;
 use sophia_api::triple::stream::TripleSource;
 use sophia_inmem::graph::FastGraph;
 use sophia_turtle::parser::turtle;
@@ -11,6 +13,11 @@ use super::triplesmap_extractor::extract_triples_maps;
 use super::ExtractorResult;
 use crate::rml_model::Document;
 
+lazy_static! {
+    static ref BASE_REGEX: Regex =
+        Regex::new(r"(?m)^\s*(?:@base|(?i:base))\s*<([^>]*)>").unwrap();
+}
+
 pub fn load_graph_bread(buf_read: impl BufRead) -> ExtractorResult<FastGraph> {
     match turtle::parse_bufread(buf_read).collect_triples() {
         Ok(it) => Ok(it),
@@ -35,10 +42,21 @@ pub fn load_graph_str(input_str: &str) -> ExtractorResult<FastGraph> {
     }
 }
 
+/// Extracts the base IRI declared by the first `@base` or `BASE` directive
+/// of the turtle document.
+fn extract_base_iri(input_str: &str) -> Option<String> {
+    BASE_REGEX
+        .captures(input_str)
+        .map(|captures| captures[1].to_string())
+}
+
 pub fn parse_str(input_str: &str) -> ExtractorResult<Document> {
     let graph = load_graph_str(input_str)?;
     let triples_maps = extract_triples_maps(&graph)?;
-    Ok(Document { triples_maps })
+    Ok(Document {
+        triples_maps,
+        default_base_iri: extract_base_iri(input_str),
+    })
 }
 
 pub fn parse_file(path: PathBuf) -> ExtractorResult<Document> {
@@ -50,9 +68,7 @@ pub fn parse_file(path: PathBuf) -> ExtractorResult<Document> {
             )));
         }
 
-        let buf_read = BufReader::new(File::open(path)?);
-        let triples_maps = extract_triples_maps(&load_graph_bread(buf_read)?)?;
-        return Ok(Document { triples_maps });
+        return parse_str(&read_to_string(path)?);
     }
 
     Err(ParseError::IOErrorStr(format!(
@@ -87,4 +103,18 @@ mod tests {
         // One TriplesMap should be parsed
         assert!(parsed_res.unwrap().triples_maps.len() == 2);
     }
+
+    #[test]
+    fn base_iri_test() -> ExtractorResult<()> {
+        let path = PathBuf::from(test_case!("sample_mapping.ttl"));
+        assert_eq!(
+            parse_file(path)?.default_base_iri,
+            Some("http://example.com/ns#".to_string())
+        );
+
+        let document =
+            parse_str("@prefix rr: <http://www.w3.org/ns/r2rml#> .")?;
+        assert_eq!(document.default_base_iri, None);
+        Ok(())
+    }
 }
@@ -7,9 +7,22 @@ use super::{ExtractorResult, FromVocab, TermMapExtractor};
 use crate::extractors::store::{get_object, get_objects};
 use crate::extractors::Extractor;
 use crate::rml_model::join::JoinCondition;
-use crate::rml_model::term_map::{GraphMap, ObjectMap, TermMapInfo};
+use crate::rml_model::term_map::{
+    GraphMap, ObjectMap, TermMapInfo, TermMapType,
+};
 use crate::IriString;
 
+/// Checks that the language tag is well-formed and starts with an ISO 639
+/// language code, since no primary language subtag longer than 3 letters has
+/// been registered.
+fn is_registered_language_tag(tag: &str) -> bool {
+    let primary = tag.split('-').next().unwrap_or_default();
+    operator::is_valid_language_tag(tag)
+        && ((2..=3).contains(&primary.len())
+            || primary.eq_ignore_ascii_case("x")
+            || primary.eq_ignore_ascii_case("i"))
+}
+
 fn extract_join_condition(
     subject_ref: &RcTerm,
     graph_ref: &FastGraph,
@@ -74,6 +87,15 @@ impl TermMapExtractor<ObjectMap> for ObjectMap {
         let language = get_object(graph_ref, subj_ref, &lang_pred)
             .ok()
             .map(|tshared| tshared.value_raw().0.to_string());
+        if let Some(lang) = language
+            .as_ref()
+            .filter(|lang| !is_registered_language_tag(lang))
+        {
+            return Err(ParseError::GenericError(format!(
+                "Object Map has an invalid language tag {}",
+                lang
+            )));
+        }
         let parent_tm = extract_parent_tm(subj_ref, graph_ref).ok();
         let join_condition = extract_join_condition(subj_ref, graph_ref).ok();
 
@@ -92,8 +114,17 @@ impl TermMapExtractor<ObjectMap> for ObjectMap {
         }
 
         let mut tm_info = tm_info_res?;
+        // Template-valued object maps generate IRIs, unless they are turned
+        // into literals by a language tag or a datatype
         if tm_info.term_type.is_none() {
-            tm_info.term_type = Some(tm_info.term_value.kind());
+            tm_info.term_type = match tm_info.term_map_type {
+                TermMapType::Template
+                    if language.is_none() && data_type.is_none() =>
+                {
+                    Some(TermKind::Iri)
+                }
+                _ => Some(tm_info.term_value.kind()),
+            };
         }
         let graph_maps =
             GraphMap::extract_many_from_container(graph_ref, subj_ref)?;
@@ -125,8 +156,8 @@ impl TermMapExtractor<ObjectMap> for ObjectMap {
 mod tests {
 
     use super::*;
+    use crate::extractors::io::load_graph_str;
     use crate::import_test_mods;
-    use crate::rml_model::term_map::TermMapType;
 
     import_test_mods!();
 
@@ -157,4 +188,45 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn invalid_language_tag_test() -> ExtractorResult<()> {
+        let graph = load_graph_str(
+            "@prefix rr: <http://www.w3.org/ns/r2rml#> .
+            @prefix ex: <http://example.com/> .
+
+            ex:english rr:constant \"Spain\" ; rr:language \"english\" .
+            ex:en rr:constant \"Spain\" ; rr:language \"en-GB\" .",
+        )?;
+        let english = RcTerm::new_iri("http://example.com/english")?;
+        let en = RcTerm::new_iri("http://example.com/en")?;
+
+        assert!(ObjectMap::create_term_map(&english, &graph).is_err());
+        assert_eq!(
+            ObjectMap::create_term_map(&en, &graph)?.language,
+            Some("en-GB".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn template_object_term_type_test() -> ExtractorResult<()> {
+        let graph = load_graph_str(
+            "@prefix rr: <http://www.w3.org/ns/r2rml#> .
+            @prefix ex: <http://example.com/> .
+
+            ex:iri rr:template \"http://example.com/sport/{ID}\" .
+            ex:literal rr:template \"{Name}\" ; rr:termType rr:Literal .
+            ex:tagged rr:template \"{Name}\" ; rr:language \"en\" .",
+        )?;
+        let term_type = |om: &str| -> ExtractorResult<Option<TermKind>> {
+            let om = RcTerm::new_iri(format!("http://example.com/{}", om))?;
+            Ok(ObjectMap::create_term_map(&om, &graph)?.tm_info.term_type)
+        };
+
+        assert_eq!(term_type("iri")?, Some(TermKind::Iri));
+        assert_eq!(term_type("literal")?, Some(TermKind::Literal));
+        assert_eq!(term_type("tagged")?, Some(TermKind::Literal));
+        Ok(())
+    }
 }
@@ -35,10 +35,14 @@ where
     TS: TTerm + ?Sized + Display,
     TP: TTerm + ?Sized + Display,
 {
-    graph
+    // The graph yields its triples in hash order, the objects are sorted to
+    // extract the same mapping document on every run
+    let mut objects: Vec<RcTerm> = graph
         .triples_with_sp(subject, pred)
         .filter_map(|trip_res| trip_res.ok().map(|trip| trip.o().to_owned()))
-        .collect()
+        .collect();
+    objects.sort_by_cached_key(|object| object.to_string());
+    objects
 }
 pub fn get_object<TS, TP>(
     graph: &FastGraph,
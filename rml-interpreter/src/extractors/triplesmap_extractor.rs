@@ -51,7 +51,7 @@ pub fn extract_triples_maps(
     let old_rml_tm_iter = graph.triples_with_p(&old_rml_subject_map);
     let rml_core_tm_iter = graph.triples_with_p(&rml_core_subject_map);
     
-    old_rml_tm_iter
+    let mut triples_maps = old_rml_tm_iter
         .chain(rml_core_tm_iter)
         .filter_map(|triple| { 
             triple.ok()
@@ -59,7 +59,11 @@ pub fn extract_triples_maps(
         .map(|triple| {
             TriplesMap::extract_self(triple.s(), graph)
         })
-        .collect()
+        .collect::<ExtractorResult<Vec<_>>>()?;
+
+    // Ordered by identifier, since the graph yields them in hash order
+    triples_maps.sort_by(|tm, other| tm.identifier.cmp(&other.identifier));
+    Ok(triples_maps)
     
     // TODO: if it really needs to be valid at thid point, check for a logical source for old RML
 }
@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Default, Hash)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct JoinCondition {
     pub parent_attributes: Vec<String>,
     pub child_attributes:  Vec<String>,
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 
 use lazy_static::lazy_static;
@@ -21,6 +21,22 @@ fn prefix_attributes_from_template(template: &str, prefix: &str) -> String {
         .replace("\\)", "\\}")
 }
 
+fn rename_attributes_from_template(
+    template: &str,
+    renames: &HashMap<String, String>,
+) -> String {
+    let sanitized = template.replace("\\{", "\\(").replace("\\}", "\\)");
+    TEMPLATE_REGEX
+        .replace_all(&sanitized, |caps: &regex::Captures| {
+            let attribute = &caps[1];
+            let renamed =
+                renames.get(attribute).map_or(attribute, String::as_str);
+            format!("{{{}}}", renamed)
+        })
+        .replace("\\(", "\\{")
+        .replace("\\)", "\\}")
+}
+
 fn get_attributes_from_template(template: &str) -> Vec<String> {
     let sanitized = template.replace("\\{", "").replace("\\}", "");
     let captured = TEMPLATE_REGEX.captures_iter(&sanitized);
@@ -89,6 +105,33 @@ impl TermMapInfo {
         self.term_value = term_value;
     }
 
+    /// Replaces the attributes referred to by the term map with their
+    /// renamed attributes, if any. A function term map without a function
+    /// map doesn't refer to any attributes and is left as is.
+    pub fn rename_attributes(&mut self, renames: &HashMap<String, String>) {
+        let mut term_value = self.term_value.clone();
+        term_value = match self.term_map_type {
+            TermMapType::Constant => term_value,
+            TermMapType::Reference => {
+                term_value.map(|val| renames.get(&val).cloned().unwrap_or(val))
+            }
+            TermMapType::Template => {
+                term_value
+                    .map(|val| rename_attributes_from_template(&val, renames))
+            }
+            TermMapType::Function => {
+                if let Some(fun_map) = self.fun_map_opt.as_mut() {
+                    fun_map.param_om_pairs.iter_mut().for_each(|(_, om)| {
+                        om.tm_info.rename_attributes(renames)
+                    });
+                }
+                term_value
+            }
+        };
+
+        self.term_value = term_value;
+    }
+
     pub fn get_attributes(&self) -> HashSet<String> {
         let tm_info = self;
         let value = tm_info.term_value.value().to_string();
@@ -114,13 +157,15 @@ impl TermMapInfo {
         let identifier = match const_value.clone() {
             Term::Iri(iri) => Term::Iri(iri.map(|i| i.to_string())),
             Term::BNode(bnode) => Term::BNode(bnode.map(|i| i.to_string())),
+            // Blank node labels only allow a few characters, hence the
+            // literal with its datatype or language tag is hex encoded
             Term::Literal(lit) => {
-                Term::new_bnode(format!(
-                    "{}-{}",
-                    lit.txt(),
-                    uuid::Uuid::new_v4()
-                ))
-                .unwrap()
+                let encoded: String = lit
+                    .to_string()
+                    .bytes()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect();
+                Term::new_bnode(format!("literal_{}", encoded)).unwrap()
             }
             Term::Variable(_) => {
                 panic!("Variable not supported yet!")
@@ -1,4 +1,7 @@
-use clap::{arg, Command};
+use clap::builder::PossibleValuesParser;
+use clap::{arg, value_parser, ArgAction, Command};
+use plangenerator::optimizer::OptimizerRule;
+use plangenerator::visualization::PlanFormat;
 
 pub const TRANSLATOR_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -23,8 +26,30 @@ impl Cli {
             .subcommand(Command::new("folder")
                          .about("translate all mapping documents under the given folder")
                          .arg(arg!(<FOLDER> "the folder containing several mapping documents"))
+                         .arg(arg!(-j --jobs <JOBS> "the number of worker threads translating the mapping documents, defaults to the available parallelism")
+                              .value_parser(value_parser!(u64).range(1..)))
                          .arg_required_else_help(true))
+            .subcommand(Command::new("run")
+                         .about("translate a single mapping document and execute the mapping plan on local files")
+                         .arg(arg!(<DOCUMENT> "the mapping document to be translated and executed"))
+                         .arg_required_else_help(true))
+            .subcommand(Command::new("verify")
+                         .about("compare the mapping plans of all mapping documents under the given folder with the golden json plans written next to them by the folder command")
+                         .arg(arg!(<FOLDER> "the folder containing several mapping documents and their golden plans"))
+                         .arg_required_else_help(true))
+            .subcommand(Command::new("schema")
+                         .about("print the JSON Schema of the generated json mapping plans"))
             .arg(arg!(-d --debug ...  "Turns on debugging and logging to file"))
+            .arg(arg!(--distinct "Inserts a distinct operator in front of every serializer to remove duplicate outputs"))
+            .arg(arg!(--optimize "Rewrites the generated mapping plans with the plan optimizer"))
+            .arg(arg!(--"disable-rule" <RULE> "Disables the given rule of the plan optimizer, can be repeated")
+                 .action(ArgAction::Append)
+                 .value_parser(PossibleValuesParser::new(OptimizerRule::names()))
+                 .requires("optimize"))
+            .arg(arg!(-f --format <FORMAT> "The visualization format of the written mapping plans next to the json plan, can be repeated")
+                 .action(ArgAction::Append)
+                 .value_parser(PossibleValuesParser::new(PlanFormat::names()))
+                 .default_value("dot"))
             .arg(arg!(-o --outputFolderSuffix <OUTPUT_FOLDER_SUFFIX> "The output folder suffix"));
 
         Self { cmd }
@@ -4,7 +4,8 @@ use std::path::PathBuf;
 use plangenerator::error::PlanError;
 use plangenerator::plan::{Init, Plan};
 
-pub trait FileTranslatorHandler: Debug {
+/// Handlers are shared by the worker threads translating a folder.
+pub trait FileTranslatorHandler: Debug + Send + Sync {
     fn supported_extension(&self) -> String;
     fn can_handle(&self, file_path: &dyn AsRef<str>) -> bool {
         let pbuf: PathBuf = file_path.as_ref().into();
@@ -4,20 +4,39 @@ mod rml;
 mod shexml;
 mod util;
 
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::BufReader;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 
+use executor::Executor;
 use handler::FileTranslatorHandler;
-use log::{debug, error};
+use log::{debug, error, info, warn};
 use meamer_rs::logger::init_logger;
 use plangenerator::error::PlanError;
+use plangenerator::exchange::plan_document_schema;
+use plangenerator::optimizer::{Optimizer, OptimizerRule};
+use plangenerator::plan::{Init, Plan};
+use plangenerator::visualization::PlanFormat;
+use translator::TranslatorOptions;
 use util::serialize_and_log_msg;
 use walkdir::WalkDir;
 
 use crate::rml::RMLFileHandler;
 use crate::shexml::ShExMLFileHandler;
 
-fn init_handlers() -> Vec<Box<dyn FileTranslatorHandler>> {
-    vec![Box::new(RMLFileHandler), Box::new(ShExMLFileHandler)]
+fn init_handlers(
+    options: TranslatorOptions,
+) -> Vec<Box<dyn FileTranslatorHandler>> {
+    vec![
+        Box::new(RMLFileHandler {
+            options: options.clone(),
+        }),
+        Box::new(ShExMLFileHandler { options }),
+    ]
 }
 
 pub fn main() -> Result<(), PlanError> {
@@ -28,7 +47,25 @@ pub fn main() -> Result<(), PlanError> {
     init_logger(debug_flag_count >= 1)
         .map_err(|err| PlanError::GenericError(err.to_string()))?;
 
-    let handlers = init_handlers();
+    let optimizer = matches.get_flag("optimize").then(|| {
+        matches
+            .get_many::<String>("disable-rule")
+            .into_iter()
+            .flatten()
+            .filter_map(|name| name.parse::<OptimizerRule>().ok())
+            .fold(Optimizer::default(), Optimizer::without_rule)
+    });
+    let options = TranslatorOptions {
+        distinct_serializer_inputs: matches.get_flag("distinct"),
+        optimizer,
+    };
+    let handlers = init_handlers(options);
+    let formats: Vec<PlanFormat> = matches
+        .get_many::<String>("format")
+        .into_iter()
+        .flatten()
+        .filter_map(|name| name.parse().ok())
+        .collect();
 
     if let Some(file_matches) = matches.subcommand_matches("file") {
         let file_path_string: &String =
@@ -42,56 +79,253 @@ pub fn main() -> Result<(), PlanError> {
             let _ = output_prefix.insert(derived_string.to_string());
         }
 
-        process_one_file(&handlers, file_path, output_prefix);
+        process_one_file(&handlers, &formats, file_path, output_prefix);
+    } else if let Some(run_matches) = matches.subcommand_matches("run") {
+        let file_path_string: &String =
+            run_matches.get_one("DOCUMENT").unwrap();
+
+        debug!("Attempting to translate and execute: {:?}", file_path_string);
+        run_one_file(&handlers, file_path_string.into())?;
+    } else if matches.subcommand_matches("schema").is_some() {
+        let schema = serde_json::to_string_pretty(&plan_document_schema())?;
+        println!("{}", schema);
     } else if let Some(folder_matches) = matches.subcommand_matches("folder") {
         let folder_path_string: &String =
             folder_matches.get_one("FOLDER").unwrap();
         let folder_path: PathBuf = folder_path_string.into();
-        let files = WalkDir::new(folder_path)
-            .max_depth(4)
-            .into_iter()
-            .filter_map(|entry| entry.ok())
-            .filter(|dentry| dentry.file_type().is_file())
-            .filter(|file| {
-                handlers.iter().any(|handler| {
-                    handler.can_handle(&file.path().to_string_lossy())
-                })
-            });
+        let jobs = folder_matches.get_one::<u64>("jobs").map_or_else(
+            || thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            |jobs| *jobs as usize,
+        );
 
-        for file in files {
-            debug!(
-                "Attempting to translate: {}",
-                file.path().to_string_lossy()
-            );
-            let input_path = file.path();
-
-            let output_dir = input_path
-                .parent()
-                .map_or("".to_string(), |p| p.to_string_lossy().to_string());
-            let output_prefix = output_dir
-                + "/"
-                + &input_path.file_stem().unwrap().to_string_lossy();
-
-            process_one_file(
-                &handlers,
-                input_path.to_path_buf(),
-                Some(output_prefix),
+        process_files_in_parallel(
+            &handlers,
+            &formats,
+            mapping_files(&handlers, folder_path),
+            jobs,
+        );
+    } else if let Some(verify_matches) = matches.subcommand_matches("verify") {
+        let folder_path_string: &String =
+            verify_matches.get_one("FOLDER").unwrap();
+        verify_folder(&handlers, folder_path_string.into())?;
+    }
+
+    Ok(())
+}
+
+/// Lists the mapping documents under the folder, which can be handled by
+/// one of the handlers.
+fn mapping_files(
+    handlers: &[Box<dyn FileTranslatorHandler>],
+    folder_path: PathBuf,
+) -> Vec<PathBuf> {
+    WalkDir::new(folder_path)
+        .max_depth(4)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|dentry| dentry.file_type().is_file())
+        .filter(|file| {
+            let file_path = file.path().to_string_lossy();
+            handlers
+                .iter()
+                .any(|handler| handler.can_handle(&file_path))
+        })
+        .map(|file| file.into_path())
+        .collect()
+}
+
+/// The prefix of the files written for a mapping document found in a
+/// folder, which are placed next to the document.
+fn folder_output_prefix(input_path: &Path) -> String {
+    let output_dir = input_path
+        .parent()
+        .map_or("".to_string(), |p| p.to_string_lossy().to_string());
+    output_dir + "/" + &input_path.file_stem().unwrap().to_string_lossy()
+}
+
+fn translate_one_file(
+    handlers: &[Box<dyn FileTranslatorHandler>],
+    file_path: &Path,
+) -> Result<Plan<Init>, PlanError> {
+    handlers
+        .iter()
+        .find(|handler| handler.can_handle(&file_path.to_string_lossy()))
+        .ok_or(PlanError::GenericError(format!(
+            "No handler found for the file: {}",
+            file_path.to_string_lossy()
+        )))?
+        .handle_file(&file_path.to_string_lossy())
+}
+
+/// Compares the plans of the mapping documents under the folder with the
+/// golden plans stored next to them, which are the json plans written by
+/// the folder command. Documents without a golden plan are skipped.
+fn verify_folder(
+    handlers: &[Box<dyn FileTranslatorHandler>],
+    folder_path: PathBuf,
+) -> Result<(), PlanError> {
+    let mut verified_count = 0;
+    let mut failed_count = 0;
+    for input_path in mapping_files(handlers, folder_path) {
+        let golden_path: PathBuf =
+            (folder_output_prefix(&input_path) + ".json").into();
+        if !golden_path.exists() {
+            warn!(
+                "No golden plan for: {}, skipping it",
+                input_path.to_string_lossy()
             );
+            continue;
+        }
+
+        verified_count += 1;
+        let diff_res =
+            translate_one_file(handlers, &input_path).and_then(|plan| {
+                let golden_file = File::open(&golden_path).map_err(|err| {
+                    PlanError::GenericError(err.to_string())
+                })?;
+                Plan::from_reader(BufReader::new(golden_file))?.diff(&plan)
+            });
+        match diff_res {
+            Ok(diff) if diff.is_empty() => {
+                info!(
+                    "The plan matches the golden plan: {}",
+                    golden_path.to_string_lossy()
+                );
+            }
+            Ok(diff) => {
+                failed_count += 1;
+                error!(
+                    "The plan of {} differs from the golden plan:\n{}",
+                    input_path.to_string_lossy(),
+                    diff
+                );
+            }
+            Err(err) => {
+                failed_count += 1;
+                error!(
+                    "Errored while verifying: {}",
+                    input_path.to_string_lossy()
+                );
+                error!("{}", err);
+            }
         }
     }
 
+    if failed_count > 0 {
+        return Err(PlanError::GenericError(format!(
+            "{} of the {} verified plans don't match their golden plans",
+            failed_count, verified_count
+        )));
+    }
     Ok(())
 }
 
-fn process_one_file(
+fn run_one_file(
     handlers: &[Box<dyn FileTranslatorHandler>],
     file_path: PathBuf,
-    output_prefix: Option<String>,
+) -> Result<(), PlanError> {
+    let plan = translate_one_file(handlers, &file_path)?;
+
+    let base_path = file_path
+        .parent()
+        .map_or(PathBuf::from("."), |p| p.to_path_buf());
+    let executor = Executor::new(base_path);
+
+    executor
+        .run(&plan, &mut std::io::stdout())
+        .map_err(|err| PlanError::GenericError(err.to_string()))
+}
+
+/// Translates the mapping documents on the given number of worker threads.
+/// The workers freeze the plans to hand them off to the current thread,
+/// which writes them in the order they are translated.
+fn process_files_in_parallel(
+    handlers: &[Box<dyn FileTranslatorHandler>],
+    formats: &[PlanFormat],
+    input_paths: Vec<PathBuf>,
+    jobs: usize,
 ) {
-    let (generated_plans, generated_errors_res): (Vec<_>, Vec<_>) = handlers
+    let next_path_idx = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(input_paths.len()) {
+            let sender = sender.clone();
+            let next_path_idx = &next_path_idx;
+            let input_paths = &input_paths;
+            scope.spawn(move || {
+                while let Some(input_path) = input_paths
+                    .get(next_path_idx.fetch_add(1, Ordering::Relaxed))
+                {
+                    debug!(
+                        "Attempting to translate: {}",
+                        input_path.to_string_lossy()
+                    );
+                    let frozen_plans: Vec<_> =
+                        translate_with_handlers(handlers, input_path)
+                            .into_iter()
+                            .map(|plan_res| plan_res.map(|plan| plan.freeze()))
+                            .collect();
+                    if sender.send((input_path, frozen_plans)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        for (input_path, frozen_plans) in receiver {
+            let plans = frozen_plans
+                .into_iter()
+                .map(|frozen_res| frozen_res.and_then(|frozen| frozen.thaw()))
+                .collect();
+            write_translated_plans(
+                handlers,
+                formats,
+                input_path,
+                Some(folder_output_prefix(input_path)),
+                plans,
+            );
+        }
+    });
+}
+
+fn translate_with_handlers(
+    handlers: &[Box<dyn FileTranslatorHandler>],
+    file_path: &Path,
+) -> Vec<Result<Plan<Init>, PlanError>> {
+    handlers
         .iter()
         .map(|handler| handler.handle_file(&file_path.to_string_lossy()))
-        .partition(|plan| plan.is_ok());
+        .collect()
+}
+
+fn process_one_file(
+    handlers: &[Box<dyn FileTranslatorHandler>],
+    formats: &[PlanFormat],
+    file_path: PathBuf,
+    output_prefix: Option<String>,
+) {
+    let translated_plans = translate_with_handlers(handlers, &file_path);
+    write_translated_plans(
+        handlers,
+        formats,
+        &file_path,
+        output_prefix,
+        translated_plans,
+    );
+}
+
+fn write_translated_plans(
+    handlers: &[Box<dyn FileTranslatorHandler>],
+    formats: &[PlanFormat],
+    file_path: &Path,
+    output_prefix: Option<String>,
+    translated_plans: Vec<Result<Plan<Init>, PlanError>>,
+) {
+    let (generated_plans, generated_errors_res): (Vec<_>, Vec<_>) =
+        translated_plans
+            .into_iter()
+            .partition(|plan| plan.is_ok());
     if generated_plans.is_empty() {
         if !generated_errors_res.is_empty() {
             error!(
@@ -113,6 +347,7 @@ fn process_one_file(
             if let Err(err) = serialize_and_log_msg(
                 output_prefix.clone().unwrap(),
                 &mut plan,
+                formats,
                 file_path.to_string_lossy(),
             ) {
                 error!(
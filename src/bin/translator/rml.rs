@@ -4,12 +4,14 @@ use plangenerator::error::PlanError;
 use plangenerator::plan::{Init, Plan};
 use rml_interpreter::extractors::io::parse_file;
 use translator::rmlalgebra::OptimizedRMLDocumentTranslator;
-use translator::LanguageTranslator;
+use translator::{LanguageTranslator, TranslatorOptions};
 
 use crate::handler::FileTranslatorHandler;
 
 #[derive(Debug)]
-pub struct RMLFileHandler;
+pub struct RMLFileHandler {
+    pub options: TranslatorOptions,
+}
 
 impl FileTranslatorHandler for RMLFileHandler {
     fn translate(
@@ -19,7 +21,10 @@ impl FileTranslatorHandler for RMLFileHandler {
         let document = parse_file(file_path.as_ref().into())
             .map_err(|err| PlanError::GenericError(format!("{:?}", err)))?;
 
-        OptimizedRMLDocumentTranslator::translate_to_plan(document)
+        OptimizedRMLDocumentTranslator::translate_to_plan_with_options(
+            document,
+            &self.options,
+        )
     }
 
     fn supported_extension(&self) -> String {
@@ -1,11 +1,13 @@
 use plangenerator::error::PlanError;
 use plangenerator::plan::{Init, Plan};
-use translator::LanguageTranslator;
+use translator::{LanguageTranslator, TranslatorOptions};
 
 use crate::handler::FileTranslatorHandler;
 
 #[derive(Debug, Clone)]
-pub struct ShExMLFileHandler;
+pub struct ShExMLFileHandler {
+    pub options: TranslatorOptions,
+}
 
 impl FileTranslatorHandler for ShExMLFileHandler {
     fn translate(
@@ -22,7 +24,10 @@ impl FileTranslatorHandler for ShExMLFileHandler {
             ))
         })?;
 
-        translator::shexml::ShExMLTranslator::translate_to_plan(shexml_document)
+        translator::shexml::ShExMLTranslator::translate_to_plan_with_options(
+            shexml_document,
+            &self.options,
+        )
     }
 
     fn supported_extension(&self) -> String {
@@ -2,20 +2,46 @@ use colored::Colorize;
 use log::info;
 use plangenerator::error::PlanError;
 use plangenerator::plan::{Init, Plan};
+use plangenerator::visualization::PlanFormat;
 
 pub fn serialize_and_log_msg<F: AsRef<str>>(
     output_prefix: String,
     mapping_plan: &mut Plan<Init>,
+    formats: &[PlanFormat],
     file: F,
 ) -> Result<(), PlanError> {
-    let full_path = output_prefix.clone() + ".dot";
-    mapping_plan
-        .write(full_path.clone().into())
-        .map_err(|err| PlanError::GenericError(format!("{:?}", err)))?;
-    let pretty_path = output_prefix.clone() + "_pretty.dot";
-    mapping_plan
-        .write_pretty(pretty_path.clone().into())
+    mapping_plan.validate()?;
+    let mut generated_msgs = Vec::new();
+    for format in formats {
+        let path = format!("{}.{}", output_prefix, format.extension());
+        match format {
+            PlanFormat::Dot => mapping_plan.write(path.clone().into()),
+            PlanFormat::Mermaid => {
+                mapping_plan.write_mermaid(path.clone().into())
+            }
+            PlanFormat::GraphML => {
+                mapping_plan.write_graphml(path.clone().into())
+            }
+            PlanFormat::Html => mapping_plan.write_html(path.clone().into()),
+        }
         .map_err(|err| PlanError::GenericError(format!("{:?}", err)))?;
+        generated_msgs.push(format!(
+            "Generated {} file: {}",
+            format,
+            path.yellow()
+        ));
+
+        if *format == PlanFormat::Dot {
+            let pretty_path = output_prefix.clone() + "_pretty.dot";
+            mapping_plan
+                .write_pretty(pretty_path.clone().into())
+                .map_err(|err| PlanError::GenericError(format!("{:?}", err)))?;
+            generated_msgs.push(format!(
+                "The pretty dot file version for visualization is: {}",
+                pretty_path.yellow()
+            ));
+        }
+    }
     let json_path = output_prefix + ".json";
     mapping_plan
         .write_json(json_path.clone().into())
@@ -24,11 +50,9 @@ pub fn serialize_and_log_msg<F: AsRef<str>>(
         "Translated file: {}",
         file.as_ref().yellow(),
     );
-    info!("Generated dot file: {}", full_path.yellow());
-    info!(
-        "The pretty dot file version for visualization is: {}",
-        pretty_path.yellow()
-    );
+    for msg in generated_msgs {
+        info!("{}", msg);
+    }
     info!("Generated json file: {}", json_path.yellow());
     Ok(())
 }
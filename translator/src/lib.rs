@@ -1,4 +1,5 @@
 use plangenerator::error::PlanError;
+use plangenerator::optimizer::Optimizer;
 use plangenerator::plan::{Init, Plan};
 
 pub mod rmlalgebra;
@@ -10,8 +11,33 @@ mod test_macro;
 
 pub type LanguageTranslateResult = Result<Plan<Init>, PlanError>;
 
+/// Options tuning the plans generated by the language translators.
+#[derive(Debug, Clone, Default)]
+pub struct TranslatorOptions {
+    /// Inserts a distinct operator in front of every serializer, so that
+    /// duplicate bindings of the serializer templates are removed.
+    pub distinct_serializer_inputs: bool,
+    /// Rewrites the generated plans with the rules of the optimizer.
+    pub optimizer:                  Option<Optimizer>,
+}
+
 pub trait LanguageTranslator<T> {
     fn translate_to_plan(model: T) -> LanguageTranslateResult;
+
+    #[allow(clippy::result_large_err)]
+    fn translate_to_plan_with_options(
+        model: T,
+        options: &TranslatorOptions,
+    ) -> LanguageTranslateResult {
+        let mut plan = Self::translate_to_plan(model)?;
+        if let Some(optimizer) = &options.optimizer {
+            optimizer.optimize(&mut plan)?;
+        }
+        if options.distinct_serializer_inputs {
+            plan.distinct_serializer_inputs()?;
+        }
+        Ok(plan)
+    }
 }
 
 pub trait OperatorTranslator<Output> {
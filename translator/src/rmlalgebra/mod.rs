@@ -7,11 +7,15 @@ use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 use operator::formats::ReferenceFormulation;
-use operator::{Extend, Field, Iterator, Operator, Projection, Source};
+use operator::{
+    Extend, Field, Fragmenter, Iterator, JoinType, Operator, Projection,
+    Source, Unnest,
+};
 use plangenerator::error::PlanError;
 use plangenerator::plan::{join, Plan, Processed, RcRefCellPlan};
-use rml_interpreter::rml_model::source_target::SourceType;
-use rml_interpreter::rml_model::term_map::SubjectMap;
+use rml_interpreter::rml_model::join::JoinCondition;
+use rml_interpreter::rml_model::source_target::{LogicalSource, SourceType};
+use rml_interpreter::rml_model::term_map::{ObjectMap, SubjectMap};
 use rml_interpreter::rml_model::{Document, PredicateObjectMap, TriplesMap};
 use sophia_api::term::TTerm;
 use vocab::ToString;
@@ -35,30 +39,56 @@ impl LanguageTranslator<Document> for OptimizedRMLDocumentTranslator {
     fn translate_to_plan(doc: Document) -> crate::LanguageTranslateResult {
         let mut plan = Plan::<()>::new();
 
-        let tm_projected_pairs_res: Result<Vec<_>, PlanError> = doc
-            .triples_maps
-            .iter()
-            .map(|tm| {
-                let source_op = translate_source_op(tm);
-                let projection_op =
-                    translate_projection_op(tm, doc.triples_maps.iter());
-                let result = (
-                    tm,
-                    Rc::new(RefCell::new(
-                        plan.source(source_op)
-                            .apply(&projection_op, "Projection")?,
-                    )),
-                );
+        // Triples maps with the same logical source share a single source
+        // node, which is fragmented into a branch per triples map
+        let mut shared_sources: Vec<(&LogicalSource, Vec<_>)> = Vec::new();
+        for (tm_idx, tm) in doc.triples_maps.iter().enumerate() {
+            match shared_sources.iter_mut().find(|(logical_source, _)| {
+                **logical_source == tm.logical_source
+            }) {
+                Some((_, tms)) => tms.push((tm_idx, tm)),
+                None => {
+                    shared_sources
+                        .push((&tm.logical_source, vec![(tm_idx, tm)]))
+                }
+            }
+        }
 
-                Ok(result)
-            })
-            .collect();
+        let mut tm_projected_pairs = Vec::new();
+        for (_, tms) in shared_sources {
+            let shared_tms: Vec<_> = tms.iter().map(|(_, tm)| *tm).collect();
+            let source_op = translate_shared_source_op(&shared_tms);
+            let mut sourced_plan = plan.source(source_op);
+
+            if let [tm] = shared_tms[..] {
+                let projected_plan =
+                    add_projection_related_ops(tm, &doc, sourced_plan, None)?;
+                tm_projected_pairs.push((tm, projected_plan));
+                continue;
+            }
+
+            let fragmented_plan = sourced_plan.fragment(Fragmenter {
+                from: "default".to_string(),
+                to:   tms
+                    .iter()
+                    .map(|(tm_idx, _)| format!("tm{}", tm_idx))
+                    .collect(),
+            })?;
+            for (tm_idx, tm) in tms {
+                let projected_plan = add_projection_related_ops(
+                    tm,
+                    &doc,
+                    fragmented_plan.clone(),
+                    Some(&format!("tm{}", tm_idx)),
+                )?;
+                tm_projected_pairs.push((tm, projected_plan));
+            }
+        }
 
         // Search dictionaries instantiations
         let variable_map = generate_variable_map(&doc);
         let target_map = generate_logtarget_map(&doc);
         let lt_id_quad_map = generate_lt_quads_from_doc(&doc);
-        let tm_projected_pairs = tm_projected_pairs_res?;
         let tm_rccellplan_map: HashMap<_, _> = tm_projected_pairs
             .clone()
             .into_iter()
@@ -70,6 +100,7 @@ impl LanguageTranslator<Document> for OptimizedRMLDocumentTranslator {
             variable_map,
             target_map,
             lt_id_quad_map,
+            base_iri: doc.default_base_iri.as_deref(),
         };
         // Finish search dictionaries instantiations
 
@@ -86,12 +117,24 @@ impl LanguageTranslator<Document> for OptimizedRMLDocumentTranslator {
                 partition_pom_join_nonjoin(poms);
 
             if !joined_poms.is_empty() {
+                // Every serializer template also generates the class triples
+                // of the subject. Without any non-join POMs, these are only
+                // generated in the join branches, hence the child rows
+                // without matching parent rows have to be kept.
+                let join_type =
+                    if no_join_poms.is_empty() && !sm_ref.classes.is_empty() {
+                        JoinType::LeftJoin
+                    } else {
+                        JoinType::InnerJoin
+                    };
+
                 add_join_related_ops(
                     tm,
                     &joined_poms,
                     sm_ref,
                     &search_map,
                     plan,
+                    join_type,
                 )?;
             }
 
@@ -119,6 +162,47 @@ impl LanguageTranslator<Document> for OptimizedRMLDocumentTranslator {
     }
 }
 
+/// Adds the unnest operators of the multi-valued references of the triples
+/// map, and its projection, to the sourced plan. If the source is shared by
+/// several triples maps, the operators start a branch from the given
+/// fragment of the fragmented source.
+fn add_projection_related_ops(
+    tm: &TriplesMap,
+    doc: &Document,
+    mut plan: Plan<Processed>,
+    fragment: Option<&str>,
+) -> Result<RcRefCellPlan<Processed>, PlanError> {
+    // Multi-valued references are unnested into one tuple per value before
+    // the projection
+    let unnest_ops = translate_source_op(tm)
+        .root_iterator
+        .unnest_attributes()
+        .into_iter()
+        .map(|attribute| {
+            (
+                Operator::UnnestOp {
+                    config: Unnest { attribute },
+                },
+                "Unnest",
+            )
+        });
+    let projection_op = translate_projection_op(tm, doc.triples_maps.iter());
+
+    let mut fragment = fragment;
+    for (operator, node_id_prefix) in
+        unnest_ops.chain([(projection_op, "Projection")])
+    {
+        plan = match fragment.take() {
+            Some(fragment) => {
+                plan.branch(&operator, node_id_prefix, fragment)?
+            }
+            None => plan.apply(&operator, node_id_prefix)?,
+        };
+    }
+
+    Ok(Rc::new(RefCell::new(plan)))
+}
+
 fn partition_pom_join_nonjoin(
     poms: Vec<PredicateObjectMap>,
 ) -> (Vec<PredicateObjectMap>, Vec<PredicateObjectMap>) {
@@ -164,7 +248,11 @@ fn add_non_join_related_ops(
     tms.push(&sm.tm_info);
     tms.extend(extract_gm_tm_infos(sm, no_join_poms));
 
-    let extend_translator = ExtendTranslator { tms, variable_map };
+    let extend_translator = ExtendTranslator {
+        tms,
+        variable_map,
+        base_iri: search_map.base_iri,
+    };
     let extend_op = extend_translator.translate();
     let extended_plan = plan.apply(&extend_op, "ExtendOp")?;
     let mut next_plan = extended_plan;
@@ -206,6 +294,7 @@ fn add_join_related_ops(
     sm: &SubjectMap,
     search_map: &SearchMap,
     plan: &RcRefCellPlan<Processed>,
+    join_type: JoinType,
 ) -> Result<(), PlanError> {
     // HashMap pairing the attribute with the function generated from
     // PTM's subject map
@@ -214,11 +303,12 @@ fn add_join_related_ops(
     let variable_map = &search_map.variable_map;
     let lt_target_map = &search_map.target_map;
 
+    // Object maps referring to the same parent triples map with the same
+    // join condition are served by a single join
+    let mut join_groups: Vec<(String, Option<&JoinCondition>, Vec<_>)> =
+        Vec::new();
     for pom in join_poms {
-        let pms = &pom.predicate_maps;
-        let oms = &pom.object_maps;
-
-        for (_om_idx, om) in oms.iter().enumerate() {
+        for om in &pom.object_maps {
             let ptm_iri = om
                 .parent_tm
                 .as_ref()
@@ -227,93 +317,187 @@ fn add_join_related_ops(
                     om
                 )))?
                 .to_string();
+            let join_cond_opt = om.join_condition.as_ref();
 
-            let (ptm, other_plan) = search_tm_plan_map.get(&ptm_iri).ok_or(
-                PlanError::GenericError(format!(
-                    "Parent triples map IRI is wrong: {}",
-                    &ptm_iri
-                )),
-            )?;
-
-            //Preparing plan for add join operator
-            let ptm_variable = variable_map.get(&ptm.identifier).unwrap();
-            let ptm_alias =
-                format!("join_{}", &ptm_variable[ptm_variable.len() - 1..]);
-            let mut aliased_plan =
-                join(Rc::clone(plan), Rc::clone(other_plan))?
-                    .alias(&ptm_alias)?;
-
-            //Check for appropriate join type and add them to the plan
-            let mut joined_plan: Plan<Processed>;
+            let pom_with_joined_ptm = PredicateObjectMap {
+                predicate_maps: pom.predicate_maps.clone(),
+                object_maps:    vec![om.clone()],
+                graph_maps:     pom.graph_maps.clone(),
+            };
 
-            let join_cond_opt = om.join_condition.as_ref();
-            if let Some(join_cond) = join_cond_opt {
-                let child_attributes = &join_cond.child_attributes;
-                let parent_attributes = &join_cond.parent_attributes;
-
-                joined_plan = aliased_plan
-                    .where_by(child_attributes.clone())?
-                    .compared_to(parent_attributes.clone())?;
-            } else if tm.logical_source == ptm.logical_source {
-                joined_plan = aliased_plan.natural_join()?;
-            } else {
-                joined_plan = aliased_plan.cross_join()?;
+            match join_groups.iter_mut().find(|(iri, cond, _)| {
+                *iri == ptm_iri && *cond == join_cond_opt
+            }) {
+                Some((_, _, joined_poms)) => {
+                    joined_poms.push(pom_with_joined_ptm)
+                }
+                None => {
+                    join_groups.push((
+                        ptm_iri,
+                        join_cond_opt,
+                        vec![pom_with_joined_ptm],
+                    ))
+                }
             }
+        }
+    }
+
+    for (ptm_iri, join_cond_opt, joined_poms) in join_groups {
+        // The join only depends on the join condition, which all the object
+        // maps of the group share
+        let om = &joined_poms[0].object_maps[0];
 
-            // Prefix the attributes in the subject map with the alias of the PTM
-            let mut ptm_sm_info = ptm.subject_map.tm_info.clone();
+        let (ptm, other_plan) =
+            search_tm_plan_map
+                .get(&ptm_iri)
+                .ok_or(PlanError::GenericError(format!(
+                    "Parent triples map IRI is wrong: {}",
+                    &ptm_iri
+                )))?;
+
+        let mut ptm_sm_info = ptm.subject_map.tm_info.clone();
+        let mut joined_plan: Plan<Processed>;
+        let mut joined_fragment = None;
+
+        //Preparing plan for add join operator
+        let ptm_variable = variable_map.get(&ptm.identifier).unwrap();
+        let ptm_alias =
+            format!("join_{}", &ptm_variable[ptm_variable.len() - 1..]);
+        let aliased_plan =
+            || join(Rc::clone(plan), Rc::clone(other_plan))?.alias(&ptm_alias);
+
+        //Check for appropriate join type and add them to the plan
+        let key_only_attributes = key_only_parent_attributes(om, ptm);
+        if is_self_join(tm, om, ptm) {
+            // The parent IRI is computed from the child's own
+            // attributes, the child branches off in a fragment of its own
+            let mut plan = plan.borrow_mut();
+            let split_plan = plan.split_fragment(&ptm_alias)?;
+            *plan = split_plan.clone();
+            joined_plan = split_plan;
+            joined_fragment = Some(ptm_alias.as_str());
+        } else if let (Some(join_cond), Some(renames), JoinType::InnerJoin) =
+            (join_cond_opt, &key_only_attributes, &join_type)
+        {
+            // The parent IRI is computed from the child attributes of
+            // the join condition, the parent is only needed to check
+            // that a matching parent exists
+            joined_plan = aliased_plan()?
+                .join_type(JoinType::SemiJoin)
+                .where_by(join_cond.child_attributes.clone())?
+                .compared_to(join_cond.parent_attributes.clone())?;
+            ptm_sm_info.rename_attributes(renames);
+        } else {
+            joined_plan = match join_cond_opt {
+                Some(join_cond) => {
+                    aliased_plan()?
+                        .join_type(join_type.clone())
+                        .where_by(join_cond.child_attributes.clone())?
+                        .compared_to(join_cond.parent_attributes.clone())?
+                }
+                None => aliased_plan()?.cross_join()?,
+            };
 
+            // Prefix the attributes in the subject map with the alias of
+            // the PTM
             ptm_sm_info.prefix_attributes(&ptm_alias);
+        }
 
-            // Pair the ptm subject iri function with an extended attribute
-            let (_, ptm_sub_function) =
-                extract_extend_function_from_term_map_info(
-                    variable_map,
-                    &ptm_sm_info,
-                );
+        // Pair the ptm subject iri function with an extended attribute
+        let (_, ptm_sub_function) = extract_extend_function_from_term_map_info(
+            variable_map,
+            &ptm_sm_info,
+            search_map.base_iri,
+        );
+        let mut extend_pairs = translate_extend_pairs(
+            variable_map,
+            sm,
+            &joined_poms,
+            search_map.base_iri,
+        );
+        for om in joined_poms.iter().flat_map(|pom| &pom.object_maps) {
             let om_extend_attr =
                 variable_map.get(&om.tm_info.identifier).unwrap().clone();
+            extend_pairs.insert(om_extend_attr, ptm_sub_function.clone());
+        }
 
-            let pom_with_joined_ptm = vec![PredicateObjectMap {
-                predicate_maps: pms.clone(),
-                object_maps:    [om.clone()].to_vec(),
-                graph_maps:     pom.graph_maps.clone(),
-            }];
-
-            let mut extend_pairs =
-                translate_extend_pairs(variable_map, sm, &pom_with_joined_ptm);
-
-            extend_pairs.insert(om_extend_attr, ptm_sub_function);
-
-            let extend_op = Operator::ExtendOp {
-                config: Extend { extend_pairs },
-            };
-            let mut extended_plan = joined_plan.apply(&extend_op, "Extend")?;
+        let extend_op = Operator::ExtendOp {
+            config: Extend { extend_pairs },
+        };
+        let mut extended_plan = match joined_fragment {
+            Some(fragment) => {
+                joined_plan.branch(&extend_op, "Extend", fragment)?
+            }
+            None => joined_plan.apply(&extend_op, "Extend")?,
+        };
 
-            let lt_quads_map =
-                generate_lt_quads_from_spo(sm, &pom_with_joined_ptm);
+        let lt_quads_map = generate_lt_quads_from_spo(sm, &joined_poms);
 
-            for lt_id in lt_quads_map.keys() {
-                let quads = lt_quads_map.get(lt_id).unwrap();
-                let target = lt_target_map.get(lt_id).unwrap();
-                let serializer_op = translate_serializer_op(
-                    quads,
-                    &target.data_format,
-                    variable_map,
-                );
+        for lt_id in lt_quads_map.keys() {
+            let quads = lt_quads_map.get(lt_id).unwrap();
+            let target = lt_target_map.get(lt_id).unwrap();
+            let serializer_op = translate_serializer_op(
+                quads,
+                &target.data_format,
+                variable_map,
+            );
 
-                extended_plan.serialize(serializer_op)?.sink(target)?;
-            }
-            //.serialize(serializer_op)?;
-            //.sink(file_target(count));
+            extended_plan.serialize(serializer_op)?.sink(target)?;
         }
     }
 
     Ok(())
 }
+
+/// Returns whether the join with the parent triples map joins the child with
+/// itself. That's the case for a parent over the same logical source, if
+/// there isn't any join condition, or if the join condition compares the same
+/// attributes on both sides and the parent subject map only refers to those
+/// attributes. Otherwise, the join attributes may not be a key and a child
+/// can join with several parents.
+fn is_self_join(tm: &TriplesMap, om: &ObjectMap, ptm: &TriplesMap) -> bool {
+    tm.logical_source == ptm.logical_source
+        && om.join_condition.as_ref().is_none_or(|join_cond| {
+            join_cond.child_attributes == join_cond.parent_attributes
+                && key_only_parent_attributes(om, ptm).is_some()
+        })
+}
+
+/// Returns the child attributes of the join condition, keyed by their
+/// parent attributes, if the parent subject map only refers to parent
+/// attributes of the join condition.
+fn key_only_parent_attributes(
+    om: &ObjectMap,
+    ptm: &TriplesMap,
+) -> Option<HashMap<String, String>> {
+    let join_cond = om.join_condition.as_ref()?;
+    let child_attributes: HashMap<_, _> = join_cond
+        .parent_attributes
+        .iter()
+        .zip(&join_cond.child_attributes)
+        .collect();
+
+    ptm.subject_map
+        .tm_info
+        .get_attributes()
+        .into_iter()
+        .map(|attribute| {
+            let child_attribute = child_attributes.get(&attribute)?;
+            Some((attribute, child_attribute.to_string()))
+        })
+        .collect()
+}
+
 fn translate_source_op(tm: &TriplesMap) -> Source {
+    translate_shared_source_op(&[tm])
+}
+
+/// Translates the logical source shared by the triples maps, with the fields
+/// referred to by any of them.
+fn translate_shared_source_op(tms: &[&TriplesMap]) -> Source {
+    let logical_source = &tms[0].logical_source;
     let reference_formulation =
-        match tm.logical_source.reference_formulation.value().to_string() {
+        match logical_source.reference_formulation.value().to_string() {
             iri if iri == vocab::query::CLASS::JSONPATH.to_string() => {
                 ReferenceFormulation::JSONPath
             }
@@ -325,7 +509,10 @@ fn translate_source_op(tm: &TriplesMap) -> Source {
 
     let mut fields = Vec::new();
     if reference_formulation != ReferenceFormulation::CSVRows {
-        let references = extract_references_in_tm(tm);
+        let references: HashSet<_> = tms
+            .iter()
+            .flat_map(|tm| extract_references_in_tm(tm))
+            .collect();
 
         fields.extend(references.into_iter().map(|reference| {
             Field {
@@ -338,14 +525,14 @@ fn translate_source_op(tm: &TriplesMap) -> Source {
     }
 
     let root_iterator = Iterator {
-        reference: tm.logical_source.iterator.clone(),
+        reference: logical_source.iterator.clone(),
         reference_formulation,
         fields,
         alias: None, 
     };
 
-    let config = tm.logical_source.source.config.clone();
-    let source_type = match tm.logical_source.source.source_type {
+    let config = logical_source.source.config.clone();
+    let source_type = match logical_source.source.source_type {
         SourceType::CSVW => operator::IOType::File,
         SourceType::FileInput => operator::IOType::File,
     };
@@ -362,7 +549,10 @@ fn translate_projection_op<'a>(
     all_tms: impl std::iter::Iterator<Item = &'a TriplesMap>,
 ) -> Operator {
     let tm_identifier = &tm.identifier;
+    let all_tms: Vec<_> = all_tms.collect();
     let other_related_tms = all_tms
+        .iter()
+        .copied()
         .filter(|tm| &tm.identifier != tm_identifier && tm.contains_ptm());
     let jc_attributes =
         extract_ptm_conditions_attributes(other_related_tms, tm_identifier);
@@ -371,6 +561,23 @@ fn translate_projection_op<'a>(
 
     projection_attributes.extend(jc_attributes);
 
+    // The parent IRIs of the self joins are computed from the attributes of
+    // this triples map
+    let oms = tm.po_maps.iter().flat_map(|pom| &pom.object_maps);
+    for om in oms {
+        let Some(ptm_iri) = &om.parent_tm else {
+            continue;
+        };
+        let ptm_iri = ptm_iri.to_string();
+        if let Some(ptm) = all_tms.iter().find(|ptm| ptm.identifier == ptm_iri)
+        {
+            if is_self_join(tm, om, ptm) {
+                projection_attributes
+                    .extend(ptm.subject_map.tm_info.get_attributes());
+            }
+        }
+    }
+
     Operator::ProjectOp {
         config: Projection {
             projection_attributes,
@@ -432,7 +639,7 @@ mod tests {
     use std::borrow::Borrow;
     use std::collections::HashSet;
 
-    use rml_interpreter::extractors::io::parse_file;
+    use rml_interpreter::extractors::io::{parse_file, parse_str};
     use rml_interpreter::extractors::triplesmap_extractor::extract_triples_maps;
     use rml_interpreter::rml_model::term_map::{self, TermMapInfo};
     use sophia_term::Term;
@@ -498,6 +705,306 @@ mod tests {
         Ok(())
     }
 
+    fn translate_csv_test_case(
+        test_case: &str,
+    ) -> ExtractorResult<plangenerator::plan::Plan<plangenerator::plan::Init>>
+    {
+        let path = format!(
+            "{}/../resources/csv-testcases/{}/mapping.ttl",
+            env!("CARGO_MANIFEST_DIR"),
+            test_case
+        );
+        let document = parse_file(path.into())?;
+        Ok(OptimizedRMLDocumentTranslator::translate_to_plan(document).unwrap())
+    }
+
+    #[test]
+    fn test_shared_logical_source() -> ExtractorResult<()> {
+        // Both triples maps iterate over the rows of student_sport.csv
+        let plan = translate_csv_test_case("RMLTC0004a-CSV")?;
+
+        let graph = RefCell::borrow(&plan.graph);
+        let sources: Vec<_> = graph
+            .node_indices()
+            .filter(|idx| {
+                matches!(graph[*idx].operator, Operator::SourceOp { .. })
+            })
+            .collect();
+        assert_eq!(sources.len(), 1);
+
+        let fragmenter = graph.neighbors(sources[0]).next();
+        let branches: HashSet<_> = graph
+            .raw_edges()
+            .iter()
+            .filter(|edge| Some(edge.source()) == fragmenter)
+            .map(|edge| edge.weight.fragment.clone())
+            .collect();
+        assert_eq!(branches, new_hash_set(vec!["tm0", "tm1"]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_valued_reference_unnested() -> ExtractorResult<()> {
+        let mapping = "@prefix rr: <http://www.w3.org/ns/r2rml#> .
+            @prefix rml: <http://semweb.mmlab.be/ns/rml#> .
+            @prefix ql: <http://semweb.mmlab.be/ns/ql#> .
+            @prefix ex: <http://example.com/> .
+            @base <http://example.com/base/> .
+
+            <TriplesMap1>
+                rml:logicalSource [
+                    rml:source \"students.json\" ;
+                    rml:referenceFormulation ql:JSONPath ;
+                    rml:iterator \"$.students[*]\"
+                ] ;
+                rr:subjectMap [ rr:template \"ex:student_{ID}\" ] ;
+                rr:predicateObjectMap [
+                    rr:predicate ex:practises ;
+                    rr:objectMap [ rml:reference \"sports[*]\" ]
+                ] .";
+        let document = parse_str(mapping)?;
+        let plan = OptimizedRMLDocumentTranslator::translate_to_plan(document)
+            .unwrap();
+
+        // Only the multi-valued reference is unnested, between the source
+        // and the projection
+        let graph = RefCell::borrow(&plan.graph);
+        let mut node = graph.node_indices().find(|idx| {
+            matches!(graph[*idx].operator, Operator::SourceOp { .. })
+        });
+        let mut unnest_attributes = Vec::new();
+        while let Some(node_idx) = node {
+            match &graph[node_idx].operator {
+                Operator::SourceOp { .. } => {}
+                Operator::UnnestOp { config } => {
+                    unnest_attributes.push(config.attribute.clone())
+                }
+                Operator::ProjectOp { .. } => break,
+                other => {
+                    panic!(
+                        "Unexpected operator before the projection: {:?}",
+                        other
+                    )
+                }
+            }
+            node = graph.neighbors(node_idx).next();
+        }
+        assert_eq!(unnest_attributes, vec!["sports[*]".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_elimination() -> ExtractorResult<()> {
+        let join_types = |test_case| -> ExtractorResult<Vec<JoinType>> {
+            let plan = translate_csv_test_case(test_case)?;
+            let graph = RefCell::borrow(&plan.graph);
+            Ok(graph
+                .node_weights()
+                .filter_map(|node| {
+                    match &node.operator {
+                        Operator::JoinOp { config } => {
+                            Some(config.join_type.clone())
+                        }
+                        _ => None,
+                    }
+                })
+                .collect())
+        };
+
+        // A self join without join condition
+        assert_eq!(join_types("RMLTC0008b-CSV")?, vec![]);
+        // The parent subject only refers to the parent join attribute
+        assert_eq!(join_types("RMLTC0009a-CSV")?, vec![JoinType::SemiJoin]);
+        // The self join branches off the child through a fragmenter
+        translate_csv_test_case("RMLTC0008b-CSV")?
+            .validate()
+            .unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn test_self_join_on_non_key() -> ExtractorResult<()> {
+        let mapping = |subject_template| {
+            format!(
+                "@prefix rr: <http://www.w3.org/ns/r2rml#> .
+                @prefix rml: <http://semweb.mmlab.be/ns/rml#> .
+                @prefix ql: <http://semweb.mmlab.be/ns/ql#> .
+                @prefix ex: <http://example.com/> .
+                @base <http://example.com/base/> .
+
+                <TriplesMap1>
+                    rml:logicalSource [
+                        rml:source \"employee.csv\" ;
+                        rml:referenceFormulation ql:CSV
+                    ] ;
+                    rr:subjectMap [ rr:template \"{}\" ] ;
+                    rr:predicateObjectMap [
+                        rr:predicate ex:colleague ;
+                        rr:objectMap [
+                            rr:parentTriplesMap <TriplesMap1> ;
+                            rr:joinCondition [
+                                rr:child \"dept\" ; rr:parent \"dept\"
+                            ]
+                        ]
+                    ] .",
+                subject_template
+            )
+        };
+        let join_types = |subject_template| -> ExtractorResult<Vec<JoinType>> {
+            let document = parse_str(&mapping(subject_template))?;
+            let plan =
+                OptimizedRMLDocumentTranslator::translate_to_plan(document)
+                    .unwrap();
+            let graph = RefCell::borrow(&plan.graph);
+            Ok(graph
+                .node_weights()
+                .filter_map(|node| {
+                    match &node.operator {
+                        Operator::JoinOp { config } => {
+                            Some(config.join_type.clone())
+                        }
+                        _ => None,
+                    }
+                })
+                .collect())
+        };
+
+        // Employees sharing a department join with each other, the join
+        // can't be eliminated since dept isn't a key
+        assert_eq!(
+            join_types("http://example.com/p/{id}")?,
+            vec![JoinType::InnerJoin]
+        );
+        // The parent subject is computed from the child's own dept
+        assert_eq!(join_types("http://example.com/d/{dept}")?, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared_join() -> ExtractorResult<()> {
+        let ref_pom = |predicate, child| {
+            format!(
+                "rr:predicateObjectMap [
+                    rr:predicate ex:{predicate} ;
+                    rr:objectMap [
+                        rr:parentTriplesMap <TriplesMap2> ;
+                        rr:joinCondition [
+                            rr:child \"{child}\" ; rr:parent \"ID\"
+                        ]
+                    ]
+                ] ;"
+            )
+        };
+        let mapping = format!(
+            "@prefix rr: <http://www.w3.org/ns/r2rml#> .
+            @prefix rml: <http://semweb.mmlab.be/ns/rml#> .
+            @prefix ql: <http://semweb.mmlab.be/ns/ql#> .
+            @prefix ex: <http://example.com/> .
+            @base <http://example.com/base/> .
+
+            <TriplesMap1>
+                rml:logicalSource [
+                    rml:source \"student.csv\" ;
+                    rml:referenceFormulation ql:CSV
+                ] ;
+                rr:subjectMap [ rr:template \"ex:student_{{ID}}\" ] ;
+                {}
+                {}
+                {}
+                rr:predicateObjectMap [
+                    rr:predicate ex:name ;
+                    rr:objectMap [ rml:reference \"Name\" ]
+                ] .
+
+            <TriplesMap2>
+                rml:logicalSource [
+                    rml:source \"sport.csv\" ;
+                    rml:referenceFormulation ql:CSV
+                ] ;
+                rr:subjectMap [ rr:template \"ex:sport_{{ID}}\" ] .",
+            ref_pom("practises", "Sport"),
+            ref_pom("likes", "Sport"),
+            ref_pom("watches", "WatchedSport"),
+        );
+        let document = parse_str(&mapping)?;
+        let plan = OptimizedRMLDocumentTranslator::translate_to_plan(document)
+            .unwrap();
+
+        // The object maps joining on Sport share one join
+        let graph = RefCell::borrow(&plan.graph);
+        let joins = graph
+            .node_weights()
+            .filter(|node| matches!(node.operator, Operator::JoinOp { .. }))
+            .count();
+        assert_eq!(joins, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reproducible_plan() -> ExtractorResult<()> {
+        let mapping = "@prefix rr: <http://www.w3.org/ns/r2rml#> .
+            @prefix rml: <http://semweb.mmlab.be/ns/rml#> .
+            @prefix ql: <http://semweb.mmlab.be/ns/ql#> .
+            @prefix ex: <http://example.com/> .
+            @base <http://example.com/base/> .
+
+            <TriplesMap1>
+                rml:logicalSource [
+                    rml:source \"student.csv\" ;
+                    rml:referenceFormulation ql:CSV
+                ] ;
+                rr:subjectMap [ rr:template \"ex:student_{ID}\" ] ;
+                rr:predicateObjectMap [
+                    rr:predicate ex:name ;
+                    rr:objectMap [ rml:reference \"Name\" ]
+                ] ;
+                rr:predicateObjectMap [
+                    rr:predicate ex:age, ex:years ;
+                    rr:objectMap [ rml:reference \"Age\" ]
+                ] ;
+                rr:predicateObjectMap [
+                    rr:predicate ex:status ;
+                    rr:object \"enrolled\"
+                ] ;
+                rr:predicateObjectMap [
+                    rr:predicate ex:practises ;
+                    rr:objectMap [
+                        rr:parentTriplesMap <TriplesMap2> ;
+                        rr:joinCondition [
+                            rr:child \"Sport\" ; rr:parent \"ID\"
+                        ]
+                    ]
+                ] .
+
+            <TriplesMap2>
+                rml:logicalSource [
+                    rml:source \"sport.csv\" ;
+                    rml:referenceFormulation ql:CSV
+                ] ;
+                rr:subjectMap [
+                    rr:template \"ex:sport_{ID}\" ;
+                    rr:class ex:Sport
+                ] ;
+                rr:predicateObjectMap [
+                    rr:predicate ex:label ;
+                    rr:objectMap [ rml:reference \"Name\" ]
+                ] .";
+
+        // Every parse fills new hash maps, which iterate in another order
+        let plan_json = || {
+            let document = parse_str(mapping).unwrap();
+            let plan =
+                OptimizedRMLDocumentTranslator::translate_to_plan(document)
+                    .unwrap();
+            serde_json::to_string(&plan.to_document().unwrap()).unwrap()
+        };
+        let expected = plan_json();
+        for _ in 0..5 {
+            assert_eq!(plan_json(), expected);
+        }
+        Ok(())
+    }
+
     fn new_term_value(value: String) -> Term<String> {
         Term::new_literal_dt_unchecked(value, Term::new_iri("string").unwrap())
     }
@@ -520,13 +1027,18 @@ mod tests {
         );
 
         let variable_map = &generate_variable_map(&Document {
-            triples_maps: triples_map_vec,
+            triples_maps:     triples_map_vec,
+            default_base_iri: None,
         });
         let mut tms = vec![&triples_map.subject_map.tm_info];
         let tms_poms = extract_tm_infos_from_poms(&triples_map.po_maps);
         tms.extend(tms_poms);
 
-        let extend_translator = ExtendTranslator { tms, variable_map };
+        let extend_translator = ExtendTranslator {
+            tms,
+            variable_map,
+            base_iri: None,
+        };
         let extend_op = extend_translator.translate();
         println!("{:#?}", extend_op);
         Ok(())
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use operator::{Extend, Function, Operator, RcExtendFunction};
+use operator::{Extend, Function, Operator, ArcExtendFunction};
 use rml_interpreter::rml_model::term_map::{
     SubjectMap, TermMapInfo, TermMapType,
 };
@@ -16,6 +16,7 @@ use crate::OperatorTranslator;
 pub struct ExtendTranslator<'a> {
     pub tms:          Vec<&'a TermMapInfo>,
     pub variable_map: &'a HashMap<String, String>,
+    pub base_iri:     Option<&'a str>,
 }
 
 impl<'a> OperatorTranslator<Operator> for ExtendTranslator<'a> {
@@ -26,6 +27,7 @@ impl<'a> OperatorTranslator<Operator> for ExtendTranslator<'a> {
                 extract_extend_function_from_term_map_info(
                     self.variable_map,
                     tm_info,
+                    self.base_iri,
                 );
             extend_pairs.insert(variable, function);
         }
@@ -39,8 +41,9 @@ impl<'a> OperatorTranslator<Operator> for ExtendTranslator<'a> {
 pub fn extract_extend_function_from_term_map_info(
     variable_map: &HashMap<String, String>,
     tm_info: &TermMapInfo,
+    base_iri: Option<&str>,
 ) -> (String, Function) {
-    let func = extract_function(tm_info);
+    let func = extract_function(tm_info, base_iri);
 
     (
         variable_map.get(&tm_info.identifier).unwrap().to_string(),
@@ -48,9 +51,9 @@ pub fn extract_extend_function_from_term_map_info(
     )
 }
 
-fn extract_function(tm_info: &TermMapInfo) -> Function {
+fn extract_function(tm_info: &TermMapInfo, base_iri: Option<&str>) -> Function {
     let term_value = tm_info.term_value.value().to_string();
-    let value_function: RcExtendFunction = match tm_info.term_map_type {
+    let value_function: ArcExtendFunction = match tm_info.term_map_type {
         TermMapType::Constant => {
             Function::Constant {
                 value: term_value.clone(),
@@ -73,7 +76,10 @@ fn extract_function(tm_info: &TermMapInfo) -> Function {
                 .param_om_pairs
                 .iter()
                 .map(|(param, om)| {
-                    (param.to_string(), extract_function(&om.tm_info).into())
+                    (
+                        param.to_string(),
+                        extract_function(&om.tm_info, base_iri).into(),
+                    )
                 })
                 .collect();
 
@@ -92,6 +98,7 @@ fn extract_function(tm_info: &TermMapInfo) -> Function {
                     inner_function: value_function,
                 }
                 .into(),
+                base_iri:       base_iri.map(ToString::to_string),
             }
         }
         sophia_api::term::TermKind::Literal => {
@@ -114,6 +121,7 @@ pub fn translate_extend_pairs(
     variable_map: &HashMap<String, String>,
     sm: &SubjectMap,
     poms: &[PredicateObjectMap],
+    base_iri: Option<&str>,
 ) -> HashMap<String, Function> {
     let mut tm_infos = extract_tm_infos_from_poms(poms);
     tm_infos.push(&sm.tm_info);
@@ -122,7 +130,11 @@ pub fn translate_extend_pairs(
     tm_infos
         .into_iter()
         .map(|tm_info| {
-            extract_extend_function_from_term_map_info(variable_map, tm_info)
+            extract_extend_function_from_term_map_info(
+                variable_map,
+                tm_info,
+                base_iri,
+            )
         })
         .collect()
 }
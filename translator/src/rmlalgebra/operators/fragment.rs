@@ -21,7 +21,8 @@ fn translate_fragment_op_from_lts_str(
 ) -> Option<Fragmenter> {
     let target_lt_ids = lt_quads_map.keys();
 
-    let to: Vec<String> = target_lt_ids.cloned().collect();
+    let mut to: Vec<String> = target_lt_ids.cloned().collect();
+    to.sort();
 
     if to.len() == 1 && to.first() == Some(&from_fragment.to_string()) {
         return None;
@@ -6,6 +6,7 @@ use std::collections::{HashMap, HashSet};
 
 use log::debug;
 use operator::formats::DataFormat;
+use operator::template::QuadPattern;
 use operator::Serializer;
 
 use self::nquads::NQuadsSerializer;
@@ -17,17 +18,17 @@ trait SerializeTranslator {
     fn generate_template(
         quads: &HashSet<Quad>,
         variable_map: &HashMap<String, String>,
-    ) -> HashSet<String>;
+    ) -> HashSet<QuadPattern>;
     fn translate(
         quads: &HashSet<Quad>,
         variable_map: &HashMap<String, String>,
     ) -> Serializer{
         let template_set = Self::generate_template(quads, variable_map);
         let mut template_vec = template_set.into_iter().collect::<Vec<_>>();
-        template_vec.sort();
+        template_vec.sort_by_cached_key(|pattern| pattern.to_string());
 
         Serializer{
-            template: template_vec.join("\n"),
+            template: template_vec,
             //TODO: Check for serializer depdendent options configuration
             options: None,
             format: Self::data_format(),
@@ -2,10 +2,11 @@ use std::collections::HashSet;
 
 use log::debug;
 use operator::formats::DataFormat;
+use operator::template::{QuadPattern, TermPattern};
 use vocab::ToString as _;
 
 use super::SerializeTranslator;
-use crate::rmlalgebra::operators::serializer::util::get_triples_strings;
+use crate::rmlalgebra::operators::serializer::util::get_triple_patterns;
 use crate::rmlalgebra::types::Quad;
 
 #[derive(Debug, Clone)]
@@ -19,19 +20,20 @@ impl SerializeTranslator for NQuadsSerializer {
     fn generate_template(
         quads: &HashSet<Quad>,
         variable_map: &std::collections::HashMap<String, String>,
-    ) -> HashSet<String> {
-        let mut quad_strings: HashSet<String> = HashSet::new();
+    ) -> HashSet<QuadPattern> {
+        let mut quad_patterns: HashSet<QuadPattern> = HashSet::new();
         for quad in quads {
-            let mut unterminated_triples =
-                get_triples_strings(quad, variable_map).into_iter();
+            let mut triple_patterns =
+                get_triple_patterns(quad, variable_map).into_iter();
 
             // TODO: Properly handle overlapping graph maps definition when generating quad
             // patterns  <11-06-24, Min Oo> //
             if quad.gm_opt.is_none() {
-                quad_strings
-                    .extend(unterminated_triples.map(|trip| trip + " ."));
+                quad_patterns.extend(triple_patterns);
             } else if let Some(gm) = quad.gm_opt {
                 let gm_var = variable_map.get(&gm.tm_info.identifier).unwrap();
+                let gm_var =
+                    TermPattern::variable(gm_var.trim_start_matches('?'));
                 if quad
                     .triple
                     .sm
@@ -42,23 +44,25 @@ impl SerializeTranslator for NQuadsSerializer {
                     .count()
                     == 0
                 {
-                    unterminated_triples = unterminated_triples
-                        .filter(|trip| {
-                            !trip.contains(&format!(
-                                "<{}>",
-                                vocab::rdf::PROPERTY::TYPE.to_string()
-                            ))
-                        })
+                    let rdf_type = TermPattern::iri(
+                        &vocab::rdf::PROPERTY::TYPE.to_string(),
+                    );
+                    triple_patterns = triple_patterns
+                        .filter(|trip| trip.predicate != rdf_type)
                         .collect::<Vec<_>>()
                         .into_iter();
                 }
 
-                let quads_with_gm = unterminated_triples
-                    .map(|trip| format!("{} {} .", trip, gm_var));
-                quad_strings.extend(quads_with_gm);
+                let quads_with_gm = triple_patterns.map(|trip| {
+                    QuadPattern {
+                        graph: Some(gm_var.clone()),
+                        ..trip
+                    }
+                });
+                quad_patterns.extend(quads_with_gm);
             }
         }
-        debug!("{:#?}", quad_strings);
-        quad_strings
+        debug!("{:#?}", quad_patterns);
+        quad_patterns
     }
 }
@@ -1,8 +1,9 @@
 use std::collections::{HashMap, HashSet};
 
 use operator::formats::DataFormat;
+use operator::template::QuadPattern;
 
-use super::util::get_triples_strings;
+use super::util::get_triple_patterns;
 use super::SerializeTranslator;
 use crate::rmlalgebra::types::Quad;
 
@@ -13,17 +14,12 @@ impl SerializeTranslator for NTriplesSerializer {
     fn generate_template(
         quads: &HashSet<Quad>,
         variable_map: &HashMap<String, String>,
-    ) -> HashSet<String> {
-        let mut triples_strings: HashSet<String> = HashSet::new();
+    ) -> HashSet<QuadPattern> {
+        let mut triple_patterns: HashSet<QuadPattern> = HashSet::new();
         for quad in quads {
-            let terminated_triples =
-                get_triples_strings(quad, variable_map)
-                    .into_iter()
-                    .map(|str| format!("{} .", str));
-
-            triples_strings.extend(terminated_triples);
+            triple_patterns.extend(get_triple_patterns(quad, variable_map));
         }
-        triples_strings
+        triple_patterns
     }
 
     fn data_format() -> DataFormat {
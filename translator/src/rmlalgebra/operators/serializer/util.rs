@@ -1,64 +1,61 @@
 use std::collections::HashMap;
 
+use operator::template::{LiteralAnnotation, QuadPattern, TermPattern};
+use sophia_api::term::TTerm;
 use vocab::ToString;
 
 use crate::rmlalgebra::types::Quad;
 
-pub fn get_triples_strings(
+/// Variables are mapped with their leading `?`, which is not part of the
+/// variable name of the pattern.
+fn variable(
+    variable_map: &HashMap<String, String>,
+    identifier: &str,
+) -> TermPattern {
+    let var = variable_map.get(identifier).unwrap();
+    TermPattern::variable(var.trim_start_matches('?'))
+}
+
+pub fn get_triple_patterns(
     quad: &Quad<'_>,
     variable_map: &HashMap<String, String>,
-) -> Vec<String> {
-    let mut result: Vec<String> = vec![];
+) -> Vec<QuadPattern> {
+    let mut result: Vec<QuadPattern> = vec![];
     let triple = &quad.triple;
 
     let sm = triple.sm;
-    let sm_var = variable_map.get(&sm.tm_info.identifier).unwrap();
+    let sm_var = variable(variable_map, &sm.tm_info.identifier);
 
-    let cls_templates = sm.classes.iter().map(|cls| {
-        format!(
-            "{} <{}> {}",
-            sm_var,
-            vocab::rdf::PROPERTY::TYPE.to_string(),
-            cls
+    let cls_patterns = sm.classes.iter().map(|cls| {
+        QuadPattern::triple(
+            sm_var.clone(),
+            TermPattern::iri(&vocab::rdf::PROPERTY::TYPE.to_string()),
+            TermPattern::iri(&cls.value()),
         )
     });
-    result.extend(cls_templates);
-
-    let pm_var = variable_map.get(&triple.pm.tm_info.identifier).unwrap();
-    let om_var = variable_map.get(&triple.om.tm_info.identifier).unwrap();
-    let pm_om_string = format!("{} {}", pm_var, om_var);
-
-    let p_o_string = if let Some(lang) = &triple.om.language {
-        format!("{}@{}", pm_om_string, lang)
-    } else if let Some(dtype) = &triple.om.data_type {
-        format!("{}^^{}", pm_om_string, dtype)
-    } else {
-        pm_om_string
+    result.extend(cls_patterns);
+
+    let pm_var = variable(variable_map, &triple.pm.tm_info.identifier);
+    let om_name = variable_map
+        .get(&triple.om.tm_info.identifier)
+        .unwrap()
+        .trim_start_matches('?')
+        .to_string();
+
+    let annotation =
+        if let Some(lang) = &triple.om.language {
+            Some(LiteralAnnotation::Language(lang.clone()))
+        } else {
+            triple.om.data_type.as_ref().map(|dtype| {
+                LiteralAnnotation::Datatype(dtype.value().to_string())
+            })
+        };
+    let om_var = TermPattern::Variable {
+        name: om_name,
+        annotation,
     };
-    let s_p_o = format!("{} {}", sm_var, p_o_string);
-
-    result.push(s_p_o);
-
-    //for pom in &triple.poms {
-    //    let p_os = pom.pm.iter().flat_map(|pm| {
-    //        let pm_var = variable_map.get(&pm.tm_info.identifier).unwrap();
-
-    //        pom.om.iter().map(move |om| {
-    //            let om_var = variable_map.get(&om.tm_info.identifier).unwrap();
-    //            let pm_om_string = format!("{} {}", pm_var, om_var);
-    //            if let Some(lang) = &om.language {
-    //                format!("{}@{}", pm_om_string, lang)
-    //            } else if let Some(dtype) = &om.data_type {
-    //                format!("{}^^{}", pm_om_string, dtype)
-    //            } else {
-    //                pm_om_string
-    //            }
-    //        })
-    //    });
 
-    //    let s_p_os = p_os.map(|p_o| format!("{} {}", sm_var, p_o));
-    //    result.extend(s_p_os);
-    //}
+    result.push(QuadPattern::triple(sm_var, pm_var, om_var));
 
     result
 }
@@ -99,4 +99,5 @@ pub struct SearchMap<'a> {
     pub variable_map:      HashMap<String, String>,
     pub target_map:        HashMap<String, Target>,
     pub lt_id_quad_map:    HashMap<String, HashSet<Quad<'a>>>,
+    pub base_iri:          Option<&'a str>,
 }
@@ -1,9 +1,10 @@
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::rc::Rc;
 
-use log::{debug, error, trace};
-use operator::{Extend, Function, Rename, Serializer, Target};
+use log::{debug, trace};
+use operator::template::{QuadPattern, TermPattern};
+use operator::{Extend, Function, Rename, Serializer, Target, Unnest};
 use plangenerator::error::PlanError;
 use plangenerator::plan::{Plan, Processed, RcRefCellPlan, Serialized, Sunk};
 use shexml_interpreter::{
@@ -40,17 +41,31 @@ impl LanguageTranslator<ShExMLDocument> for ShExMLTranslator {
         };
 
         debug!("Translating all source operators");
-        let scidentkey_sourcedplan_exprident_pairval_map: HashMap<
+        // The sources and graph shapes are ordered by their identifiers, so
+        // that the plan is generated in the same order on every run
+        let scidentkey_sourcedplan_exprident_pairval_map: BTreeMap<
             String,
             (RcRefCellPlan<Processed>, Vec<String>),
         > = source_translator
             .translate()?
             .into_iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
             .map(|(key, value)| {
-                (key, (Rc::new(RefCell::new(plan.source(value.0))), value.1))
+                // Nested iterators are unnested right after the source so
+                // that their fields can be referred to as single values
+                let unnest_attributes =
+                    value.0.root_iterator.unnest_attributes();
+                let mut sourced_plan = plan.source(value.0);
+                for attribute in unnest_attributes {
+                    sourced_plan = sourced_plan.unnest(Unnest { attribute })?;
+                }
+                Ok((key, (Rc::new(RefCell::new(sourced_plan)), value.1)))
             })
-            .collect();
+            .collect::<Result<_, PlanError>>()?;
 
+        let graph_shapes: BTreeMap<_, _> =
+            indexed_document.graph_shapes.iter().collect();
         for (source_iter_ident, (sourced_plan, expr_idents)) in
             scidentkey_sourcedplan_exprident_pairval_map.iter()
         {
@@ -64,7 +79,7 @@ impl LanguageTranslator<ShExMLDocument> for ShExMLTranslator {
             debug!("Generating quads from same source");
             let filtered_same_source_quads = get_quads_from_same_source(
                 &indexed_document,
-                indexed_document.graph_shapes.values(),
+                graph_shapes.values().copied(),
                 expr_idents_hashset,
             );
 
@@ -127,7 +142,7 @@ fn add_rename_extend_op_from_quads(
 ) -> Result<Plan<Processed>, PlanError> {
     let mut expression_extend_func_pairs: Vec<(String, Function)> = Vec::new();
     let expression_stmts_map = &doc.expression_stmts;
-    let mut expr_ident_set = HashSet::new();
+    let mut expr_ident_set = BTreeSet::new();
     let mut rename_pairs = HashMap::new();
     for (subj, _, obj, _) in quads {
         expr_ident_set.extend(subj.expression.extract_expr_idents());
@@ -238,10 +253,11 @@ fn add_rename_extend_op_from_quads(
                 } else {
                     Function::Iri {
                         inner_function: subj_term_func.into(),
+                        base_iri:       None,
                     }
                 };
 
-            for (obj, graph_shape_ident) in obj_graph_pairs.iter() {
+            for (obj, _) in obj_graph_pairs.iter() {
                 let subj_variable =
                     variablized_terms.subject_variable_index.get(subj).unwrap();
 
@@ -324,28 +340,32 @@ fn add_serializer_op_from_quads(
             doc.prefixes.get(&pred.prefix.to_string())
         {
             let pred_prefix_uri = &pred_prefix_value.uri;
-            let graph_value = if graph.local == *"" {
-                "".to_string()
+            let graph_term = if graph.local == *"" {
+                None
             } else {
                 let graph_prefix_uri =
                     &doc.prefixes.get(&graph.prefix.to_string()).unwrap().uri;
-                format!("<{}{}>", graph_prefix_uri, graph.local)
+                Some(TermPattern::iri(&format!(
+                    "{}{}",
+                    graph_prefix_uri, graph.local
+                )))
             };
 
-            let single_bgp = format!(
-                "?{} <{}{}> ?{} {}.",
-                subj_variable,
-                pred_prefix_uri,
-                pred.local,
-                obj_variable,
-                graph_value
-            );
+            let single_bgp = QuadPattern {
+                subject:   TermPattern::variable(subj_variable),
+                predicate: TermPattern::iri(&format!(
+                    "{}{}",
+                    pred_prefix_uri, pred.local
+                )),
+                object:    TermPattern::variable(obj_variable),
+                graph:     graph_term,
+            };
 
             bgp_patterns.push(single_bgp);
         };
     }
     let serializer = Serializer {
-        template: bgp_patterns.join("\n"),
+        template: bgp_patterns,
         options:  None,
         format:   operator::formats::DataFormat::NQuads,
     };
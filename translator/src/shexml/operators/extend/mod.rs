@@ -1,5 +1,5 @@
 use std::collections::{HashMap, HashSet};
-use std::rc::Rc;
+use std::sync::Arc;
 
 use either::Either;
 use log::{trace, warn};
@@ -135,9 +135,9 @@ fn create_concat_function(
     right_field: String,
     concate_string: &str,
 ) -> Function {
-    let left_value = Rc::new(Function::Reference { value: left_field });
+    let left_value = Arc::new(Function::Reference { value: left_field });
 
-    let right_value = Rc::new(Function::Reference { value: right_field });
+    let right_value = Arc::new(Function::Reference { value: right_field });
 
     Function::Concatenate {
         left_value,
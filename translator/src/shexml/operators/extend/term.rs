@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use log::warn;
 use operator::Function;
@@ -22,6 +22,7 @@ pub fn obj_lang_datatype_function(
         } else {
             Some(Function::Iri {
                 inner_function: obj_inner_function.into(),
+                base_iri:       None,
             })
         }
     } else {
@@ -63,7 +64,14 @@ pub fn rdf_term_function(
 
             doc.matchers.get(matcher_ident).map(|matcher| {
                 Function::Replace {
-                    replace_map:    matcher.rename_map.clone(),
+                    replace_map:    matcher
+                        .rename_map
+                        .iter()
+                        .map(|(replacement, matches)| {
+                            let matches = matches.iter().cloned().collect();
+                            (replacement.clone(), matches)
+                        })
+                        .collect(),
                     inner_function: ref_func.into(),
                 }
             })
@@ -101,7 +109,7 @@ pub fn rdf_term_function(
                     template,
                     variable_function_pairs: vec![(
                         "func_value".to_string(),
-                        Rc::new(new_func),
+                        Arc::new(new_func),
                     )],
                 });
             } else if prefix_ns == &PrefixNameSpace::BNodePrefix {
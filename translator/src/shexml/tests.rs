@@ -1,4 +1,4 @@
-
+use operator::Operator;
 use plangenerator::error::PlanError;
 
 use super::*;
@@ -13,3 +13,38 @@ fn translate_to_plan_test() -> Result<(), PlanError> {
     ShExMLTranslator::translate_to_plan(shexml_document)?;
     Ok(())
 }
+
+#[test]
+fn test_nested_iterators_unnested() -> Result<(), PlanError> {
+    let input_shexml =
+        test_case!("shexml/unorganized/DeepHierarchyJSONTest.shexml");
+    let shexml_document = shexml_interpreter::parse_file(input_shexml).unwrap();
+    let plan = ShExMLTranslator::translate_to_plan(shexml_document)?;
+
+    // The nested iterators are unnested from the outermost one inwards,
+    // between the source and the rename of the fields
+    let graph = RefCell::borrow(&plan.graph);
+    let mut node = graph
+        .node_indices()
+        .find(|idx| matches!(graph[*idx].operator, Operator::SourceOp { .. }));
+    let mut unnest_attributes = Vec::new();
+    while let Some(node_idx) = node {
+        match &graph[node_idx].operator {
+            Operator::SourceOp { .. } => {}
+            Operator::UnnestOp { config } => {
+                unnest_attributes.push(config.attribute.clone())
+            }
+            Operator::RenameOp { .. } => break,
+            other => {
+                panic!("Unexpected operator before the rename: {:?}", other)
+            }
+        }
+        node = graph.neighbors(node_idx).next();
+    }
+
+    let expected: Vec<_> = (1..=9)
+        .map(|depth| format!("first_tag{}", ".tag".repeat(depth)))
+        .collect();
+    assert_eq!(unnest_attributes, expected);
+    Ok(())
+}
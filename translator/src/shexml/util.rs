@@ -45,9 +45,11 @@ pub fn convert_graph_shape_to_quads(
     let mut result = Vec::new();
 
     for shape in &graph_shapes.shapes {
-        let quads = shape
-            .pred_obj_pairs
-            .iter()
+        // Ordered by predicate, since the pairs are kept in a hash map
+        let mut pred_obj_pairs: Vec<_> = shape.pred_obj_pairs.iter().collect();
+        pred_obj_pairs.sort_by_cached_key(|(pred, _)| pred.to_string());
+        let quads = pred_obj_pairs
+            .into_iter()
             .map(|(pred, obj)| (&shape.subject, pred, obj, graph_ident));
         result.extend(quads);
     }
@@ -0,0 +1,37 @@
+pub const PREFIX: &str = "grel";
+pub const IRI: &str = "http://users.ugent.be/~bjdmeest/function/grel.ttl#";
+
+pub mod FUNCTION {
+    use super::IRI;
+    use crate::PAIR;
+
+    pub const TO_UPPER_CASE: PAIR = (IRI, "toUpperCase");
+    pub const TO_LOWER_CASE: PAIR = (IRI, "toLowerCase");
+    pub const TO_TITLE_CASE: PAIR = (IRI, "toTitlecase");
+    pub const STRING_TRIM: PAIR = (IRI, "string_trim");
+    pub const STRING_LENGTH: PAIR = (IRI, "string_length");
+    pub const STRING_CONTAINS: PAIR = (IRI, "string_contains");
+    pub const STRING_STARTS_WITH: PAIR = (IRI, "string_startsWith");
+    pub const STRING_ENDS_WITH: PAIR = (IRI, "string_endsWith");
+    pub const STRING_REPLACE: PAIR = (IRI, "string_replace");
+    pub const STRING_SUBSTRING: PAIR = (IRI, "string_substring");
+    pub const STRING_SPLIT: PAIR = (IRI, "string_split");
+    pub const ARRAY_JOIN: PAIR = (IRI, "array_join");
+    pub const BOOLEAN_NOT: PAIR = (IRI, "boolean_not");
+}
+
+pub mod PARAMETER {
+    use super::IRI;
+    use crate::PAIR;
+
+    pub const VALUE: PAIR = (IRI, "valueParameter");
+    pub const VALUE_2: PAIR = (IRI, "valueParameter2");
+    pub const STRING_SUB: PAIR = (IRI, "string_sub");
+    pub const STRING_FIND: PAIR = (IRI, "p_string_find");
+    pub const STRING_REPLACE: PAIR = (IRI, "p_string_replace");
+    pub const STRING_SEP: PAIR = (IRI, "p_string_sep");
+    pub const INT_FROM: PAIR = (IRI, "p_int_i_from");
+    pub const INT_OPT_TO: PAIR = (IRI, "p_int_i_opt_to");
+    pub const ARRAY_A: PAIR = (IRI, "p_array_a");
+    pub const BOOL_B: PAIR = (IRI, "bool_b");
+}
@@ -0,0 +1,29 @@
+pub const PREFIX: &str = "idlab-fn";
+pub const IRI: &str = "https://w3id.org/imec/idlab/function#";
+
+pub mod FUNCTION {
+    use super::IRI;
+    use crate::PAIR;
+
+    pub const EQUAL: PAIR = (IRI, "equal");
+    pub const NOT_EQUAL: PAIR = (IRI, "notEqual");
+    pub const IS_NULL: PAIR = (IRI, "isNull");
+    pub const TRUE_CONDITION: PAIR = (IRI, "trueCondition");
+    pub const CONCAT: PAIR = (IRI, "concat");
+    pub const STRING_CONTAINS_OTHER_STRING: PAIR =
+        (IRI, "stringContainsOtherString");
+    pub const NORMALIZE_DATE: PAIR = (IRI, "normalizeDate");
+    pub const NORMALIZE_DATE_TIME: PAIR = (IRI, "normalizeDateTime");
+}
+
+pub mod PARAMETER {
+    use super::IRI;
+    use crate::PAIR;
+
+    pub const STR: PAIR = (IRI, "str");
+    pub const OTHER_STR: PAIR = (IRI, "otherStr");
+    pub const DELIMITER: PAIR = (IRI, "delimiter");
+    pub const STR_BOOLEAN: PAIR = (IRI, "strBoolean");
+    pub const STR_DATE: PAIR = (IRI, "strDate");
+    pub const PATTERN: PAIR = (IRI, "pattern");
+}
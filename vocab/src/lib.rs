@@ -1,8 +1,14 @@
+// The vocabulary terms are grouped in modules named after their kind, like
+// CLASS and PROPERTY
+#![allow(non_snake_case)]
+
 pub mod comp;
 pub mod csvw;
 pub mod fnml;
 pub mod fno;
 pub mod formats;
+pub mod grel;
+pub mod idlab_fn;
 pub mod query;
 pub mod r2rml;
 pub mod rdf;
@@ -14,4 +14,5 @@ pub mod CLASS {
 
     pub const RDF_OBJECT: PAIR = (IRI, "Object");
     pub const RDF_LIST: PAIR = (IRI, "List");
+    pub const RDF_LANG_STRING: PAIR = (IRI, "langString");
 }
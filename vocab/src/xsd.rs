@@ -14,5 +14,17 @@ pub mod TYPE {
     pub const XSD_BOOLEAN: PAIR = (IRI, "boolean");
     pub const XSD_DATETIME: PAIR = (IRI, "dateTime");
 
+    pub const XSD_DECIMAL: PAIR = (IRI, "decimal");
+    pub const XSD_FLOAT: PAIR = (IRI, "float");
+    pub const XSD_SHORT: PAIR = (IRI, "short"); // signed 16-bit integer
+    pub const XSD_BYTE: PAIR = (IRI, "byte"); // signed 8-bit integer
+    pub const XSD_NON_POSITIVE_INTEGER: PAIR = (IRI, "nonPositiveInteger");
+    pub const XSD_NEGATIVE_INTEGER: PAIR = (IRI, "negativeInteger");
+    pub const XSD_NON_NEGATIVE_INTEGER: PAIR = (IRI, "nonNegativeInteger");
+    pub const XSD_UNSIGNED_LONG: PAIR = (IRI, "unsignedLong");
+    pub const XSD_UNSIGNED_INT: PAIR = (IRI, "unsignedInt");
+    pub const XSD_UNSIGNED_SHORT: PAIR = (IRI, "unsignedShort");
+    pub const XSD_UNSIGNED_BYTE: PAIR = (IRI, "unsignedByte");
+
     pub const XSD_ANY: PAIR = (IRI, "any");
 }